@@ -0,0 +1,142 @@
+//! Milestones earned from lifetime stats. Once earned they stay earned, even
+//! if the stats behind them are reset, and each one unlocks extra options on
+//! the main menu.
+
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::RainbowSkin;
+use crate::mode::GameMode;
+use crate::stats::Stats;
+use crate::theme::{Theme, ThemeChoice};
+use crate::toast::{ToastEvent, ToastKind};
+
+const FEAST_FOOD: u32 = 250;
+const VETERAN_RUNS: u32 = 25;
+const HIGH_SCORE: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstWin,
+    Feast,
+    Veteran,
+    HighScore,
+}
+
+impl Achievement {
+    const ALL: [Self; 4] = [Self::FirstWin, Self::Feast, Self::Veteran, Self::HighScore];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FirstWin => "First Win",
+            Self::Feast => "Feast",
+            Self::Veteran => "Veteran",
+            Self::HighScore => "High Score",
+        }
+    }
+
+    /// What it takes to earn, phrased to finish "locked: ...".
+    pub fn condition(&self) -> String {
+        match self {
+            Self::FirstWin => "win a run".to_string(),
+            Self::Feast => format!("eat {} food", FEAST_FOOD),
+            Self::Veteran => format!("play {} runs", VETERAN_RUNS),
+            Self::HighScore => format!("score {} in one run", HIGH_SCORE),
+        }
+    }
+
+    fn is_earned(&self, stats: &Stats) -> bool {
+        match self {
+            Self::FirstWin => stats.wins > 0,
+            Self::Feast => stats.food_eaten >= FEAST_FOOD,
+            Self::Veteran => stats.runs_played >= VETERAN_RUNS,
+            Self::HighScore => stats.best_score >= HIGH_SCORE,
+        }
+    }
+}
+
+/// Something on the menu that has to be earned before it can be picked.
+pub trait Unlockable {
+    fn required(&self) -> Option<Achievement>;
+}
+
+impl Unlockable for RainbowSkin {
+    fn required(&self) -> Option<Achievement> {
+        Some(Achievement::FirstWin)
+    }
+}
+
+impl Unlockable for ThemeChoice {
+    /// Only pinning a holiday theme is gated, the calendar still brings them
+    /// round for everyone.
+    fn required(&self) -> Option<Achievement> {
+        match self {
+            Self::Auto | Self::Fixed(Theme::Classic) => None,
+            Self::Fixed(Theme::Halloween) => Some(Achievement::Feast),
+            Self::Fixed(Theme::Winter) => Some(Achievement::Veteran),
+        }
+    }
+}
+
+impl Unlockable for GameMode {
+    fn required(&self) -> Option<Achievement> {
+        match self {
+            Self::Classic
+            | Self::Sandbox
+            | Self::Puzzle
+            | Self::Mirror
+            | Self::Endless
+            | Self::Decay => None,
+            Self::Length => Some(Achievement::FirstWin),
+            Self::Walls => Some(Achievement::Feast),
+            Self::Maze => Some(Achievement::HighScore),
+        }
+    }
+}
+
+/// The achievements the active profile has earned.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    earned: BTreeSet<Achievement>,
+}
+
+impl Achievements {
+    pub fn unlocks(&self, item: &impl Unlockable) -> bool {
+        match item.required() {
+            Some(achievement) => self.earned.contains(&achievement),
+            None => true,
+        }
+    }
+}
+
+fn check_achievements(
+    stats: Res<Stats>,
+    mut achievements: ResMut<Achievements>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+
+    for achievement in Achievement::ALL {
+        if !achievements.earned.contains(&achievement) && achievement.is_earned(&stats) {
+            achievements.earned.insert(achievement);
+            toasts.send(ToastEvent::new(
+                ToastKind::Achievement,
+                format!("Achievement unlocked: {}", achievement.name()),
+            ));
+        }
+    }
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Achievements>()
+            .add_system(check_achievements);
+    }
+}
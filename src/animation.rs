@@ -0,0 +1,137 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::settings::{CosmeticSettings, Settings};
+use crate::timer::{Clock, TickTimer};
+use crate::{
+    size_scaling, AppState, Position, SnakeBody, SnakeHead, SnakePart, SNAKE_BODY_COLOR,
+    SNAKE_HEAD_COLOR,
+};
+
+const PULSE_PER_SECOND: f32 = 1.2;
+const RAINBOW_DEGREES_PER_SECOND: f32 = 90.0;
+/// How far apart in hue neighbouring segments are, so the colours flow
+/// down the body instead of the whole snake flashing at once.
+const RAINBOW_DEGREES_PER_SEGMENT: f32 = 15.0;
+/// How far regular food swells; anything worth more should pulse harder.
+pub const FOOD_PULSE: f32 = 0.12;
+/// Movement ticks a new segment takes to grow to full size.
+const GROW_IN_TICKS: f32 = 2.0;
+
+/// Gently scales an entity up and down on top of its grid size. The value is
+/// how far it swells, as a fraction of that size.
+#[derive(Component)]
+pub struct Pulse(pub f32);
+
+/// Runs after [`size_scaling`], which resets the scale every frame, so this
+/// only ever nudges the base size rather than compounding.
+fn pulse(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut query: Query<(&Pulse, &Position, &mut Transform)>,
+) {
+    if settings.accessibility.reduced_motion {
+        return;
+    }
+
+    let t = time.seconds_since_startup() as f32 * PULSE_PER_SECOND * TAU;
+
+    for (pulse, position, mut transform) in query.iter_mut() {
+        // Offset by cell so neighbouring food doesn't throb in lockstep.
+        let phase = (position.x + position.y) as f32 * 0.7;
+        let swell = 1.0 + pulse.0 * ((t + phase).sin() * 0.5 + 0.5);
+
+        transform.scale.x *= swell;
+        transform.scale.y *= swell;
+    }
+}
+
+/// Scales a new segment up from nothing, rather than it popping in at full
+/// size.
+#[derive(Component)]
+pub struct GrowIn;
+
+impl GrowIn {
+    /// Grows over a couple of ticks at the given seconds per tick. The timer
+    /// only counts while the game is being played, so a pause holds it part
+    /// way.
+    pub fn over_ticks(step: f64) -> (Self, TickTimer) {
+        (
+            Self,
+            TickTimer::new(step as f32 * GROW_IN_TICKS, Clock::Playing),
+        )
+    }
+}
+
+/// Like [`pulse`], scales on top of what [`size_scaling`] set.
+pub fn grow_in(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut query: Query<(Entity, &TickTimer, &mut Transform), With<GrowIn>>,
+) {
+    for (ent, timer, mut transform) in query.iter_mut() {
+        if timer.finished() || settings.accessibility.reduced_motion {
+            commands
+                .entity(ent)
+                .remove::<GrowIn>()
+                .remove::<TickTimer>();
+            continue;
+        }
+
+        let scale = timer.percent();
+        transform.scale.x *= scale;
+        transform.scale.y *= scale;
+    }
+}
+
+/// The rainbow snake, as something to unlock.
+pub struct RainbowSkin;
+
+fn rainbow_snake(
+    time: Res<Time>,
+    cosmetics: Res<CosmeticSettings>,
+    body: Res<SnakeBody>,
+    mut sprites: Query<&mut Sprite, With<SnakePart>>,
+) {
+    if !cosmetics.rainbow {
+        return;
+    }
+
+    let start = time.seconds_since_startup() as f32 * RAINBOW_DEGREES_PER_SECOND;
+
+    for (i, segment) in body.0.iter().enumerate() {
+        if let Ok(mut sprite) = sprites.get_mut(*segment) {
+            let hue = (start - i as f32 * RAINBOW_DEGREES_PER_SEGMENT).rem_euclid(360.0);
+            sprite.color = Color::hsl(hue, 0.8, 0.5);
+        }
+    }
+}
+
+/// Puts the usual colours back when the rainbow is switched off.
+fn reset_snake_colors(
+    cosmetics: Res<CosmeticSettings>,
+    mut sprites: Query<(&mut Sprite, Option<&SnakeHead>), With<SnakePart>>,
+) {
+    if !cosmetics.is_changed() || cosmetics.rainbow {
+        return;
+    }
+
+    for (mut sprite, head) in sprites.iter_mut() {
+        sprite.color = match head {
+            Some(_) => SNAKE_HEAD_COLOR,
+            None => SNAKE_BODY_COLOR,
+        };
+    }
+}
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, pulse.after(size_scaling))
+            .add_system_to_stage(CoreStage::PostUpdate, grow_in.after(size_scaling))
+            .add_system(reset_snake_colors)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(rainbow_snake));
+    }
+}
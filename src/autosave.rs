@@ -0,0 +1,87 @@
+//! Keeps the active profile on disk, so a crash or a force quit loses at most
+//! the last few seconds of settings and stats. Saves happen on a timer, on
+//! every state change and on exit.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::profile::{ProfileResources, Profiles};
+use crate::AppState;
+
+pub const SAVE_DIR: &str = "save";
+const AUTOSAVE_SECONDS: f32 = 30.0;
+
+struct AutosaveTimer(Timer);
+
+/// Writes to a temporary file first and renames it over the old one, so a
+/// crash mid-write leaves the previous save intact rather than a torn file.
+fn write_atomically(path: &Path, text: &str) -> io::Result<()> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, text)?;
+    fs::rename(temp, path)
+}
+
+/// Saves `value` as RON to `path`, relative to [`SAVE_DIR`].
+pub fn save_file<T: Serialize>(path: &Path, value: &T) {
+    let path = Path::new(SAVE_DIR).join(path);
+    let written = fs::create_dir_all(path.parent().unwrap())
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            ron::ser::to_string_pretty(value, PrettyConfig::new()).map_err(|e| e.to_string())
+        })
+        .and_then(|text| write_atomically(&path, &text).map_err(|e| e.to_string()));
+
+    if let Err(e) = written {
+        eprintln!("Failed to save {}: {}", path.display(), e);
+    }
+}
+
+/// Loads a file written by [`save_file`], if there is one and it's readable.
+pub fn load_file<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let path = Path::new(SAVE_DIR).join(path);
+    let text = fs::read_to_string(&path).ok()?;
+
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("Ignoring unreadable save {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn autosave(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    profiles: Res<Profiles>,
+    resources: ProfileResources,
+    mut timer: ResMut<AutosaveTimer>,
+    mut app_exit: EventReader<AppExit>,
+) {
+    let timer_done = timer.0.tick(time.delta()).just_finished();
+    let exiting = app_exit.iter().next().is_some();
+
+    if timer_done || exiting || app_state.is_changed() {
+        profiles.save(&resources.capture());
+    }
+
+    if exiting {
+        profiles.finish_sync();
+    }
+}
+
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AutosaveTimer(Timer::from_seconds(AUTOSAVE_SECONDS, true)))
+            .add_system_to_stage(CoreStage::Last, autosave);
+    }
+}
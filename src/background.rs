@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::chroma_key::StreamOverlay;
+use crate::mode::GameMode;
+use crate::settings::Settings;
+use crate::theme::ActiveTheme;
+use crate::{DevouredFood, RunKind, SnakeBody};
+
+const BG_FADE_PER_SECOND: f32 = 0.5;
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let [r1, g1, b1, a1] = from.as_rgba_f32();
+    let [r2, g2, b2, a2] = to.as_rgba_f32();
+
+    Color::rgba(
+        r1 + (r2 - r1) * t,
+        g1 + (g2 - g1) * t,
+        b1 + (b2 - b1) * t,
+        a1 + (a2 - a1) * t,
+    )
+}
+
+/// The palette color for `progress`, blending between the two nearest stops.
+fn palette_color(palette: &[Color], progress: f32) -> Color {
+    let scaled = progress * (palette.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(palette.len() - 2);
+
+    lerp_color(palette[index], palette[index + 1], scaled - index as f32)
+}
+
+/// Eases the background towards the palette color for the current progress,
+/// so each piece of food nudges the color rather than snapping it.
+#[allow(clippy::too_many_arguments)]
+fn shift_background(
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    run_kind: Res<RunKind>,
+    settings: Res<Settings>,
+    overlay: Res<StreamOverlay>,
+    theme: Res<ActiveTheme>,
+    body: Res<SnakeBody>,
+    devoured: Query<&DevouredFood>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    // A chroma key background has to stay the one solid color.
+    if overlay.key.is_some() {
+        return;
+    }
+
+    let devoured = devoured.iter().next().map_or(0, |count| count.0);
+    let progress = run_kind
+        .win_condition(*game_mode)
+        .map_or(0.0, |condition| condition.progress(devoured, body.0.len()));
+    let target = palette_color(&theme.0.palette(), progress);
+
+    if clear_color.0 == target {
+        return;
+    }
+
+    clear_color.0 = if settings.accessibility.reduced_motion {
+        target
+    } else {
+        let t = (BG_FADE_PER_SECOND * time.delta_seconds()).min(1.0);
+        lerp_color(clear_color.0, target, t)
+    };
+}
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(shift_background);
+    }
+}
@@ -0,0 +1,50 @@
+//! More than one board in the window at a time, side by side. Each board has
+//! a `WIN_WIDTH` by `WIN_HEIGHT` slot of the world to itself, the first one
+//! where a lone board would be and the rest carrying on to the right, so
+//! everything drawn for the first board, the HUD included, stays put however
+//! many there are. Anything on the grid belongs to the first board unless
+//! it's tagged with a [`BoardId`].
+
+use bevy::prelude::*;
+
+use crate::{WIN_HEIGHT, WIN_WIDTH};
+
+/// Which board an entity with a [`Position`](crate::Position) is on, counting
+/// from 0 on the left.
+#[derive(Component, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoardId(pub u8);
+
+impl BoardId {
+    /// The middle of this board's slot of the world.
+    pub fn origin(&self) -> Vec2 {
+        Vec2::new(self.0 as f32 * WIN_WIDTH, 0.0)
+    }
+}
+
+/// How many boards are up.
+pub struct BoardLayout {
+    pub boards: u8,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self { boards: 1 }
+    }
+}
+
+impl BoardLayout {
+    pub fn ids(&self) -> impl Iterator<Item = BoardId> {
+        (0..self.boards.max(1)).map(BoardId)
+    }
+
+    /// The bottom left and top right corners of all the boards' slots.
+    pub fn world_bounds(&self) -> (Vec2, Vec2) {
+        let min = Vec2::new(-WIN_WIDTH, -WIN_HEIGHT) / 2.0;
+        let max = Vec2::new(
+            min.x + WIN_WIDTH * self.boards.max(1) as f32,
+            WIN_HEIGHT / 2.0,
+        );
+
+        (min, max)
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::hud::{spawn_hud_bar, HudBar, HudBarFill};
+use crate::{AppState, TickRate};
+
+const STAMINA_MAX: f32 = 100.0;
+const STAMINA_DRAIN_PER_TICK: f32 = 2.5;
+const STAMINA_REGEN_PER_SECOND: f32 = 12.0;
+const STAMINA_RECOVERED: f32 = 25.0;
+const STAMINA_BAR: HudBar = HudBar {
+    x: 250.0,
+    y: 275.0,
+    width: 150.0,
+    height: 12.0,
+    color: Color::rgb(0.2, 0.5, 0.9),
+    low_color: Color::rgb(0.4, 0.4, 0.6),
+};
+
+/// Fuel for boosting. Running dry locks the boost out until enough has
+/// regenerated, so holding shift can't flicker between speeds.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Stamina {
+    level: f32,
+    exhausted: bool,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            level: STAMINA_MAX,
+            exhausted: false,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+struct StaminaBar;
+
+fn setup_stamina_bar(mut commands: Commands) {
+    spawn_hud_bar(&mut commands, STAMINA_BAR, StaminaBar);
+}
+
+fn boost_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    stamina: Res<Stamina>,
+    mut tick_rate: ResMut<TickRate>,
+) {
+    let boosted =
+        keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]) && !stamina.exhausted;
+
+    if tick_rate.boosted != boosted {
+        tick_rate.boosted = boosted;
+    }
+}
+
+pub fn stamina_drain(tick_rate: Res<TickRate>, mut stamina: ResMut<Stamina>) {
+    if !tick_rate.boosted {
+        return;
+    }
+
+    stamina.level -= STAMINA_DRAIN_PER_TICK;
+    if stamina.level <= 0.0 {
+        stamina.level = 0.0;
+        stamina.exhausted = true;
+    }
+}
+
+fn stamina_regen(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    tick_rate: Res<TickRate>,
+    mut stamina: ResMut<Stamina>,
+) {
+    if tick_rate.boosted
+        || *app_state.current() != AppState::Playing
+        || stamina.level >= STAMINA_MAX
+    {
+        return;
+    }
+
+    stamina.level =
+        (stamina.level + STAMINA_REGEN_PER_SECOND * time.delta_seconds()).min(STAMINA_MAX);
+    if stamina.exhausted && stamina.level >= STAMINA_RECOVERED {
+        stamina.exhausted = false;
+    }
+}
+
+fn update_stamina_bar(stamina: Res<Stamina>, mut fills: Query<&mut HudBarFill, With<StaminaBar>>) {
+    if !stamina.is_changed() {
+        return;
+    }
+
+    for mut fill in fills.iter_mut() {
+        fill.0 = stamina.level / STAMINA_MAX;
+    }
+}
+
+pub struct BoostPlugin;
+
+impl Plugin for BoostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Stamina>()
+            .add_startup_system(setup_stamina_bar)
+            .add_system(boost_input)
+            .add_system(stamina_regen)
+            .add_system(update_stamina_bar);
+    }
+}
@@ -0,0 +1,170 @@
+//! The boss on the campaign's boss stages: a big, slow chaser that ends the
+//! run on contact. Boxing it in so it can't move wins the stage, and so does
+//! lasting until the fight's timer runs out.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::campaign::is_boss_stage;
+use crate::cheats::Cheats;
+use crate::hud::{spawn_hud_bar, HudBar, HudBarFill};
+use crate::layers::Layer;
+use crate::obstacles::Wall;
+use crate::toast::ToastEvent;
+use crate::{
+    new_run, AppState, Direction, GameStatus, NewRunEvent, Position, RunKind, Size, SnakeHead,
+    SnakePart, GRID_HEIGHT, GRID_WIDTH,
+};
+
+const BOSS_COLOR: Color = Color::rgb(0.8, 0.15, 0.35);
+/// The boss covers this many cells either side of its centre.
+const BOSS_REACH: i32 = 1;
+const BOSS_SURVIVE_SECONDS: f32 = 45.0;
+const BOSS_BAR: HudBar = HudBar {
+    x: -250.0,
+    y: 275.0,
+    width: 150.0,
+    height: 12.0,
+    color: Color::rgb(0.8, 0.15, 0.35),
+    low_color: Color::rgb(0.9, 0.8, 0.2),
+};
+
+/// Moves on every other movement tick, like the hunter.
+#[derive(Component, Default)]
+pub struct Boss {
+    rested: bool,
+}
+
+/// Counts down the time left to outlast the boss.
+pub struct BossFight(Timer);
+
+/// The timer bar shown during a boss fight, cleared with the run.
+#[derive(Component, Clone)]
+pub struct BossBar;
+
+/// Every cell the boss covers when centred on `center`.
+fn footprint(center: Position) -> impl Iterator<Item = Position> {
+    (-BOSS_REACH..=BOSS_REACH).flat_map(move |dx| {
+        (-BOSS_REACH..=BOSS_REACH).map(move |dy| Position::new(center.x + dx, center.y + dy))
+    })
+}
+
+fn start_boss_fight(
+    mut commands: Commands,
+    run_kind: Res<RunKind>,
+    mut reader: EventReader<NewRunEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if reader.iter().next().is_none() {
+        return;
+    }
+
+    let stage = match *run_kind {
+        RunKind::Campaign(stage) if is_boss_stage(stage) => stage,
+        _ => return commands.remove_resource::<BossFight>(),
+    };
+
+    let size = (BOSS_REACH * 2 + 1) as f32;
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: BOSS_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Boss::default())
+        .insert(Position::new(GRID_WIDTH as i32 / 2, GRID_HEIGHT as i32 / 2))
+        .insert(Size::square(size - 0.2))
+        .insert(Layer::Entities.at(5.0));
+
+    spawn_hud_bar(&mut commands, BOSS_BAR, BossBar);
+    commands.insert_resource(BossFight(Timer::from_seconds(BOSS_SURVIVE_SECONDS, false)));
+
+    toasts.send(ToastEvent::info(format!(
+        "Stage {} boss: trap it or survive {} seconds",
+        stage, BOSS_SURVIVE_SECONDS
+    )));
+}
+
+fn end_fight(game_status: &mut Query<&mut GameStatus>, end: GameStatus) {
+    if let Some(mut status) = game_status.iter_mut().next() {
+        status.end(end);
+    }
+}
+
+/// Steps the boss towards the snake head along whichever free direction gets
+/// it closest. With nowhere left to go, it's trapped and the stage is won.
+pub fn boss_movement(
+    mut bosses: Query<(&mut Boss, &mut Position), Without<SnakePart>>,
+    heads: Query<&Position, (With<SnakeHead>, Without<Boss>)>,
+    parts: Query<&Position, (With<SnakePart>, Without<SnakeHead>)>,
+    walls: Query<&Position, (With<Wall>, Without<Boss>)>,
+    cheats: Res<Cheats>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    let blocked: HashSet<Position> = parts.iter().chain(walls.iter()).copied().collect();
+
+    for (mut boss, mut center) in bosses.iter_mut() {
+        let moves: Vec<Position> = [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+        .into_iter()
+        .map(|direction| center.step(direction))
+        .filter(|next| footprint(*next).all(|cell| !blocked.contains(&cell)))
+        .collect();
+
+        if moves.is_empty() {
+            end_fight(&mut game_status, GameStatus::Won);
+            return;
+        }
+
+        boss.rested = !boss.rested;
+        if !boss.rested {
+            let distance = |p: &Position| (p.x - head.x).abs() + (p.y - head.y).abs();
+            *center = moves.into_iter().min_by_key(distance).unwrap();
+        }
+
+        if footprint(*center).any(|cell| cell == head) && !cheats.invincible {
+            end_fight(&mut game_status, GameStatus::Lost);
+        }
+    }
+}
+
+fn boss_timer(
+    time: Res<Time>,
+    fight: Option<ResMut<BossFight>>,
+    mut bars: Query<&mut HudBarFill, With<BossBar>>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    let mut fight = match fight {
+        Some(fight) => fight,
+        None => return,
+    };
+
+    if fight.0.tick(time.delta()).just_finished() {
+        end_fight(&mut game_status, GameStatus::Won);
+    }
+
+    for mut fill in bars.iter_mut() {
+        fill.0 = 1.0 - fight.0.percent();
+    }
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(start_boss_fight.after(new_run))
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(boss_timer));
+    }
+}
@@ -0,0 +1,113 @@
+//! A run of stages played in order from the main menu. Clearing one unlocks
+//! the next, and every fifth stage is a boss fight (see [`crate::boss`]).
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::level::Level;
+use crate::maze::maze_layout;
+use crate::mode::WinCondition;
+use crate::obstacles::{walls_layout, Tile};
+use crate::toast::ToastEvent;
+use crate::{GameOverEvent, GameStatus, Position, RunKind, GRID_HEIGHT, GRID_WIDTH};
+
+pub const CAMPAIGN_STAGES: u32 = 10;
+const BOSS_EVERY: u32 = 5;
+const STAGE_FOOD: u32 = 20;
+
+pub fn is_boss_stage(stage: u32) -> bool {
+    matches!(stage % BOSS_EVERY, 0)
+}
+
+/// Regular stages are won on food. Boss stages have no score to reach, the
+/// boss fight decides them instead.
+pub fn win_condition(stage: u32) -> Option<WinCondition> {
+    if is_boss_stage(stage) {
+        None
+    } else {
+        Some(WinCondition::FoodDevoured(STAGE_FOOD))
+    }
+}
+
+/// A walled-in arena, so the boss can't slip away around the edges.
+fn arena_layout() -> Vec<(Position, Tile)> {
+    let (width, height) = (GRID_WIDTH as i32, GRID_HEIGHT as i32);
+
+    (0..width)
+        .flat_map(|x| [Position::new(x, 0), Position::new(x, height - 1)])
+        .chain((1..height - 1).flat_map(|y| [Position::new(0, y), Position::new(width - 1, y)]))
+        .map(|position| (position, Tile::Wall))
+        .collect()
+}
+
+/// The board for a stage. Mazes are seeded from the stage number, so a
+/// stage is the same every time it's played.
+pub fn stage_level(stage: u32) -> Level {
+    let tiles = match stage % BOSS_EVERY {
+        0 => arena_layout(),
+        1 => Vec::new(),
+        2 => walls_layout(),
+        _ => maze_layout(&mut StdRng::seed_from_u64(stage as u64)),
+    };
+
+    Level {
+        name: format!("Stage {}", stage),
+        tiles,
+        ..default()
+    }
+}
+
+/// How far the active profile has got.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Campaign {
+    /// The furthest stage unlocked, one past the last when it's all done.
+    pub reached: u32,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self { reached: 1 }
+    }
+}
+
+impl Campaign {
+    /// The furthest stage that can be played.
+    pub fn last_playable(&self) -> u32 {
+        self.reached.min(CAMPAIGN_STAGES)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.reached > CAMPAIGN_STAGES
+    }
+}
+
+fn record_stage(
+    run_kind: Res<RunKind>,
+    mut campaign: ResMut<Campaign>,
+    mut reader: EventReader<GameOverEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let event = match reader.iter().next() {
+        Some(event) => event,
+        None => return,
+    };
+
+    if let (RunKind::Campaign(stage), GameStatus::Won) = (*run_kind, &event.0) {
+        toasts.send(ToastEvent::info(format!("Stage {} cleared!", stage)));
+
+        if stage + 1 > campaign.reached {
+            campaign.reached = stage + 1;
+        }
+    }
+}
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Campaign>().add_system(record_stage);
+    }
+}
@@ -0,0 +1,237 @@
+//! Chain objectives: a level's `chain` is a run of numbered food that has to
+//! be eaten in order, 1 first. Each link eaten in turn is worth a point, and
+//! finishing the chain pays a bonus of one more point a link. Biting into
+//! the wrong number breaks the chain, clearing what's left of it and taking
+//! points off.
+
+use bevy::prelude::*;
+
+use crate::cleanup::DespawnOnGameOver;
+use crate::layers::Layer;
+use crate::level::ActiveLevel;
+use crate::scoring::{ScoreBreakdown, ScoreSource};
+use crate::toast::ToastEvent;
+use crate::{AppState, DevouredFood, NewRunEvent, Position, Size, SnakeHead};
+
+const LINK_COLOR: Color = Color::rgb(0.95, 0.55, 0.15);
+const LINK_NUMBER_COLOR: Color = Color::BLACK;
+const LINK_FONT_SIZE: f32 = 14.0;
+const LINK_POINTS: u32 = 1;
+const BREAK_PENALTY: u32 = 3;
+const CHAIN_X: f32 = 250.0;
+const CHAIN_Y: f32 = 228.0;
+const CHAIN_FONT_SIZE: f32 = 20.0;
+const BROKEN_COLOR: Color = Color::rgb(0.9, 0.4, 0.4);
+const DONE_COLOR: Color = Color::rgb(0.4, 0.9, 0.4);
+
+/// A piece of chain food, numbered from 0 though shown from 1.
+#[derive(Component, Clone, Copy)]
+pub struct ChainLink(pub usize);
+
+/// The number drawn over a [`ChainLink`], despawned along with it.
+#[derive(Component)]
+pub struct LinkNumber(Entity);
+
+/// How the current run's chain is going.
+#[derive(Default)]
+pub struct ChainObjective {
+    eaten: usize,
+    broken: bool,
+}
+
+#[derive(Component)]
+struct ChainText;
+
+/// Spawns the links of a level's chain, in order.
+pub fn spawn_chain(commands: &mut Commands, asset_server: &AssetServer, chain: &[Position]) {
+    for (index, position) in chain.iter().enumerate() {
+        let link = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: LINK_COLOR,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(ChainLink(index))
+            .insert(DespawnOnGameOver)
+            .insert(*position)
+            .insert(Size::square(0.8))
+            .insert(Layer::Entities.at(1.0))
+            .id();
+
+        // Not a child of the link, which is scaled up to the size of a cell.
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    (index + 1).to_string(),
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: LINK_FONT_SIZE,
+                        color: LINK_NUMBER_COLOR,
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                ),
+                ..default()
+            })
+            .insert(LinkNumber(link))
+            .insert(DespawnOnGameOver)
+            .insert(*position)
+            .insert(Layer::Entities.at(1.5));
+    }
+}
+
+/// Lays the chain back out at the start of every run, and at startup for the
+/// first one. A run can be started over before the last one ended, so
+/// anything left of its chain goes first.
+#[allow(clippy::too_many_arguments)]
+fn reset_chain(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    active_level: Res<ActiveLevel>,
+    mut objective: ResMut<ChainObjective>,
+    mut new_runs: EventReader<NewRunEvent>,
+    mut started: Local<bool>,
+    links: Query<Entity, With<ChainLink>>,
+    numbers: Query<Entity, With<LinkNumber>>,
+) {
+    if new_runs.iter().next().is_none() && *started {
+        return;
+    }
+    *started = true;
+
+    for ent in links.iter().chain(numbers.iter()) {
+        commands.entity(ent).despawn();
+    }
+
+    *objective = ChainObjective::default();
+    spawn_chain(&mut commands, &asset_server, &active_level.0.chain);
+}
+
+/// Takes the link under the head, if any. The lowest link left is the one
+/// due next, so anything else breaks the chain.
+#[allow(clippy::too_many_arguments)]
+pub fn eat_chain(
+    mut commands: Commands,
+    mut objective: ResMut<ChainObjective>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    links: Query<(Entity, &ChainLink, &Position)>,
+    numbers: Query<(Entity, &LinkNumber)>,
+    heads: Query<&Position, With<SnakeHead>>,
+    devoured: Query<&DevouredFood>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    let (bitten, link) = match links.iter().find(|(_, _, position)| **position == head) {
+        Some((ent, link, _)) => (ent, *link),
+        None => return,
+    };
+
+    let due = links.iter().map(|(_, link, _)| link.0).min();
+    let despawn = |commands: &mut Commands, link: Entity| {
+        commands.entity(link).despawn();
+        for (number, _) in numbers.iter().filter(|(_, number)| number.0 == link) {
+            commands.entity(number).despawn();
+        }
+    };
+
+    let eaten = match devoured.iter().next() {
+        Some(eaten) => eaten,
+        None => return,
+    };
+
+    if Some(link.0) != due {
+        for (ent, _, _) in links.iter() {
+            despawn(&mut commands, ent);
+        }
+
+        objective.broken = true;
+        breakdown.penalise(eaten, BREAK_PENALTY);
+        toasts.send(ToastEvent::info(format!(
+            "Chain broken! -{}",
+            BREAK_PENALTY
+        )));
+        return;
+    }
+
+    despawn(&mut commands, bitten);
+    objective.eaten += 1;
+    breakdown.award(ScoreSource::Bonus, LINK_POINTS);
+
+    if links.iter().count() == 1 {
+        let bonus = objective.eaten as u32;
+        breakdown.award(ScoreSource::Bonus, bonus);
+        toasts.send(ToastEvent::info(format!("Chain complete! +{}", bonus)));
+    }
+}
+
+fn setup_chain_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: CHAIN_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(CHAIN_X, CHAIN_Y, 0.0),
+            ..default()
+        })
+        .insert(ChainText)
+        .insert(Layer::Hud.at(1.0));
+}
+
+/// Shows how far through the chain the run is, or nothing on a level
+/// without one.
+fn update_chain_text(
+    active_level: Res<ActiveLevel>,
+    objective: Res<ChainObjective>,
+    mut query: Query<&mut Text, With<ChainText>>,
+) {
+    if !objective.is_changed() && !active_level.is_changed() {
+        return;
+    }
+
+    let length = active_level.0.chain.len();
+    let (value, color) = if length == 0 {
+        (String::new(), Color::WHITE)
+    } else if objective.broken {
+        ("Chain broken".to_string(), BROKEN_COLOR)
+    } else if objective.eaten == length {
+        ("Chain complete".to_string(), DONE_COLOR)
+    } else {
+        (
+            format!("Chain {}/{}", objective.eaten, length),
+            Color::WHITE,
+        )
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = value.clone();
+        text.sections[0].style.color = color;
+    }
+}
+
+pub struct ChainPlugin;
+
+impl Plugin for ChainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChainObjective>()
+            .add_startup_system(setup_chain_text)
+            .add_system(reset_chain)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(update_chain_text));
+    }
+}
@@ -0,0 +1,124 @@
+//! Classic cheat codes, typed on the pause screen after pressing `/`. Any run
+//! a cheat has been used in still plays out as normal, but its score doesn't
+//! count towards the best score.
+
+use bevy::prelude::*;
+
+use crate::settings::KeyboardCaptured;
+use crate::toast::ToastEvent;
+use crate::{AppState, NewRunEvent, TickRate};
+
+/// The snake passes through walls, enemies and itself.
+const INVINCIBILITY_CODE: &str = "IDDQD";
+/// The snake moves at half speed.
+const SLOW_CODE: &str = "DOZY";
+const CODES: [&str; 2] = [INVINCIBILITY_CODE, SLOW_CODE];
+/// Starts a code, holding the keyboard so its letters don't also trigger
+/// hotkeys.
+const ENTRY_KEY: KeyCode = KeyCode::Slash;
+
+/// The code being typed, if one's been started.
+#[derive(Default)]
+struct CheatEntry(Option<String>);
+
+#[derive(Default)]
+pub struct Cheats {
+    pub invincible: bool,
+    pub slow: bool,
+    /// Set once any cheat has been on during the current run.
+    pub used: bool,
+}
+
+/// Cheats stay on from one run to the next, so a run that starts with one
+/// on is flagged straight away.
+fn reset_cheats(mut cheats: ResMut<Cheats>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        cheats.used = cheats.invincible || cheats.slow;
+    }
+}
+
+/// Takes the letters typed after [`ENTRY_KEY`] until they spell out a code,
+/// or stop being the start of one, which gives the keyboard back.
+fn cheat_code_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut typed: EventReader<ReceivedCharacter>,
+    mut entry: ResMut<CheatEntry>,
+    mut captured: ResMut<KeyboardCaptured>,
+    mut cheats: ResMut<Cheats>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if entry.0.is_none() {
+        if keyboard_input.just_pressed(ENTRY_KEY) && !captured.0 {
+            entry.0 = Some(String::new());
+            captured.0 = true;
+        }
+
+        // The key's own character is dropped along with anything else.
+        typed.iter().for_each(drop);
+        return;
+    }
+
+    for event in typed.iter() {
+        let entered = match entry.0.as_mut() {
+            Some(entered) => entered,
+            None => break,
+        };
+        entered.push(event.char.to_ascii_uppercase());
+
+        if !CODES.iter().any(|code| code.starts_with(entered.as_str())) {
+            entry.0 = None;
+            captured.0 = false;
+            toasts.send(ToastEvent::info("Unknown cheat code"));
+            break;
+        }
+
+        let (name, on) = if entered == INVINCIBILITY_CODE {
+            cheats.invincible = !cheats.invincible;
+            ("Invincibility", cheats.invincible)
+        } else if entered == SLOW_CODE {
+            cheats.slow = !cheats.slow;
+            ("Slow mode", cheats.slow)
+        } else {
+            continue;
+        };
+
+        entry.0 = None;
+        captured.0 = false;
+
+        if on {
+            cheats.used = true;
+            toasts.send(ToastEvent::info(format!(
+                "{} on, this run won't count",
+                name
+            )));
+        } else {
+            toasts.send(ToastEvent::info(format!("{} off", name)));
+        }
+    }
+}
+
+/// Leaving the pause screen halfway through a code drops it.
+fn cancel_cheat_entry(mut entry: ResMut<CheatEntry>, mut captured: ResMut<KeyboardCaptured>) {
+    if entry.0.take().is_some() {
+        captured.0 = false;
+    }
+}
+
+fn apply_slow_mode(cheats: Res<Cheats>, mut tick_rate: ResMut<TickRate>) {
+    if tick_rate.crawling != cheats.slow {
+        tick_rate.crawling = cheats.slow;
+    }
+}
+
+pub struct CheatsPlugin;
+
+impl Plugin for CheatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cheats>()
+            .init_resource::<CheatEntry>()
+            .add_system(reset_cheats)
+            .add_system(apply_slow_mode)
+            .add_system_set(SystemSet::on_update(AppState::Paused).with_system(cheat_code_input))
+            .add_system_set(SystemSet::on_exit(AppState::Paused).with_system(cancel_cheat_entry));
+    }
+}
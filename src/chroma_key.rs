@@ -0,0 +1,68 @@
+//! Streaming overlay options, set from the command line. `--chroma-key`
+//! swaps the background for solid green (or magenta, with
+//! `--chroma-key magenta`) to key out, and `--hud-only` hides the board so
+//! just the score and the rest of the HUD are drawn.
+
+use bevy::prelude::*;
+
+use crate::Position;
+
+const CHROMA_GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+const CHROMA_MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+
+#[derive(Default)]
+pub struct StreamOverlay {
+    /// The solid background to key out, in place of the usual one.
+    pub key: Option<Color>,
+    pub hud_only: bool,
+}
+
+impl StreamOverlay {
+    fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+
+        let key = args.iter().position(|arg| arg == "--chroma-key").map(|i| {
+            match args.get(i + 1).map(String::as_str) {
+                Some("magenta") => CHROMA_MAGENTA,
+                _ => CHROMA_GREEN,
+            }
+        });
+
+        Self {
+            key,
+            hud_only: args.iter().any(|arg| arg == "--hud-only"),
+        }
+    }
+}
+
+fn apply_chroma_key(overlay: Res<StreamOverlay>, mut clear_color: ResMut<ClearColor>) {
+    if let Some(key) = overlay.key {
+        if clear_color.0 != key {
+            clear_color.0 = key;
+        }
+    }
+}
+
+/// Everything on the board sits on a grid cell, which is what sets it apart
+/// from the HUD.
+fn hide_board(overlay: Res<StreamOverlay>, mut query: Query<&mut Visibility, With<Position>>) {
+    if !overlay.hud_only {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        if visibility.is_visible {
+            visibility.is_visible = false;
+        }
+    }
+}
+
+pub struct ChromaKeyPlugin;
+
+impl Plugin for ChromaKeyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StreamOverlay::from_args())
+            .add_system(apply_chroma_key)
+            .add_system_to_stage(CoreStage::PostUpdate, hide_board);
+    }
+}
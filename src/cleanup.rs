@@ -0,0 +1,50 @@
+//! Tearing down entities that only belong to part of the game. Rather than
+//! despawning them by hand on the way out, they're tagged with when they
+//! should go, and despawned here exactly once.
+
+use bevy::prelude::*;
+
+use crate::AppState;
+
+/// Despawned, along with its children, when the given state is left. Pausing
+/// a state by pushing another on top of it doesn't count.
+#[derive(Component)]
+pub struct DespawnOnStateExit(pub AppState);
+
+/// Despawned, along with its children, the moment a run ends.
+#[derive(Component)]
+pub struct DespawnOnGameOver;
+
+/// Runs on exit from every state, while the state being left is still the
+/// current one.
+fn despawn_on_state_exit(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    query: Query<(Entity, &DespawnOnStateExit)>,
+) {
+    for (ent, DespawnOnStateExit(state)) in query.iter() {
+        if state == app_state.current() {
+            commands.entity(ent).despawn_recursive();
+        }
+    }
+}
+
+fn despawn_on_game_over(mut commands: Commands, query: Query<Entity, With<DespawnOnGameOver>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+pub struct CleanupPlugin;
+
+impl Plugin for CleanupPlugin {
+    fn build(&self, app: &mut App) {
+        for state in AppState::ALL {
+            app.add_system_set(SystemSet::on_exit(state).with_system(despawn_on_state_exit));
+        }
+
+        app.add_system_set(
+            SystemSet::on_enter(AppState::GameOver).with_system(despawn_on_game_over),
+        );
+    }
+}
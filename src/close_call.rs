@@ -0,0 +1,102 @@
+//! A small bonus for dodging out of the way of the snake's own body or a wall
+//! at the last moment, spotted by [`crate::snake_movement`].
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::scoring::{ScoreBreakdown, ScoreSource};
+use crate::settings::Settings;
+use crate::timer::{Clock, TickTimer};
+use crate::Occupant;
+
+const CLOSE_CALL_BONUS: u32 = 1;
+/// Weaving back and forth along the body would otherwise pay out every tick.
+const CLOSE_CALL_COOLDOWN_SECONDS: f32 = 2.0;
+const FLAIR_SECONDS: f32 = 1.0;
+const FLAIR_RISE: f32 = 20.0;
+const FLAIR_Y: f32 = 240.0;
+const FLAIR_COLOR: Color = Color::rgb(0.4, 0.9, 1.0);
+
+/// The head dodged whatever's in the cell it was about to move into.
+pub struct CloseCallEvent(pub Occupant);
+
+#[derive(Component)]
+struct CloseCallFlair;
+
+fn award_close_calls(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut cooldown: Local<Timer>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    mut reader: EventReader<CloseCallEvent>,
+) {
+    cooldown.tick(time.delta());
+
+    let occupant = match reader.iter().last() {
+        Some(CloseCallEvent(occupant)) => *occupant,
+        None => return,
+    };
+
+    if !cooldown.finished() {
+        return;
+    }
+
+    *cooldown = Timer::from_seconds(CLOSE_CALL_COOLDOWN_SECONDS, false);
+
+    breakdown.award(ScoreSource::CloseCall, CLOSE_CALL_BONUS);
+
+    let message = match occupant {
+        Occupant::Body => "Close call!",
+        Occupant::Wall => "Close shave!",
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                format!("{} +{}", message, CLOSE_CALL_BONUS),
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 24.0,
+                    color: FLAIR_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, FLAIR_Y, 0.0),
+            ..default()
+        })
+        .insert(CloseCallFlair)
+        .insert(TickTimer::despawning(FLAIR_SECONDS, Clock::Real))
+        .insert(Layer::Overlay.at(4.0));
+}
+
+/// Floats the flair up from under the score as it fades out.
+fn fade_flair(
+    settings: Res<Settings>,
+    mut query: Query<(&TickTimer, &mut Text, &mut Transform), With<CloseCallFlair>>,
+) {
+    for (timer, mut text, mut transform) in query.iter_mut() {
+        let progress = timer.percent();
+
+        if !settings.accessibility.reduced_motion {
+            transform.translation.y = FLAIR_Y + FLAIR_RISE * progress;
+        }
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(1.0 - progress);
+        }
+    }
+}
+
+pub struct CloseCallPlugin;
+
+impl Plugin for CloseCallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CloseCallEvent>()
+            .add_system(award_close_calls)
+            .add_system(fade_flair);
+    }
+}
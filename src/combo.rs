@@ -0,0 +1,161 @@
+//! Eating food in quick succession builds a combo, and every piece eaten
+//! while it lasts scores extra. The widget opposite the score shows the
+//! multiplier and how long is left to keep the chain going.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::hud::{spawn_hud_bar, HudBar, HudBarFill};
+use crate::layers::Layer;
+use crate::scoring::{ScoreBreakdown, ScoreSource};
+use crate::{AppState, GrowthEvent};
+
+/// Seconds after eating to get to the next piece and keep the chain.
+const COMBO_WINDOW_SECONDS: f32 = 3.0;
+const COMBO_MAX_MULTIPLIER: u32 = 5;
+const COMBO_TEXT_Y: f32 = -262.0;
+const COMBO_COLOR: Color = Color::rgb(1.0, 0.55, 0.2);
+const COMBO_BAR: HudBar = HudBar {
+    x: 0.0,
+    y: -282.0,
+    width: 150.0,
+    height: 10.0,
+    color: Color::rgb(1.0, 0.55, 0.2),
+    low_color: Color::rgb(0.8, 0.1, 0.1),
+};
+
+/// The current chain of food, and the time left to extend it.
+#[derive(Default)]
+pub struct Combo {
+    chain: u32,
+    window: Option<Timer>,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        self.chain.clamp(1, COMBO_MAX_MULTIPLIER)
+    }
+
+    fn time_left(&self) -> f32 {
+        self.window
+            .as_ref()
+            .map_or(0.0, |timer| 1.0 - timer.percent())
+    }
+
+    pub fn save(&self) -> SavedCombo {
+        SavedCombo {
+            chain: self.chain,
+            elapsed: self.window.as_ref().map(Timer::elapsed_secs),
+        }
+    }
+
+    pub fn restore(saved: &SavedCombo) -> Self {
+        Self {
+            chain: saved.chain,
+            window: saved.elapsed.map(|elapsed| {
+                let mut timer = Timer::from_seconds(COMBO_WINDOW_SECONDS, false);
+                timer.set_elapsed(Duration::from_secs_f32(elapsed));
+                timer
+            }),
+        }
+    }
+}
+
+/// A combo as a snapshot keeps it, with the window as the seconds gone.
+#[derive(Default, Serialize, Deserialize)]
+pub struct SavedCombo {
+    chain: u32,
+    elapsed: Option<f32>,
+}
+
+#[derive(Component, Clone)]
+struct ComboWidget;
+
+fn setup_combo_widget(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 28.0,
+                    color: COMBO_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, COMBO_TEXT_Y, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ComboWidget)
+        .insert(Layer::Hud.at(1.0));
+
+    spawn_hud_bar(&mut commands, COMBO_BAR, ComboWidget);
+}
+
+/// Extends the chain on every piece of food, scoring one extra point for
+/// each step of the multiplier past the first, and breaks it once the
+/// window runs out.
+fn combo_scoring(
+    time: Res<Time>,
+    mut combo: ResMut<Combo>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    mut growth_reader: EventReader<GrowthEvent>,
+) {
+    let expired = match combo.window.as_mut() {
+        Some(window) => window.tick(time.delta()).finished(),
+        None => false,
+    };
+
+    if expired {
+        *combo = Combo::default();
+    }
+
+    for _ in growth_reader.iter() {
+        combo.chain += 1;
+        combo.window = Some(Timer::from_seconds(COMBO_WINDOW_SECONDS, false));
+
+        breakdown.award(ScoreSource::Combo, combo.multiplier() - 1);
+    }
+}
+
+fn update_combo_widget(
+    combo: Res<Combo>,
+    mut widgets: Query<&mut Visibility, With<ComboWidget>>,
+    mut texts: Query<&mut Text, With<ComboWidget>>,
+    mut fills: Query<&mut HudBarFill, With<ComboWidget>>,
+) {
+    if !combo.is_changed() {
+        return;
+    }
+
+    let showing = combo.multiplier() > 1;
+
+    for mut visibility in widgets.iter_mut() {
+        visibility.is_visible = showing;
+    }
+
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("Combo x{}", combo.multiplier());
+    }
+
+    for mut fill in fills.iter_mut() {
+        fill.0 = combo.time_left();
+    }
+}
+
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Combo>()
+            .add_startup_system(setup_combo_widget)
+            .add_system(update_combo_widget)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(combo_scoring));
+    }
+}
@@ -0,0 +1,261 @@
+//! A drop-down console for poking at the game while working on it, opened
+//! with the backtick key. It's only there when the game is started with
+//! `--cheats`. Any plugin can add commands to it with
+//! [`ConsoleCommandsExt::console_command`].
+
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::KeyboardCaptured;
+use crate::{WIN_HEIGHT, WIN_WIDTH};
+
+const CONSOLE_FLAG: &str = "--cheats";
+const CONSOLE_LINES: usize = 8;
+const CONSOLE_HEIGHT: f32 = 200.0;
+const CONSOLE_PADDING: f32 = 10.0;
+const CONSOLE_FONT_SIZE: f32 = 18.0;
+const CONSOLE_BG_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+const CONSOLE_TEXT_COLOR: Color = Color::rgb(0.7, 1.0, 0.7);
+
+/// Runs a command given the words typed after its name, returning the line
+/// to print back either way.
+pub type ConsoleHandler = fn(&mut World, &[&str]) -> Result<String, String>;
+
+struct ConsoleCommand {
+    usage: &'static str,
+    handler: ConsoleHandler,
+}
+
+/// Every command the console knows, by name.
+#[derive(Default)]
+pub struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+pub trait ConsoleCommandsExt {
+    /// Adds a command to the console. `usage` is how it's listed by `help`.
+    fn console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        handler: ConsoleHandler,
+    ) -> &mut Self;
+}
+
+impl ConsoleCommandsExt for App {
+    fn console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        handler: ConsoleHandler,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(ConsoleCommands::default)
+            .0
+            .insert(name, ConsoleCommand { usage, handler });
+        self
+    }
+}
+
+/// Reads one argument to a command, with a message fit to print back when
+/// it doesn't parse.
+pub fn parse_arg<T: FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse()
+        .map_err(|_| format!("'{}' isn't a valid value", arg))
+}
+
+#[derive(Default)]
+struct Console {
+    open: bool,
+    input: String,
+    log: VecDeque<String>,
+    /// Lines entered since commands were last run.
+    submitted: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: String) {
+        self.log.push_back(line);
+
+        while self.log.len() > CONSOLE_LINES {
+            self.log.pop_front();
+        }
+    }
+}
+
+#[derive(Component)]
+struct ConsolePanel;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn help(world: &mut World, _: &[&str]) -> Result<String, String> {
+    let usages: Vec<&str> = world
+        .resource::<ConsoleCommands>()
+        .0
+        .values()
+        .map(|command| command.usage)
+        .collect();
+
+    Ok(usages.join(", "))
+}
+
+fn setup_console(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let top = WIN_HEIGHT / 2.0;
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: CONSOLE_BG_COLOR,
+                custom_size: Some(Vec2::new(WIN_WIDTH, CONSOLE_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, top - CONSOLE_HEIGHT / 2.0, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ConsolePanel)
+        .insert(Layer::Overlay.at(8.0));
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: CONSOLE_FONT_SIZE,
+                    color: CONSOLE_TEXT_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Top,
+                    horizontal: HorizontalAlign::Left,
+                },
+            ),
+            transform: Transform::from_xyz(
+                -WIN_WIDTH / 2.0 + CONSOLE_PADDING,
+                top - CONSOLE_PADDING,
+                0.0,
+            ),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ConsoleText)
+        .insert(Layer::Overlay.at(8.5));
+}
+
+/// Runs straight after input is read each frame. While the console is open it
+/// swallows every key press, so nothing else in the game reacts to typing.
+fn console_input(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut typed: EventReader<ReceivedCharacter>,
+    mut console: ResMut<Console>,
+    mut captured: ResMut<KeyboardCaptured>,
+) {
+    let typed: Vec<char> = typed.iter().map(|event| event.char).collect();
+
+    if keyboard_input.just_pressed(KeyCode::Grave) {
+        console.open = !console.open;
+        captured.0 = console.open;
+        keyboard_input.clear();
+        return;
+    }
+
+    if !console.open {
+        return;
+    }
+
+    console
+        .input
+        .extend(typed.into_iter().filter(|c| !c.is_control() && *c != '`'));
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+
+        if !line.trim().is_empty() {
+            console.print(format!("> {}", line));
+            console.submitted.push(line);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        console.open = false;
+        captured.0 = false;
+    }
+
+    keyboard_input.clear();
+}
+
+/// Runs whatever was entered this frame. Commands get the whole world, so
+/// they can reach anything a system could.
+fn run_console_commands(world: &mut World) {
+    let lines = std::mem::take(&mut world.resource_mut::<Console>().submitted);
+
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (name, args) = match words.split_first() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        let handler = world
+            .resource::<ConsoleCommands>()
+            .0
+            .get(name)
+            .map(|command| command.handler);
+
+        let output = match handler {
+            Some(handler) => handler(world, args).unwrap_or_else(|e| e),
+            None => format!("Unknown command '{}', try 'help'", name),
+        };
+
+        world.resource_mut::<Console>().print(output);
+    }
+}
+
+fn draw_console(
+    console: Res<Console>,
+    mut panels: Query<&mut Visibility, (With<ConsolePanel>, Without<ConsoleText>)>,
+    mut texts: Query<(&mut Text, &mut Visibility), With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    for mut visibility in panels.iter_mut() {
+        visibility.is_visible = console.open;
+    }
+
+    for (mut text, mut visibility) in texts.iter_mut() {
+        let mut lines: Vec<&str> = console.log.iter().map(String::as_str).collect();
+        let prompt = format!("> {}_", console.input);
+        lines.push(&prompt);
+
+        text.sections[0].value = lines.join("\n");
+        visibility.is_visible = console.open;
+    }
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommands>()
+            .console_command("help", "help", help);
+
+        if !std::env::args().any(|arg| arg == CONSOLE_FLAG) {
+            return;
+        }
+
+        app.init_resource::<Console>()
+            .add_startup_system(setup_console)
+            .add_system_to_stage(CoreStage::PreUpdate, console_input.after(InputSystem))
+            .add_system(run_console_commands.exclusive_system().at_start())
+            .add_system(draw_console);
+    }
+}
@@ -0,0 +1,139 @@
+//! Joins the snake up into one body. A joint fills the gap between each pair
+//! of neighbouring segments, following the order in [`SnakeBody`], so a
+//! straight run draws as a solid bar and a segment where the body turns
+//! draws as a bent corner piece rather than a lone square.
+
+use bevy::prelude::*;
+
+use crate::animation::grow_in;
+use crate::layers::Layer;
+use crate::tail::draw_tail;
+use crate::viewport::snap_to_pixels;
+use crate::{position_translation, size_scaling, Position, SnakeBody, SnakePart};
+
+/// One of a pool of joint sprites, reused from frame to frame.
+#[derive(Component)]
+struct Joint;
+
+type Segments<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Transform,
+        &'static Sprite,
+        &'static Visibility,
+    ),
+    (With<SnakePart>, Without<Joint>),
+>;
+
+type Joints<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static mut Sprite,
+        &'static mut Visibility,
+    ),
+    (With<Joint>, Without<SnakePart>),
+>;
+
+/// Where the joint between `a` and `b` goes, and its size, from how they're
+/// drawn right now. Works from their transforms rather than their cells, so
+/// it keeps up with the tail sliding along and segments growing in.
+fn joint_between(a: &Transform, b: &Transform) -> Option<(Vec2, Vec2)> {
+    let (from, to) = (a.translation.truncate(), b.translation.truncate());
+    let gap = (to - from).abs();
+
+    let (length, width) = if gap.x > gap.y {
+        (
+            gap.x - (a.scale.x + b.scale.x) / 2.0,
+            a.scale.y.min(b.scale.y),
+        )
+    } else {
+        (
+            gap.y - (a.scale.y + b.scale.y) / 2.0,
+            a.scale.x.min(b.scale.x),
+        )
+    };
+
+    if length <= 0.0 {
+        return None;
+    }
+
+    let size = if gap.x > gap.y {
+        Vec2::new(length, width)
+    } else {
+        Vec2::new(width, length)
+    };
+
+    Some(((from + to) / 2.0, size))
+}
+
+/// Runs once everything has been put in place for the frame, so the joints
+/// line up with the segments as they're drawn.
+fn draw_joints(
+    mut commands: Commands,
+    body: Res<SnakeBody>,
+    segments: Segments,
+    mut joints: Joints,
+) {
+    let wanted: Vec<_> = body
+        .0
+        .windows(2)
+        .filter_map(|pair| Some((segments.get(pair[0]).ok()?, segments.get(pair[1]).ok()?)))
+        // Segments either side of the board's edge aren't joined up.
+        .filter(|((a, ..), (b, ..))| (a.x - b.x).abs() + (a.y - b.y).abs() == 1)
+        .filter_map(|((_, a, sprite, a_vis), (_, b, _, b_vis))| {
+            let (at, size) = joint_between(a, b)?;
+            Some((at, size, sprite.color, a_vis.is_visible && b_vis.is_visible))
+        })
+        .collect();
+
+    let mut pool = joints.iter_mut();
+
+    for (at, size, color, visible) in wanted.iter() {
+        match pool.next() {
+            Some((_, mut transform, mut sprite, mut visibility)) => {
+                transform.translation.x = at.x;
+                transform.translation.y = at.y;
+                transform.scale = size.extend(1.0);
+                sprite.color = *color;
+                visibility.is_visible = *visible;
+            }
+            // Shows up from the next frame.
+            None => {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        visibility: Visibility { is_visible: false },
+                        ..default()
+                    })
+                    .insert(Joint)
+                    .insert(Layer::Entities.at(6.0));
+            }
+        }
+    }
+
+    for (_, _, _, mut visibility) in pool {
+        if visibility.is_visible {
+            visibility.is_visible = false;
+        }
+    }
+}
+
+pub struct CornersPlugin;
+
+impl Plugin for CornersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            draw_joints
+                .after(position_translation)
+                .after(size_scaling)
+                .after(grow_in)
+                .after(draw_tail)
+                .after(snap_to_pixels),
+        );
+    }
+}
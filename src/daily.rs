@@ -0,0 +1,127 @@
+//! The daily challenge: a maze and a run of food seeded from the date, so
+//! everyone playing on the same day gets the same board. Winning one without
+//! cheats on consecutive days builds a streak, kept in the profile.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::cheats::Cheats;
+use crate::level::Level;
+use crate::mode::GameMode;
+use crate::{GameOverEvent, GameStatus, RunKind};
+
+/// Days since the Unix epoch, in UTC.
+pub fn today() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| (elapsed.as_secs() / 86_400) as u32)
+}
+
+/// The day's board: classic rules inside a maze every player shares.
+pub fn daily_level(day: u32) -> Level {
+    let mut level = Level::for_mode_with(GameMode::Maze, &mut StdRng::seed_from_u64(day as u64));
+    level.name = format!("Daily challenge #{}", day);
+    level
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyStreak {
+    last_day: Option<u32>,
+    current: u32,
+    pub best: u32,
+}
+
+impl DailyStreak {
+    fn record(&mut self, day: u32) {
+        self.current = match self.last_day {
+            Some(last) if last == day => return,
+            Some(last) if last + 1 == day => self.current + 1,
+            _ => 1,
+        };
+
+        self.last_day = Some(day);
+        self.best = self.best.max(self.current);
+    }
+
+    /// The streak as of `day`. It's still alive until a whole day goes by
+    /// without a challenge finished.
+    pub fn current(&self, day: u32) -> u32 {
+        match self.last_day {
+            Some(last) if last + 1 >= day => self.current,
+            _ => 0,
+        }
+    }
+
+    pub fn done_on(&self, day: u32) -> bool {
+        self.last_day == Some(day)
+    }
+}
+
+fn record_daily(
+    run_kind: Res<RunKind>,
+    cheats: Res<Cheats>,
+    mut streak: ResMut<DailyStreak>,
+    mut reader: EventReader<GameOverEvent>,
+) {
+    let won = match reader.iter().next() {
+        Some(event) => matches!(event.0, GameStatus::Won),
+        None => return,
+    };
+
+    if !won || cheats.used {
+        return;
+    }
+
+    if let RunKind::Daily(day) = *run_kind {
+        streak.record(day);
+    }
+}
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DailyStreak>().add_system(record_daily);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_counts_consecutive_days() {
+        let mut streak = DailyStreak::default();
+        streak.record(10);
+        streak.record(11);
+        streak.record(12);
+
+        assert_eq!(streak.current(12), 3);
+        assert_eq!(streak.best, 3);
+    }
+
+    #[test]
+    fn streak_counts_a_day_once() {
+        let mut streak = DailyStreak::default();
+        streak.record(10);
+        streak.record(10);
+
+        assert_eq!(streak.current(10), 1);
+    }
+
+    #[test]
+    fn streak_restarts_after_a_missed_day() {
+        let mut streak = DailyStreak::default();
+        streak.record(10);
+        streak.record(11);
+        streak.record(13);
+
+        assert_eq!(streak.current(13), 1);
+        assert_eq!(streak.best, 2);
+    }
+}
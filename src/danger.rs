@@ -0,0 +1,138 @@
+//! A warning as uneaten food piles up towards the loss threshold. Past
+//! [`DANGER_FRACTION`] of it, a red vignette pulses around the edge of the
+//! screen and a tick sounds with every pulse, so losing to the food never
+//! comes out of nowhere.
+
+use std::f32::consts::PI;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::{AppState, RenderedFood, Rules, WIN_HEIGHT, WIN_WIDTH};
+
+/// How much of the loss threshold has to be on the board before the warning.
+const DANGER_FRACTION: f32 = 0.8;
+const PULSE_SECONDS: f32 = 0.8;
+const VIGNETTE_COLOR: Color = Color::rgb(0.9, 0.05, 0.05);
+const VIGNETTE_ALPHA: f32 = 0.45;
+/// How far in from the edge the vignette reaches, in steps of fading red.
+const VIGNETTE_BANDS: usize = 4;
+const BAND_WIDTH: f32 = 12.0;
+/// Not bundled, so the tick only sounds once one has been dropped in.
+const WARNING_SOUND: &str = "sounds/warning.ogg";
+
+/// One ring of the vignette, counting in from the edge.
+#[derive(Component)]
+struct VignetteBand(usize);
+
+fn setup_vignette(mut commands: Commands) {
+    for band in 0..VIGNETTE_BANDS {
+        let inset = band as f32 * BAND_WIDTH;
+        let (width, height) = (WIN_WIDTH - inset * 2.0, WIN_HEIGHT - inset * 2.0);
+
+        let edges = [
+            (
+                Vec2::new(0.0, (height - BAND_WIDTH) / 2.0),
+                Vec2::new(width, BAND_WIDTH),
+            ),
+            (
+                Vec2::new(0.0, -(height - BAND_WIDTH) / 2.0),
+                Vec2::new(width, BAND_WIDTH),
+            ),
+            (
+                Vec2::new((width - BAND_WIDTH) / 2.0, 0.0),
+                Vec2::new(BAND_WIDTH, height - BAND_WIDTH * 2.0),
+            ),
+            (
+                Vec2::new(-(width - BAND_WIDTH) / 2.0, 0.0),
+                Vec2::new(BAND_WIDTH, height - BAND_WIDTH * 2.0),
+            ),
+        ];
+
+        for (at, size) in edges {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: VIGNETTE_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(at.extend(0.0)),
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(VignetteBand(band))
+                .insert(Layer::Overlay.at(0.5));
+        }
+    }
+}
+
+fn in_danger(rules: &Rules, rendered: &RenderedFood) -> bool {
+    rendered.0 as f32 >= rules.food_to_lose as f32 * DANGER_FRACTION
+}
+
+/// Pulses the vignette while the run is in danger, ticking once a pulse.
+/// With reduced motion on, the vignette holds steady instead.
+#[allow(clippy::too_many_arguments)]
+fn danger_warning(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    rules: Res<Rules>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    mut pulse: Local<Option<Timer>>,
+    rendered: Query<&RenderedFood>,
+    mut bands: Query<(&VignetteBand, &mut Sprite, &mut Visibility)>,
+) {
+    let danger = *app_state.current() == AppState::Playing
+        && matches!(rendered.iter().next(), Some(rendered) if in_danger(&rules, rendered));
+
+    if !danger {
+        *pulse = None;
+        for (_, _, mut visibility) in bands.iter_mut() {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+            }
+        }
+        return;
+    }
+
+    // The first tick sounds as soon as the danger starts.
+    let tick = match pulse.as_mut() {
+        Some(pulse) => pulse.tick(time.delta()).just_finished(),
+        None => true,
+    };
+    let pulse = pulse.get_or_insert_with(|| Timer::from_seconds(PULSE_SECONDS, true));
+
+    if tick && Path::new("assets").join(WARNING_SOUND).exists() {
+        audio.play_with_settings(
+            asset_server.load(WARNING_SOUND),
+            PlaybackSettings::ONCE.with_volume(settings.audio.sound_volume),
+        );
+    }
+
+    let strength = if settings.accessibility.reduced_motion {
+        0.5
+    } else {
+        // Brightest on the tick, fading out before the next one.
+        0.5 + 0.5 * (pulse.percent() * PI * 2.0).cos()
+    };
+
+    for (band, mut sprite, mut visibility) in bands.iter_mut() {
+        let fade = 1.0 - band.0 as f32 / VIGNETTE_BANDS as f32;
+        sprite.color.set_a(VIGNETTE_ALPHA * strength * fade);
+        visibility.is_visible = true;
+    }
+}
+
+pub struct DangerPlugin;
+
+impl Plugin for DangerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_vignette)
+            .add_system(danger_warning);
+    }
+}
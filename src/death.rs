@@ -0,0 +1,140 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::viewport::board_size;
+use crate::{
+    calculate_grid_position, show_end_game_text, AppState, GameStatus, Position, SnakeBody,
+    SnakePart, GRID_HEIGHT, GRID_WIDTH,
+};
+
+const DEAD_SEGMENT_COLOR: Color = Color::rgb(0.45, 0.45, 0.45);
+/// How long everything holds still on the moment of death before it crumbles.
+const HIT_STOP_SECONDS: f32 = 0.3;
+/// How far the camera closes in on the collision during the hit-stop, as a
+/// fraction of the view.
+const HIT_STOP_ZOOM: f32 = 0.12;
+const CRUMBLE_INTERVAL: f32 = 0.08;
+/// Long snakes crumble faster, so the sequence never drags on past this.
+const CRUMBLE_MAX_DURATION: f32 = 1.5;
+
+/// Plays out a lost run: the snake greys out and the camera leans in on
+/// where it died for a beat, then it falls apart one segment at a time from
+/// the tail up, before the end screen is shown.
+pub struct DeathSequence {
+    remaining: Vec<Entity>,
+    hit_stop: Timer,
+    focus: Option<Position>,
+    timer: Timer,
+    status: GameStatus,
+    greyed: bool,
+}
+
+impl DeathSequence {
+    pub fn new(body: &SnakeBody, status: GameStatus, focus: Option<Position>) -> Self {
+        let interval = CRUMBLE_INTERVAL.min(CRUMBLE_MAX_DURATION / body.0.len().max(1) as f32);
+
+        Self {
+            remaining: body.0.clone(),
+            hit_stop: Timer::from_seconds(HIT_STOP_SECONDS, false),
+            focus,
+            timer: Timer::from_seconds(interval, true),
+            status,
+            greyed: false,
+        }
+    }
+
+    /// How far the camera is zoomed in right now, easing in and back out
+    /// over the hit-stop.
+    fn zoom(&self) -> f32 {
+        if self.hit_stop.finished() {
+            0.0
+        } else {
+            HIT_STOP_ZOOM * (self.hit_stop.percent() * PI).sin()
+        }
+    }
+}
+
+fn crumble(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    sequence: Option<ResMut<DeathSequence>>,
+    mut segments: Query<&mut Sprite, With<SnakePart>>,
+) {
+    let mut sequence = match sequence {
+        Some(sequence) => sequence,
+        None => return,
+    };
+
+    if !sequence.greyed {
+        for mut sprite in segments.iter_mut() {
+            sprite.color = DEAD_SEGMENT_COLOR;
+        }
+
+        sequence.greyed = true;
+    }
+
+    if !sequence.hit_stop.tick(time.delta()).finished() {
+        return;
+    }
+
+    for _ in 0..sequence.timer.tick(time.delta()).times_finished() {
+        if let Some(segment) = sequence.remaining.pop() {
+            commands.entity(segment).despawn_recursive();
+        }
+    }
+
+    if sequence.remaining.is_empty() {
+        commands.remove_resource::<DeathSequence>();
+        show_end_game_text(commands, &sequence.status, asset_server);
+    }
+}
+
+/// Points the camera at the collision during a hit-stop, and puts it back
+/// the rest of the time, even if the sequence is cut short by a new run.
+fn frame_camera(
+    sequence: Option<Res<DeathSequence>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let board = board_size();
+
+    let (zoom, focus) = match sequence
+        .as_ref()
+        .and_then(|s| s.focus.map(|f| (s.zoom(), f)))
+    {
+        Some((zoom, focus)) => (
+            zoom,
+            Vec2::new(
+                calculate_grid_position(focus.x as f32, board.x, GRID_WIDTH as f32),
+                calculate_grid_position(focus.y as f32, board.y, GRID_HEIGHT as f32),
+            ),
+        ),
+        None => (0.0, Vec2::ZERO),
+    };
+
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        let scale = 1.0 - zoom;
+        // Keeps the focus where it was on screen while everything around it
+        // grows.
+        let target = focus * zoom;
+
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+
+        if transform.translation.truncate() != target {
+            transform.translation.x = target.x;
+            transform.translation.y = target.y;
+        }
+    }
+}
+
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(frame_camera)
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(crumble));
+    }
+}
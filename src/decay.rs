@@ -0,0 +1,120 @@
+//! Decay mode: the score drains away all the time, faster the higher it
+//! gets, and only eating keeps it climbing. The drain is worked out in
+//! fractions of a point and taken off the score a whole point at a time, with
+//! the current rate shown under the score.
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::mode::GameMode;
+use crate::scoring::ScoreBreakdown;
+use crate::{AppState, DevouredFood, GameStatus};
+
+/// Points drained a second at a score of 0.
+const DECAY_BASE: f32 = 0.2;
+/// How much faster it drains for every point held.
+const DECAY_PER_POINT: f32 = 0.01;
+const DECAY_TEXT_Y: f32 = 248.0;
+const DECAY_FONT_SIZE: f32 = 18.0;
+const DECAY_COLOR: Color = Color::rgb(0.9, 0.4, 0.4);
+
+/// The part of a point drained since the last whole one came off the score.
+#[derive(Default)]
+pub struct ScoreDecay {
+    drained: f32,
+}
+
+fn decay_rate(score: u32) -> f32 {
+    DECAY_BASE + DECAY_PER_POINT * score as f32
+}
+
+#[derive(Component)]
+struct DecayText;
+
+fn decay_score(
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    mut decay: ResMut<ScoreDecay>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    query: Query<(&GameStatus, &DevouredFood)>,
+) {
+    if *game_mode != GameMode::Decay {
+        return;
+    }
+
+    let (status, eaten) = match query.iter().next() {
+        Some(state) => state,
+        None => return,
+    };
+
+    if !matches!(status, GameStatus::InProgress) {
+        return;
+    }
+
+    // Nothing to take at 0, and nothing saved up against the next point.
+    let score = breakdown.score(eaten);
+    if score == 0 {
+        decay.drained = 0.0;
+        return;
+    }
+
+    decay.drained += decay_rate(score) * time.delta_seconds();
+
+    let whole = decay.drained.floor();
+    if whole >= 1.0 {
+        breakdown.penalise(eaten, whole as u32);
+        decay.drained -= whole;
+    }
+}
+
+fn setup_decay_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: DECAY_FONT_SIZE,
+                    color: DECAY_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, DECAY_TEXT_Y, 0.0),
+            ..default()
+        })
+        .insert(DecayText)
+        .insert(Layer::Hud.at(1.0));
+}
+
+/// Shows the drain at the current score, or nothing outside decay mode.
+fn update_decay_text(
+    game_mode: Res<GameMode>,
+    breakdown: Res<ScoreBreakdown>,
+    devoured: Query<&DevouredFood>,
+    mut query: Query<&mut Text, With<DecayText>>,
+) {
+    let value = match (*game_mode, devoured.iter().next()) {
+        (GameMode::Decay, Some(eaten)) => format!("-{:.2}/s", decay_rate(breakdown.score(eaten))),
+        _ => String::new(),
+    };
+
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+pub struct DecayPlugin;
+
+impl Plugin for DecayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreDecay>()
+            .add_startup_system(setup_decay_text)
+            .add_system(update_decay_text)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(decay_score));
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::layers::Layer;
+use crate::obstacles::Wall;
+use crate::toast::{ToastEvent, ToastKind};
+use crate::{Position, Size, SnakeHead};
+
+const KEY_ICON_X: f32 = -325.0;
+const KEY_ICON_Y: f32 = 250.0;
+const KEY_ICON_SPACING: f32 = 20.0;
+const KEY_ICON_SIZE: f32 = 14.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum KeyColor {
+    Blue,
+    Gold,
+}
+
+impl KeyColor {
+    fn color(&self) -> Color {
+        match self {
+            Self::Blue => Color::rgb(0.2, 0.4, 0.9),
+            Self::Gold => Color::rgb(0.9, 0.75, 0.1),
+        }
+    }
+}
+
+/// A pickup that opens every [`Door`] of the same color.
+#[derive(Component)]
+pub struct Key(KeyColor);
+
+/// Solid like a wall until the snake is holding the matching key.
+#[derive(Component)]
+pub struct Door(KeyColor);
+
+/// Keys collected during the current run.
+#[derive(Default)]
+pub struct HeldKeys(BTreeSet<KeyColor>);
+
+#[derive(Component)]
+struct KeyIcon;
+
+pub fn spawn_key(commands: &mut Commands, position: Position, color: KeyColor) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: color.color(),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Key(color))
+        .insert(position)
+        .insert(Size::square(0.5))
+        .insert(Layer::Board.at(1.0))
+        .id()
+}
+
+pub fn spawn_door(commands: &mut Commands, position: Position, color: KeyColor) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: color.color(),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Door(color))
+        .insert(Wall)
+        .insert(position)
+        .insert(Size::square(0.9))
+        .insert(Layer::Board.at(2.0))
+        .id()
+}
+
+/// Collects any key under the head and opens its doors, before the wall
+/// collision check gets a chance to see them.
+pub fn key_pickup(
+    mut commands: Commands,
+    mut held_keys: ResMut<HeldKeys>,
+    keys: Query<(Entity, &Key, &Position)>,
+    doors: Query<(Entity, &Door)>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for head in heads.iter() {
+        for (ent, key, position) in keys.iter() {
+            if position != head {
+                continue;
+            }
+
+            commands.entity(ent).despawn();
+            held_keys.0.insert(key.0);
+            toasts.send(ToastEvent::new(
+                ToastKind::PowerUp,
+                format!("{:?} key! {:?} doors open", key.0, key.0),
+            ));
+
+            for (door_ent, door) in doors.iter() {
+                if door.0 == key.0 {
+                    commands.entity(door_ent).despawn();
+                }
+            }
+        }
+    }
+}
+
+fn update_key_icons(
+    mut commands: Commands,
+    held_keys: Res<HeldKeys>,
+    icons: Query<Entity, With<KeyIcon>>,
+) {
+    if !held_keys.is_changed() {
+        return;
+    }
+
+    for ent in icons.iter() {
+        commands.entity(ent).despawn();
+    }
+
+    for (i, color) in held_keys.0.iter().enumerate() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: color.color(),
+                    custom_size: Some(Vec2::splat(KEY_ICON_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    KEY_ICON_X + i as f32 * KEY_ICON_SPACING,
+                    KEY_ICON_Y,
+                    0.0,
+                ),
+                ..default()
+            })
+            .insert(KeyIcon)
+            .insert(Layer::Hud.at(1.0));
+    }
+}
+
+pub struct DoorsPlugin;
+
+impl Plugin for DoorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeldKeys>().add_system(update_key_icons);
+    }
+}
@@ -0,0 +1,483 @@
+use arboard::Clipboard;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::boards::BoardLayout;
+use crate::chain::ChainLink;
+use crate::gates::DEFAULT_GATE_TICKS;
+use crate::layers::Layer;
+use crate::level::{ActiveLevel, Level, LEVELS_DIR};
+use crate::obstacles::{spawn_tile, LevelTile, Tile};
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::terrain::TileEffect;
+use crate::transition::{ScreenFade, TransitionEvent};
+use crate::{
+    cursor_to_grid, spawn_food, AppState, Food, Position, RenderFoodEvent, RenderedFood, Size,
+    DEFAULT_SPAWN,
+};
+
+const EDITOR_FILE: &str = "custom.ron";
+const CODE_PREVIEW_LEN: usize = 40;
+const SPAWN_MARKER_COLOR: Color = Color::rgba(0.0, 0.7, 0.0, 0.4);
+const EDITOR_HELP: &str =
+    "1 Wall  2 Breakable  3 Portal A  4 Portal B  5 Ice  6 Mud  7 Spawn  8 Food  9 Gate\n\
+     LMB paint  RMB erase  Ctrl+S save  Ctrl+L load  Ctrl+C copy code  Ctrl+O load code  E exit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Brush {
+    Tile(Tile),
+    Spawn,
+    Food,
+}
+
+const BRUSHES: [(KeyCode, Brush); 9] = [
+    (KeyCode::Key1, Brush::Tile(Tile::Wall)),
+    (KeyCode::Key2, Brush::Tile(Tile::Breakable)),
+    (KeyCode::Key3, Brush::Tile(Tile::Portal(0))),
+    (KeyCode::Key4, Brush::Tile(Tile::Portal(1))),
+    (KeyCode::Key5, Brush::Tile(Tile::Terrain(TileEffect::Ice))),
+    (KeyCode::Key6, Brush::Tile(Tile::Terrain(TileEffect::Mud))),
+    (KeyCode::Key7, Brush::Spawn),
+    (KeyCode::Key8, Brush::Food),
+    (KeyCode::Key9, Brush::Tile(Tile::Gate(DEFAULT_GATE_TICKS))),
+];
+
+struct EditorBrush(Brush);
+
+impl Default for EditorBrush {
+    fn default() -> Self {
+        Self(Brush::Tile(Tile::Wall))
+    }
+}
+
+#[derive(Component)]
+struct SpawnMarker;
+
+type TileQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static Position, &'static LevelTile), Without<SpawnMarker>>;
+type FoodQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static Position), (With<Food>, Without<SpawnMarker>)>;
+
+#[derive(Component)]
+struct EditorText;
+
+/// Everything spawned on entering the editor, torn down again on exit.
+#[derive(Component)]
+struct EditorOnly;
+
+fn toggle_editor(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    fade: Res<ScreenFade>,
+    app_state: Res<State<AppState>>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if captured.0 || fade.is_active() || !settings.controls.hotkey(&keyboard_input, KeyCode::E) {
+        return;
+    }
+
+    let next = match app_state.current() {
+        AppState::Playing | AppState::Paused => AppState::Editor,
+        AppState::Editor => AppState::Playing,
+        _ => return,
+    };
+
+    transitions.send(TransitionEvent(next));
+}
+
+fn enter_editor(
+    mut commands: Commands,
+    active_level: Res<ActiveLevel>,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: SPAWN_MARKER_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SpawnMarker)
+        .insert(EditorOnly)
+        .insert(active_level.0.spawn.unwrap_or(DEFAULT_SPAWN))
+        .insert(Size::square(1.0))
+        .insert(Layer::Hud.at(1.0));
+
+    let style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+        font_size: 18.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: format!("{}\n", EDITOR_HELP),
+                        style: style.clone(),
+                    },
+                    TextSection {
+                        value: String::new(),
+                        style,
+                    },
+                ],
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            },
+            transform: Transform::from_xyz(0.0, -260.0, 0.0),
+            ..default()
+        })
+        .insert(EditorText)
+        .insert(EditorOnly)
+        .insert(Layer::Hud.at(2.0));
+}
+
+fn exit_editor(
+    mut commands: Commands,
+    mut dialog: ResMut<CodeDialog>,
+    mut captured: ResMut<KeyboardCaptured>,
+    query: Query<Entity, With<EditorOnly>>,
+) {
+    if dialog.open {
+        dialog.open = false;
+        captured.0 = false;
+    }
+
+    for ent in query.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+fn select_brush(
+    keyboard_input: Res<Input<KeyCode>>,
+    dialog: Res<CodeDialog>,
+    mut brush: ResMut<EditorBrush>,
+) {
+    if dialog.open {
+        return;
+    }
+
+    for (key, choice) in BRUSHES {
+        if keyboard_input.just_pressed(key) {
+            brush.0 = choice;
+        }
+    }
+}
+
+/// The parts of the world the editor reads and rewrites.
+#[derive(SystemParam)]
+struct EditorBoard<'w, 's> {
+    commands: Commands<'w, 's>,
+    tiles: TileQuery<'w, 's>,
+    food: FoodQuery<'w, 's>,
+    chain: Query<'w, 's, (&'static ChainLink, &'static Position)>,
+    markers: Query<'w, 's, &'static mut Position, With<SpawnMarker>>,
+    rendered: Query<'w, 's, &'static mut RenderedFood>,
+    render_event: EventWriter<'w, 's, RenderFoodEvent>,
+}
+
+impl<'w, 's> EditorBoard<'w, 's> {
+    /// Removes any level tiles and food from a cell, keeping the count of
+    /// food on the board in sync.
+    fn clear_cell(&mut self, cell: Position) {
+        for (ent, _, _) in self.tiles.iter().filter(|(_, pos, _)| **pos == cell) {
+            self.commands.entity(ent).despawn_recursive();
+        }
+
+        for (ent, _) in self.food.iter().filter(|(_, pos)| **pos == cell) {
+            self.commands.entity(ent).despawn();
+
+            if let Some(mut count) = self.rendered.iter_mut().next() {
+                count.0 = count.0.saturating_sub(1);
+            }
+        }
+    }
+
+    fn paint(&mut self, cell: Position, brush: Brush) {
+        match brush {
+            Brush::Spawn => {
+                for mut marker in self.markers.iter_mut() {
+                    *marker = cell;
+                }
+            }
+            Brush::Food => {
+                if self.food.iter().all(|(_, pos)| *pos != cell) {
+                    self.clear_cell(cell);
+                    spawn_food(&mut self.commands, cell);
+                    self.render_event.send(RenderFoodEvent);
+                }
+            }
+            Brush::Tile(tile) => {
+                let placed = self
+                    .tiles
+                    .iter()
+                    .any(|(_, pos, placed)| *pos == cell && placed.0 == tile);
+
+                if !placed {
+                    self.clear_cell(cell);
+                    spawn_tile(&mut self.commands, cell, tile);
+                }
+            }
+        }
+    }
+
+    /// Whatever's left of the chain, in the order it's to be eaten.
+    fn chain_in_order(&self) -> Vec<Position> {
+        let mut links: Vec<_> = self.chain.iter().collect();
+        links.sort_by_key(|(link, _)| link.0);
+        links.into_iter().map(|(_, pos)| *pos).collect()
+    }
+
+    /// The board as a level, keeping the name and par of the level it was
+    /// opened from.
+    fn to_level(&self, opened: &Level) -> Level {
+        Level {
+            name: opened.name.clone(),
+            spawn: self.markers.iter().next().copied(),
+            tiles: self
+                .tiles
+                .iter()
+                .map(|(_, pos, tile)| (*pos, tile.0))
+                .collect(),
+            food: self.food.iter().map(|(_, pos)| *pos).collect(),
+            chain: self.chain_in_order(),
+            par: opened.par,
+            // A scene's walls are tiles on the board by now, and go in with
+            // the rest.
+            scene: None,
+            generated: None,
+        }
+    }
+
+    /// Swaps everything on the board for the contents of `level`.
+    fn replace(&mut self, level: &Level) {
+        for (ent, _, _) in self.tiles.iter() {
+            self.commands.entity(ent).despawn_recursive();
+        }
+
+        for (ent, _) in self.food.iter() {
+            self.commands.entity(ent).despawn();
+        }
+
+        if let Some(mut count) = self.rendered.iter_mut().next() {
+            count.0 = 0;
+        }
+
+        for mut marker in self.markers.iter_mut() {
+            *marker = level.spawn.unwrap_or(*marker);
+        }
+
+        level.spawn(&mut self.commands, &mut self.render_event);
+    }
+}
+
+/// The "load from code" prompt. It only takes pasted input, so typing can't
+/// trip any of the other hotkeys while it's open.
+#[derive(Default)]
+struct CodeDialog {
+    open: bool,
+    code: String,
+}
+
+#[derive(Component)]
+struct CodeDialogText;
+
+type DialogTextQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static mut Text), (With<CodeDialogText>, Without<EditorText>)>;
+
+fn set_status(text: &mut Query<&mut Text, With<EditorText>>, status: String) {
+    for mut text in text.iter_mut() {
+        text.sections[1].value = status.clone();
+    }
+}
+
+fn paint(
+    windows: Res<Windows>,
+    layout: Res<BoardLayout>,
+    mouse_input: Res<Input<MouseButton>>,
+    brush: Res<EditorBrush>,
+    dialog: Res<CodeDialog>,
+    mut board: EditorBoard,
+) {
+    if dialog.open {
+        return;
+    }
+
+    let window = windows.get_primary().unwrap();
+    let cell = match window
+        .cursor_position()
+        .and_then(|c| cursor_to_grid(c, window, &layout))
+    {
+        Some(cell) => cell,
+        None => return,
+    };
+
+    if mouse_input.pressed(MouseButton::Right) {
+        board.clear_cell(cell);
+    } else if mouse_input.pressed(MouseButton::Left) {
+        board.paint(cell, brush.0);
+    }
+}
+
+fn editor_shortcuts(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut dialog: ResMut<CodeDialog>,
+    mut captured: ResMut<KeyboardCaptured>,
+    mut board: EditorBoard,
+    mut text: Query<&mut Text, With<EditorText>>,
+) {
+    if dialog.open || !keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+
+    let path = format!("{}/{}", LEVELS_DIR, EDITOR_FILE);
+
+    let status = if keyboard_input.just_pressed(KeyCode::S) {
+        let level = board.to_level(&active_level.0);
+        let status = match level.save(&path) {
+            Ok(()) => format!("Saved to {}", path),
+            Err(e) => format!("Failed to save {}: {}", path, e),
+        };
+
+        active_level.0 = level;
+        status
+    } else if keyboard_input.just_pressed(KeyCode::L) {
+        match Level::load(&path) {
+            Ok(level) => {
+                board.replace(&level);
+                active_level.0 = level;
+                format!("Loaded {}", path)
+            }
+            Err(e) => format!("Failed to load {}: {}", path, e),
+        }
+    } else if keyboard_input.just_pressed(KeyCode::C) {
+        let code = board.to_level(&active_level.0).to_code();
+
+        match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(code)) {
+            Ok(()) => "Copied the level code to the clipboard".to_string(),
+            Err(e) => format!("Failed to copy the level code: {}", e),
+        }
+    } else if keyboard_input.just_pressed(KeyCode::O) {
+        dialog.open = true;
+        captured.0 = true;
+        dialog.code.clear();
+        String::new()
+    } else {
+        return;
+    };
+
+    set_status(&mut text, status);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn code_dialog(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut dialog: ResMut<CodeDialog>,
+    mut captured: ResMut<KeyboardCaptured>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut board: EditorBoard,
+    mut text: Query<&mut Text, With<EditorText>>,
+    mut dialog_text: DialogTextQuery,
+) {
+    if !dialog.open {
+        return;
+    }
+
+    let ctrl = keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+
+    if ctrl && keyboard_input.just_pressed(KeyCode::V) {
+        match Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+            Ok(code) => dialog.code = code.trim().to_string(),
+            Err(e) => set_status(&mut text, format!("Failed to read the clipboard: {}", e)),
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Back) {
+        dialog.code.clear();
+    } else if keyboard_input.just_pressed(KeyCode::Return) {
+        match Level::from_code(&dialog.code) {
+            Ok(level) => {
+                board.replace(&level);
+                set_status(&mut text, format!("Loaded '{}' from code", level.name));
+                active_level.0 = level;
+                dialog.open = false;
+            }
+            Err(e) => set_status(&mut text, format!("Invalid level code: {}", e)),
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        dialog.open = false;
+    }
+
+    // Pasting with Ctrl+V and typing over the board shouldn't fire the
+    // global hotkeys too.
+    captured.0 = dialog.open;
+
+    let preview = match dialog.code.chars().count() {
+        0 => "<empty>".to_string(),
+        len if len > CODE_PREVIEW_LEN => format!(
+            "{}...",
+            dialog
+                .code
+                .chars()
+                .take(CODE_PREVIEW_LEN)
+                .collect::<String>()
+        ),
+        _ => dialog.code.clone(),
+    };
+    let message = format!(
+        "Load from code\nPaste with Ctrl+V, Enter to load, Esc to cancel\n{}",
+        preview
+    );
+
+    match dialog_text.iter_mut().next() {
+        Some((ent, _)) if !dialog.open => commands.entity(ent).despawn(),
+        Some((_, mut text)) => text.sections[0].value = message,
+        None if dialog.open => {
+            commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        message,
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                            font_size: 24.0,
+                            color: Color::WHITE,
+                        },
+                        TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    ),
+                    ..default()
+                })
+                .insert(CodeDialogText)
+                .insert(EditorOnly)
+                .insert(Layer::Overlay.at(1.0));
+        }
+        None => (),
+    }
+}
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorBrush>()
+            .init_resource::<CodeDialog>()
+            .add_system(toggle_editor)
+            .add_system_set(SystemSet::on_enter(AppState::Editor).with_system(enter_editor))
+            .add_system_set(SystemSet::on_exit(AppState::Editor).with_system(exit_editor))
+            .add_system_set(
+                SystemSet::on_update(AppState::Editor)
+                    .with_system(select_brush)
+                    .with_system(paint.after(select_brush))
+                    .with_system(editor_shortcuts)
+                    .with_system(code_dialog.after(editor_shortcuts)),
+            );
+    }
+}
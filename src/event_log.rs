@@ -0,0 +1,157 @@
+//! Opt in JSON lines log of what happened on each movement tick, enabled with
+//! `--event-log <path>`. Handy for chasing desyncs and for feeding analysis
+//! tools, one self-contained object per line.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::level::ActiveLevel;
+use crate::{Direction, Food, GameStatus, GrowthEvent, NewRunEvent, Position, SnakeHead};
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum LoggedEvent {
+    RunStarted { level: String },
+    Turn { direction: Direction },
+    FoodSpawned { position: Position },
+    FoodEaten { position: Position },
+    Status { status: GameStatus },
+}
+
+#[derive(Serialize)]
+struct LogLine {
+    tick: u64,
+    #[serde(flatten)]
+    event: LoggedEvent,
+}
+
+#[derive(Default)]
+pub struct EventLog {
+    writer: Option<BufWriter<File>>,
+    tick: u64,
+    direction: Option<Direction>,
+}
+
+impl EventLog {
+    pub fn from_args() -> Self {
+        let path = std::env::args()
+            .skip_while(|arg| arg != "--event-log")
+            .nth(1);
+
+        let writer =
+            path.and_then(
+                |path| match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(file) => Some(BufWriter::new(file)),
+                    Err(e) => {
+                        eprintln!("Failed to open event log '{}': {}", path, e);
+                        None
+                    }
+                },
+            );
+
+        Self {
+            writer,
+            ..default()
+        }
+    }
+
+    fn write(&mut self, event: LoggedEvent) {
+        let line = LogLine {
+            tick: self.tick,
+            event,
+        };
+
+        if let Some(writer) = self.writer.as_mut() {
+            let written = serde_json::to_writer(&mut *writer, &line)
+                .map_err(|e| e.to_string())
+                .and_then(|_| writeln!(writer).map_err(|e| e.to_string()));
+
+            // Give up on the log rather than spamming an error every tick.
+            if let Err(e) = written {
+                eprintln!("Failed to write to the event log, disabling it: {}", e);
+                self.writer = None;
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+fn log_run_started(
+    mut log: ResMut<EventLog>,
+    mut reader: EventReader<NewRunEvent>,
+    active_level: Res<ActiveLevel>,
+) {
+    if log.writer.is_none() {
+        return;
+    }
+
+    if reader.iter().count() > 0 {
+        log.tick = 0;
+        log.direction = None;
+        log.write(LoggedEvent::RunStarted {
+            level: active_level.0.name.clone(),
+        });
+        log.flush();
+    }
+}
+
+/// Runs last on each movement tick and records whatever changed during it.
+pub fn log_tick_events(
+    mut log: ResMut<EventLog>,
+    mut growth_reader: EventReader<GrowthEvent>,
+    heads: Query<(&SnakeHead, &Position)>,
+    spawned_food: Query<&Position, Added<Food>>,
+    statuses: Query<&GameStatus, Changed<GameStatus>>,
+) {
+    if log.writer.is_none() {
+        return;
+    }
+
+    log.tick += 1;
+
+    for position in spawned_food.iter() {
+        log.write(LoggedEvent::FoodSpawned {
+            position: *position,
+        });
+    }
+
+    if let Some((head, position)) = heads.iter().next() {
+        if log.direction != Some(head.moved) {
+            log.direction = Some(head.moved);
+            log.write(LoggedEvent::Turn {
+                direction: head.moved,
+            });
+        }
+
+        for _ in growth_reader.iter() {
+            log.write(LoggedEvent::FoodEaten {
+                position: *position,
+            });
+        }
+    }
+
+    for status in statuses.iter() {
+        log.write(LoggedEvent::Status {
+            status: status.clone(),
+        });
+    }
+
+    log.flush();
+}
+
+pub struct EventLogPlugin;
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EventLog::from_args())
+            .add_system(log_run_started);
+    }
+}
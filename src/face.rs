@@ -0,0 +1,218 @@
+//! A face on the snake's head: eyes that blink now and then, and a tongue
+//! that flicks out when food is close. Both are small sprites on the head,
+//! played through a handful of frames by [`FrameAnimation`].
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{Direction, Food, Position, SnakeHead};
+
+const EYE_COLOR: Color = Color::rgb(0.05, 0.05, 0.05);
+const TONGUE_COLOR: Color = Color::rgb(0.9, 0.2, 0.3);
+/// Sizes and offsets are fractions of the head.
+const EYE_SIZE: f32 = 0.18;
+const EYE_FORWARD: f32 = 0.18;
+const EYE_APART: f32 = 0.22;
+const TONGUE_WIDTH: f32 = 0.12;
+const TONGUE_LENGTH: f32 = 0.45;
+const FRAME_SECONDS: f32 = 0.05;
+/// Seconds between blinks, picked at random from this range each time.
+const BLINK_INTERVAL: (f32, f32) = (2.0, 6.0);
+/// How close food has to be, in cells, for the tongue to go after it.
+const TONGUE_REACH: u32 = 2;
+
+/// How open the eyes are on each frame of a blink.
+const BLINK_FRAMES: &[f32] = &[0.5, 0.1, 0.5];
+/// How far out the tongue is on each frame of a flick, two in quick
+/// succession then a pause before the next.
+const FLICK_FRAMES: &[f32] = &[0.5, 1.0, 0.5, 0.0, 0.5, 1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+/// Plays through `frames` once each time it's started, then holds on `rest`.
+#[derive(Component)]
+struct FrameAnimation {
+    frames: &'static [f32],
+    rest: f32,
+    timer: Timer,
+    frame: Option<usize>,
+}
+
+impl FrameAnimation {
+    fn new(frames: &'static [f32], rest: f32) -> Self {
+        Self {
+            frames,
+            rest,
+            timer: Timer::from_seconds(FRAME_SECONDS, true),
+            frame: None,
+        }
+    }
+
+    fn play(&mut self) {
+        if self.frame.is_none() {
+            self.frame = Some(0);
+            self.timer.reset();
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        self.frame.is_some()
+    }
+
+    fn value(&self) -> f32 {
+        self.frame.map_or(self.rest, |frame| self.frames[frame])
+    }
+}
+
+fn advance_frames(time: Res<Time>, mut query: Query<&mut FrameAnimation>) {
+    for mut animation in query.iter_mut() {
+        let frame = match animation.frame {
+            Some(frame) => frame,
+            None => continue,
+        };
+
+        let steps = animation.timer.tick(time.delta()).times_finished() as usize;
+        let next = frame + steps;
+        animation.frame = (next < animation.frames.len()).then_some(next);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum FacePart {
+    /// Which side of the head, -1 for the left of the way it faces or 1 for
+    /// the right.
+    Eye(f32),
+    Tongue,
+}
+
+/// Counts down to the next blink.
+#[derive(Component)]
+struct Blink(Timer);
+
+impl Blink {
+    fn random() -> Self {
+        let seconds = rand::thread_rng().gen_range(BLINK_INTERVAL.0..BLINK_INTERVAL.1);
+        Self(Timer::from_seconds(seconds, false))
+    }
+}
+
+fn add_face(mut commands: Commands, heads: Query<Entity, Added<SnakeHead>>) {
+    for head in heads.iter() {
+        commands
+            .entity(head)
+            .insert(Blink::random())
+            .with_children(|parent| {
+                let parts = [
+                    (
+                        FacePart::Eye(-1.0),
+                        EYE_COLOR,
+                        FrameAnimation::new(BLINK_FRAMES, 1.0),
+                    ),
+                    (
+                        FacePart::Eye(1.0),
+                        EYE_COLOR,
+                        FrameAnimation::new(BLINK_FRAMES, 1.0),
+                    ),
+                    (
+                        FacePart::Tongue,
+                        TONGUE_COLOR,
+                        FrameAnimation::new(FLICK_FRAMES, 0.0),
+                    ),
+                ];
+
+                for (part, color, animation) in parts {
+                    parent
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite { color, ..default() },
+                            ..default()
+                        })
+                        .insert(part)
+                        .insert(animation);
+                }
+            });
+    }
+}
+
+/// Starts a blink when it's due, and a flick whenever there's food in reach
+/// of the tongue and it isn't already out.
+fn trigger_face(
+    time: Res<Time>,
+    food: Query<&Position, With<Food>>,
+    mut heads: Query<(&Position, &mut Blink, &Children)>,
+    mut parts: Query<(&FacePart, &mut FrameAnimation)>,
+) {
+    for (head, mut blink, children) in heads.iter_mut() {
+        let blinking = blink.0.tick(time.delta()).just_finished();
+        if blinking {
+            *blink = Blink::random();
+        }
+
+        let in_reach = food
+            .iter()
+            .any(|f| head.x.abs_diff(f.x) + head.y.abs_diff(f.y) <= TONGUE_REACH);
+
+        for child in children.iter() {
+            if let Ok((part, mut animation)) = parts.get_mut(*child) {
+                match part {
+                    FacePart::Eye(_) if blinking => animation.play(),
+                    FacePart::Tongue if in_reach && !animation.is_playing() => animation.play(),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Lays the face out towards the way the head last moved. The head's own
+/// scale carries through, so everything here is in fractions of it.
+fn draw_face(
+    heads: Query<(&SnakeHead, &Visibility, &Children)>,
+    mut parts: Query<
+        (&FacePart, &FrameAnimation, &mut Transform, &mut Visibility),
+        Without<SnakeHead>,
+    >,
+) {
+    for (head, head_visibility, children) in heads.iter() {
+        let (forward, side) = match head.moved {
+            Direction::Up => (Vec2::Y, Vec2::X),
+            Direction::Down => (-Vec2::Y, -Vec2::X),
+            Direction::Left => (-Vec2::X, Vec2::Y),
+            Direction::Right => (Vec2::X, -Vec2::Y),
+        };
+
+        for child in children.iter() {
+            let (part, animation, mut transform, mut visibility) = match parts.get_mut(*child) {
+                Ok(part) => part,
+                Err(_) => continue,
+            };
+
+            let (at, along, across) = match *part {
+                FacePart::Eye(side_of) => (
+                    forward * EYE_FORWARD + side * EYE_APART * side_of,
+                    EYE_SIZE * animation.value(),
+                    EYE_SIZE,
+                ),
+                FacePart::Tongue => {
+                    let length = TONGUE_LENGTH * animation.value();
+                    (forward * (0.5 + length / 2.0), length, TONGUE_WIDTH)
+                }
+            };
+
+            // Sizes go along and across the way the head faces.
+            let size = (forward.abs() * along + side.abs() * across).extend(1.0);
+
+            transform.translation = at.extend(0.1);
+            transform.scale = size;
+            visibility.is_visible = head_visibility.is_visible && along > 0.0;
+        }
+    }
+}
+
+pub struct FacePlugin;
+
+impl Plugin for FacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(add_face)
+            .add_system(advance_frames)
+            .add_system(trigger_face.before(advance_frames))
+            .add_system(draw_face.after(advance_frames));
+    }
+}
@@ -0,0 +1,107 @@
+//! Where food drops as the run goes on, picked per profile. With a bias set,
+//! food stops landing evenly over the free cells and leans toward the risky
+//! ones, more so the higher the score, so late food is harder to get at.
+
+use std::collections::HashSet;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Position, GRID_HEIGHT, GRID_WIDTH};
+
+/// How many times likelier the riskiest cell is than the safest, at full
+/// strength.
+const MAX_WEIGHT: f32 = 8.0;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum FoodBias {
+    /// Anywhere free, evenly.
+    #[default]
+    Off,
+    /// Toward the edges of the board and any walls on it, `per_point` more
+    /// strongly for every point scored, up to full strength at 1.0.
+    NearWalls { per_point: f32 },
+    /// Away from the head, `per_point` more strongly for every point scored,
+    /// up to full strength at 1.0.
+    FarFromHead { per_point: f32 },
+}
+
+impl FoodBias {
+    fn strength(&self, score: u32) -> f32 {
+        let per_point = match self {
+            Self::Off => 0.0,
+            Self::NearWalls { per_point } | Self::FarFromHead { per_point } => *per_point,
+        };
+
+        (per_point * score as f32).clamp(0.0, 1.0)
+    }
+
+    /// How risky `position` is, from 0 for the safest cell on the board to 1
+    /// for the riskiest.
+    fn risk(&self, position: Position, walls: &HashSet<Position>, head: Option<Position>) -> f32 {
+        match self {
+            Self::Off => 0.0,
+            Self::NearWalls { .. } => {
+                let to_edge = position
+                    .x
+                    .min(position.y)
+                    .min(GRID_WIDTH as i32 - 1 - position.x)
+                    .min(GRID_HEIGHT as i32 - 1 - position.y);
+                let to_wall = walls
+                    .iter()
+                    .map(|wall| (wall.x - position.x).abs().max((wall.y - position.y).abs()) - 1)
+                    .min()
+                    .unwrap_or(i32::MAX);
+                let furthest = (GRID_WIDTH.min(GRID_HEIGHT) as i32 - 1) / 2;
+
+                1.0 - to_edge.min(to_wall) as f32 / furthest as f32
+            }
+            Self::FarFromHead { .. } => {
+                let head = match head {
+                    Some(head) => head,
+                    None => return 0.0,
+                };
+                let distance = (head.x - position.x).abs() + (head.y - position.y).abs();
+                let furthest = GRID_WIDTH + GRID_HEIGHT - 2;
+
+                distance as f32 / furthest as f32
+            }
+        }
+    }
+}
+
+/// Picks a cell that isn't in `occupied` for the next piece of food. Every
+/// free cell is in the draw, weighted by `bias` for the score so far.
+/// Returns `None` if the board is full.
+pub fn biased_free_position(
+    rng: &mut impl Rng,
+    bias: &FoodBias,
+    score: u32,
+    occupied: &HashSet<Position>,
+    walls: &HashSet<Position>,
+    head: Option<Position>,
+) -> Option<Position> {
+    let strength = bias.strength(score);
+
+    let weighted: Vec<(Position, f32)> = (0..GRID_HEIGHT as i32)
+        .flat_map(|y| (0..GRID_WIDTH as i32).map(move |x| Position::new(x, y)))
+        .filter(|position| !occupied.contains(position))
+        .map(|position| {
+            let risk = bias.risk(position, walls, head);
+            (position, 1.0 + strength * (MAX_WEIGHT - 1.0) * risk)
+        })
+        .collect();
+
+    let total: f32 = weighted.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen::<f32>() * total;
+
+    for (position, weight) in &weighted {
+        if roll < *weight {
+            return Some(*position);
+        }
+        roll -= weight;
+    }
+
+    // Rounding can leave the roll a hair past the end.
+    weighted.last().map(|(position, _)| *position)
+}
@@ -0,0 +1,92 @@
+//! Gates: level tiles that open and close on a beat. A `Gate(n)` starts
+//! open, shuts after `n` ticks, opens again `n` ticks after that, and so on.
+//! Shut, it's a [`Wall`] like any other. It flashes for the last few ticks
+//! before it shuts, and holds off shutting on any part of the snake.
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::obstacles::Wall;
+use crate::{Position, Size, SnakePart};
+
+const GATE_OPEN_COLOR: Color = Color::rgba(0.6, 0.45, 0.8, 0.25);
+const GATE_SHUT_COLOR: Color = Color::rgb(0.6, 0.45, 0.8);
+const GATE_WARNING_COLOR: Color = Color::rgb(0.95, 0.3, 0.3);
+/// How many ticks before shutting a gate starts to flash.
+const GATE_WARNING_TICKS: u32 = 3;
+/// Ticks a gate painted in the editor stays open or shut for.
+pub const DEFAULT_GATE_TICKS: u8 = 8;
+
+#[derive(Component)]
+pub struct Gate {
+    every: u32,
+    ticks: u32,
+}
+
+impl Gate {
+    /// Whether the gate should be shut, going by the beat alone.
+    fn due_shut(&self) -> bool {
+        self.ticks % (self.every * 2) >= self.every
+    }
+
+    fn ticks_until_shut(&self) -> u32 {
+        self.every - self.ticks % (self.every * 2)
+    }
+}
+
+pub fn spawn_gate(commands: &mut Commands, position: Position, every: u8) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: GATE_OPEN_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Gate {
+            every: every.max(1) as u32,
+            ticks: 0,
+        })
+        .insert(position)
+        .insert(Size::square(1.0))
+        .insert(Layer::Board.at(2.0))
+        .id()
+}
+
+/// Moves every gate on a tick, shutting or opening it on the beat and
+/// flashing it on the way to shutting.
+pub fn cycle_gates(
+    mut commands: Commands,
+    mut gates: Query<(Entity, &mut Gate, &Position, &mut Sprite, Option<&Wall>)>,
+    parts: Query<&Position, With<SnakePart>>,
+) {
+    for (ent, mut gate, position, mut sprite, wall) in gates.iter_mut() {
+        let shut = wall.is_some();
+        let blocked = parts.iter().any(|part| part == position);
+
+        // Held where it is while the snake is in the way, to try again on
+        // the next tick.
+        if !(gate.due_shut() && !shut && blocked) {
+            gate.ticks += 1;
+        }
+
+        match (gate.due_shut(), shut) {
+            (true, false) if !blocked => {
+                commands.entity(ent).insert(Wall);
+                sprite.color = GATE_SHUT_COLOR;
+            }
+            (false, true) => {
+                commands.entity(ent).remove::<Wall>();
+                sprite.color = GATE_OPEN_COLOR;
+            }
+            (false, false) if gate.ticks_until_shut() <= GATE_WARNING_TICKS => {
+                sprite.color = if gate.ticks % 2 == 0 {
+                    GATE_WARNING_COLOR
+                } else {
+                    GATE_OPEN_COLOR
+                };
+            }
+            _ => (),
+        }
+    }
+}
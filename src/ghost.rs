@@ -0,0 +1,149 @@
+//! Race the ghost, turned on with `--ghost`. The window splits into two
+//! boards: the run being played on the left, and the one before it played
+//! back on the right, tick for tick, so it's easy to see which is ahead. The
+//! ghost is drawn from the board [`Observation`] taken on every tick, and
+//! holds on its last tick once it runs out.
+
+use bevy::prelude::*;
+
+use crate::boards::{BoardId, BoardLayout};
+use crate::layers::Layer;
+use crate::mini_replay::cell_color;
+use crate::observation::{Observation, ObservationEvent};
+use crate::{NewRunEvent, Position, Size};
+
+const GHOST_BOARD: BoardId = BoardId(1);
+const GHOST_ALPHA: f32 = 0.6;
+const LABEL_Y: f32 = 270.0;
+const LABEL_FONT_SIZE: f32 = 24.0;
+
+#[derive(Default)]
+struct Ghost {
+    enabled: bool,
+    /// The run being played, a board for each tick so far.
+    recording: Vec<Observation>,
+    /// The run before, being played back.
+    previous: Vec<Observation>,
+    tick: usize,
+}
+
+impl Ghost {
+    fn from_args() -> Self {
+        Self {
+            enabled: std::env::args().any(|arg| arg == "--ghost"),
+            ..default()
+        }
+    }
+}
+
+#[derive(Component)]
+struct GhostCell;
+
+fn setup_ghost_board(
+    mut commands: Commands,
+    ghost: Res<Ghost>,
+    asset_server: Res<AssetServer>,
+    mut layout: ResMut<BoardLayout>,
+) {
+    if !ghost.enabled {
+        return;
+    }
+
+    layout.boards = 2;
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Last run",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: LABEL_FONT_SIZE,
+                    color: Color::rgba(1.0, 1.0, 1.0, GHOST_ALPHA),
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_translation(
+                (GHOST_BOARD.origin() + Vec2::new(0.0, LABEL_Y)).extend(0.0),
+            ),
+            ..default()
+        })
+        .insert(Layer::Hud.at(1.0));
+}
+
+/// Keeps this run's boards, and draws the last run's for the same tick on
+/// the ghost board. A new run takes over the one just played as the ghost.
+fn race_ghost(
+    mut commands: Commands,
+    mut ghost: ResMut<Ghost>,
+    mut new_runs: EventReader<NewRunEvent>,
+    mut observations: EventReader<ObservationEvent>,
+    cells: Query<Entity, With<GhostCell>>,
+) {
+    if !ghost.enabled {
+        return;
+    }
+
+    if new_runs.iter().next().is_some() {
+        if !ghost.recording.is_empty() {
+            ghost.previous = std::mem::take(&mut ghost.recording);
+        }
+        ghost.tick = 0;
+
+        for cell in cells.iter() {
+            commands.entity(cell).despawn();
+        }
+    }
+
+    let ticks = ghost.tick;
+    for ObservationEvent(board) in observations.iter() {
+        ghost.recording.push(board.clone());
+        ghost.tick += 1;
+    }
+
+    if ghost.tick == ticks {
+        return;
+    }
+
+    let frame = match ghost.previous.get(ghost.tick - 1).or(ghost.previous.last()) {
+        Some(frame) => frame,
+        None => return,
+    };
+
+    for cell in cells.iter() {
+        commands.entity(cell).despawn();
+    }
+
+    for (i, cell) in frame.cells.iter().enumerate() {
+        let mut color = match cell_color(*cell) {
+            Some(color) => color,
+            None => continue,
+        };
+        color.set_a(GHOST_ALPHA);
+
+        let (x, y) = (i as u32 % frame.width, i as u32 / frame.width);
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite { color, ..default() },
+                ..default()
+            })
+            .insert(GhostCell)
+            .insert(GHOST_BOARD)
+            .insert(Position::new(x as i32, y as i32))
+            .insert(Size::square(0.8))
+            .insert(Layer::Entities.at(6.0));
+    }
+}
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Ghost::from_args())
+            .add_startup_system(setup_ghost_board)
+            .add_system(race_ghost);
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::sprite::MaterialMesh2dBundle;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::viewport::board_size;
+use crate::{GRID_HEIGHT, GRID_WIDTH};
+
+const GRID_LINE_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.08);
+
+#[derive(Component)]
+struct GridLines;
+
+/// Every cell boundary as one line list, so the whole overlay is a single
+/// draw rather than a sprite per line.
+fn grid_mesh(width: f32, height: f32) -> Mesh {
+    let (left, bottom) = (-width / 2.0, -height / 2.0);
+    let (cell_w, cell_h) = (width / GRID_WIDTH as f32, height / GRID_HEIGHT as f32);
+
+    let mut positions = Vec::new();
+
+    for x in 0..=GRID_WIDTH {
+        let x = left + x as f32 * cell_w;
+        positions.push([x, bottom, 0.0]);
+        positions.push([x, -bottom, 0.0]);
+    }
+
+    for y in 0..=GRID_HEIGHT {
+        let y = bottom + y as f32 * cell_h;
+        positions.push([left, y, 0.0]);
+        positions.push([-left, y, 0.0]);
+    }
+
+    // The 2d mesh pipeline expects normals and uvs, even if they're unused.
+    let count = positions.len();
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+    mesh.set_indices(Some(Indices::U32((0..count as u32).collect())));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; count]);
+    mesh
+}
+
+fn setup_grid_lines(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let board = board_size();
+
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: meshes.add(grid_mesh(board.x, board.y)).into(),
+            material: materials.add(ColorMaterial::from(GRID_LINE_COLOR)),
+            visibility: Visibility {
+                is_visible: settings.video.grid_lines,
+            },
+            ..default()
+        })
+        .insert(GridLines)
+        .insert(Layer::Board.at(50.0));
+}
+
+fn toggle_grid_lines(settings: Res<Settings>, mut query: Query<&mut Visibility, With<GridLines>>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = settings.video.grid_lines;
+    }
+}
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_grid_lines)
+            .add_system(toggle_grid_lines);
+    }
+}
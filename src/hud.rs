@@ -0,0 +1,163 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+
+const NUMBER_TWEEN_SECONDS: f32 = 0.3;
+/// How much bigger an [`AnimatedNumber`] gets at the height of its pop.
+const NUMBER_POP: f32 = 0.25;
+
+/// A horizontal meter drawn in screen space. The fill shrinks towards the
+/// left edge and switches to `low_color` below a quarter full.
+#[derive(Component, Clone, Copy)]
+pub struct HudBar {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: Color,
+    pub low_color: Color,
+}
+
+/// How full a [`HudBar`] is, from 0.0 to 1.0.
+#[derive(Component)]
+pub struct HudBarFill(pub f32);
+
+const HUD_BAR_BG_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+
+/// Spawns the background and fill sprites for a bar. Both carry `marker`, so
+/// the owning feature can find them to set the fill or toggle visibility.
+pub fn spawn_hud_bar<M: Component + Clone>(commands: &mut Commands, bar: HudBar, marker: M) {
+    let transform = Transform::from_xyz(bar.x, bar.y, 0.0);
+    let size = Some(Vec2::new(bar.width, bar.height));
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: HUD_BAR_BG_COLOR,
+                custom_size: size,
+                ..default()
+            },
+            transform,
+            ..default()
+        })
+        .insert(marker.clone())
+        .insert(bar)
+        .insert(Layer::Hud.at(1.0));
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: bar.color,
+                custom_size: size,
+                ..default()
+            },
+            transform,
+            ..default()
+        })
+        .insert(marker)
+        .insert(bar)
+        .insert(HudBarFill(1.0))
+        .insert(Layer::Hud.at(1.5));
+}
+
+fn draw_hud_bars(
+    mut query: Query<(&HudBar, &HudBarFill, &mut Sprite, &mut Transform), Changed<HudBarFill>>,
+) {
+    for (bar, fill, mut sprite, mut transform) in query.iter_mut() {
+        let fraction = fill.0.clamp(0.0, 1.0);
+        let width = bar.width * fraction;
+
+        sprite.custom_size = Some(Vec2::new(width, bar.height));
+        sprite.color = if fraction < 0.25 {
+            bar.low_color
+        } else {
+            bar.color
+        };
+
+        transform.translation.x = bar.x - (bar.width - width) / 2.0;
+    }
+}
+
+/// A number shown after `label` in a text's first section. When it goes up,
+/// the text ticks through the numbers in between and pops up in size for a
+/// moment, rather than snapping to the new value.
+#[derive(Component)]
+pub struct AnimatedNumber {
+    label: &'static str,
+    from: u32,
+    target: u32,
+    timer: Timer,
+}
+
+impl AnimatedNumber {
+    pub fn new(label: &'static str) -> Self {
+        let mut timer = Timer::from_seconds(NUMBER_TWEEN_SECONDS, false);
+        timer.tick(timer.duration());
+
+        Self {
+            label,
+            from: 0,
+            target: 0,
+            timer,
+        }
+    }
+
+    /// Going down, e.g. back to zero for a new run, snaps straight there.
+    pub fn set(&mut self, value: u32) {
+        if value == self.target {
+            return;
+        }
+
+        self.from = if value > self.target {
+            self.shown()
+        } else {
+            value
+        };
+        self.target = value;
+        self.timer.reset();
+    }
+
+    fn shown(&self) -> u32 {
+        let t = self.timer.percent();
+        (self.from as f32 + (self.target - self.from) as f32 * t).round() as u32
+    }
+}
+
+fn animate_numbers(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut query: Query<(&mut AnimatedNumber, &mut Text, &mut Transform)>,
+) {
+    for (mut number, mut text, mut transform) in query.iter_mut() {
+        if settings.accessibility.reduced_motion {
+            let duration = number.timer.duration();
+            number.timer.set_elapsed(duration);
+        } else {
+            number.timer.tick(time.delta());
+        }
+
+        let value = format!("{}{}", number.label, number.shown());
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+
+        let pop = if number.timer.finished() {
+            1.0
+        } else {
+            1.0 + NUMBER_POP * (number.timer.percent() * PI).sin()
+        };
+        transform.scale = Vec3::new(pop, pop, 1.0);
+    }
+}
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, draw_hud_bars)
+            .add_system(animate_numbers);
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::hud::{spawn_hud_bar, HudBar, HudBarFill};
+use crate::settings::GameplaySettings;
+use crate::{GameStatus, SnakeBody};
+
+const HUNGER_MAX: f32 = 100.0;
+const HUNGER_DRAIN_PER_TICK: f32 = 0.8;
+const HUNGER_REFILL: f32 = 45.0;
+const HUNGER_AFTER_STARVING: f32 = 35.0;
+const HUNGER_BAR: HudBar = HudBar {
+    x: -250.0,
+    y: 275.0,
+    width: 150.0,
+    height: 12.0,
+    color: Color::rgb(0.9, 0.6, 0.1),
+    low_color: Color::rgb(0.8, 0.1, 0.1),
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Hunger(f32);
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self(HUNGER_MAX)
+    }
+}
+
+impl Hunger {
+    pub fn feed(&mut self) {
+        self.0 = (self.0 + HUNGER_REFILL).min(HUNGER_MAX);
+    }
+
+    fn fraction(&self) -> f32 {
+        self.0 / HUNGER_MAX
+    }
+}
+
+#[derive(Component, Clone)]
+struct HungerBar;
+
+fn setup_hunger_bar(mut commands: Commands) {
+    spawn_hud_bar(&mut commands, HUNGER_BAR, HungerBar);
+}
+
+/// Drains hunger once per movement tick. Starving costs a tail segment, and
+/// starving with nothing left but the head ends the game.
+pub fn hunger_drain(
+    mut commands: Commands,
+    settings: Res<GameplaySettings>,
+    mut hunger: ResMut<Hunger>,
+    mut body: ResMut<SnakeBody>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    if !settings.hunger {
+        return;
+    }
+
+    hunger.0 -= HUNGER_DRAIN_PER_TICK;
+    if hunger.0 > 0.0 {
+        return;
+    }
+
+    if body.0.len() > 1 {
+        let tail = body.0.pop().unwrap();
+        commands.entity(tail).despawn();
+        hunger.0 = HUNGER_AFTER_STARVING;
+    } else if let Some(mut status) = game_status.iter_mut().next() {
+        status.end(GameStatus::Lost);
+    }
+}
+
+fn update_hunger_bar(
+    settings: Res<GameplaySettings>,
+    hunger: Res<Hunger>,
+    mut bars: Query<&mut Visibility, With<HungerBar>>,
+    mut fills: Query<&mut HudBarFill, With<HungerBar>>,
+) {
+    if !settings.is_changed() && !hunger.is_changed() {
+        return;
+    }
+
+    for mut visibility in bars.iter_mut() {
+        visibility.is_visible = settings.hunger;
+    }
+
+    for mut fill in fills.iter_mut() {
+        fill.0 = hunger.fraction();
+    }
+}
+
+pub struct HungerPlugin;
+
+impl Plugin for HungerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hunger>()
+            .add_startup_system(setup_hunger_bar)
+            .add_system(update_hunger_bar);
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::cheats::Cheats;
+use crate::layers::Layer;
+use crate::obstacles::Wall;
+use crate::{
+    DevouredFood, Direction, GameStatus, Position, Size, SnakeHead, SnakePart, GRID_HEIGHT,
+    GRID_WIDTH,
+};
+
+const HUNTER_COLOR: Color = Color::rgb(0.6, 0.2, 0.8);
+const HUNTER_SPAWN_SCORE: u32 = 20;
+
+/// An enemy that chases the snake head, moving on every other movement tick.
+#[derive(Component, Default)]
+pub struct Hunter {
+    rested: bool,
+}
+
+pub fn hunter_spawner(
+    mut commands: Commands,
+    devoured: Query<&DevouredFood>,
+    hunters: Query<Entity, With<Hunter>>,
+    heads: Query<&Position, With<SnakeHead>>,
+) {
+    if !hunters.is_empty() {
+        return;
+    }
+
+    let passed_threshold = devoured.iter().any(|count| count.0 >= HUNTER_SPAWN_SCORE);
+
+    if let (true, Some(head)) = (passed_threshold, heads.iter().next()) {
+        // Start as far from the head as the board allows.
+        let position = Position::new(
+            (head.x + GRID_WIDTH as i32 / 2) % GRID_WIDTH as i32,
+            (head.y + GRID_HEIGHT as i32 / 2) % GRID_HEIGHT as i32,
+        );
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: HUNTER_COLOR,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Hunter::default())
+            .insert(position)
+            .insert(Size::square(0.8))
+            .insert(Layer::Entities.at(5.0));
+    }
+}
+
+/// Breadth first search across the wrapping grid, returning the first cell on
+/// a shortest path from `from` to `to` that avoids `blocked` cells.
+fn next_step(from: Position, to: Position, blocked: &HashSet<Position>) -> Option<Position> {
+    let mut came_from = HashMap::from([(from, from)]);
+    let mut frontier = VecDeque::from([from]);
+
+    while let Some(current) = frontier.pop_front() {
+        if current == to {
+            let mut step = current;
+            while came_from[&step] != from {
+                step = came_from[&step];
+            }
+
+            return Some(step);
+        }
+
+        for direction in [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ] {
+            let next = current.step(direction);
+            if (next == to || !blocked.contains(&next)) && !came_from.contains_key(&next) {
+                came_from.insert(next, current);
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+pub fn hunter_movement(
+    mut hunters: Query<(&mut Hunter, &mut Position), Without<SnakePart>>,
+    heads: Query<&Position, (With<SnakeHead>, Without<Hunter>)>,
+    parts: Query<&Position, (With<SnakePart>, Without<SnakeHead>)>,
+    walls: Query<&Position, (With<Wall>, Without<Hunter>)>,
+    cheats: Res<Cheats>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    let body: HashSet<Position> = parts.iter().copied().collect();
+    let blocked: HashSet<Position> = body.iter().chain(walls.iter()).copied().collect();
+
+    for (mut hunter, mut position) in hunters.iter_mut() {
+        hunter.rested = !hunter.rested;
+
+        if !hunter.rested {
+            if let Some(step) = next_step(*position, head, &blocked) {
+                *position = step;
+            }
+        }
+
+        if (*position == head || body.contains(&position)) && !cheats.invincible {
+            if let Some(mut status) = game_status.iter_mut().next() {
+                status.end(GameStatus::Lost);
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! An overlay of the last few turns pressed, with the time between them, for
+//! streams and recordings. Toggled with `I`.
+
+use std::collections::VecDeque;
+
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ElementState;
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::{AppState, Direction, WIN_HEIGHT, WIN_WIDTH};
+
+const SHOWN_INPUTS: usize = 6;
+const INPUT_DISPLAY_PADDING: f32 = 10.0;
+const INPUT_FONT_SIZE: f32 = 22.0;
+const INPUT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+/// Older presses fade towards this alpha.
+const OLDEST_ALPHA: f32 = 0.3;
+
+/// The latest turns pressed, newest first, with the time each came in.
+#[derive(Default)]
+struct InputHistory(VecDeque<(Direction, f64)>);
+
+#[derive(Component)]
+struct InputDisplay;
+
+fn arrow(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Left => "\u{2190}",
+        Direction::Up => "\u{2191}",
+        Direction::Right => "\u{2192}",
+        Direction::Down => "\u{2193}",
+    }
+}
+
+fn setup_input_display(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: INPUT_FONT_SIZE,
+        color: INPUT_COLOR,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text {
+                sections: (0..SHOWN_INPUTS)
+                    .map(|_| TextSection {
+                        value: String::new(),
+                        style: style.clone(),
+                    })
+                    .collect(),
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Bottom,
+                    horizontal: HorizontalAlign::Right,
+                },
+            },
+            transform: Transform::from_xyz(
+                WIN_WIDTH / 2.0 - INPUT_DISPLAY_PADDING,
+                -WIN_HEIGHT / 2.0 + INPUT_DISPLAY_PADDING,
+                0.0,
+            ),
+            ..default()
+        })
+        .insert(InputDisplay)
+        .insert(Layer::Overlay.at(3.0));
+}
+
+/// Picks up turns the same way the snake does, so what's shown matches
+/// what it was told.
+fn record_inputs(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut history: ResMut<InputHistory>,
+) {
+    let presses: Vec<Direction> = key_events
+        .iter()
+        .filter(|event| event.state == ElementState::Pressed)
+        .filter_map(|event| event.key_code)
+        .filter(|key| keyboard_input.just_pressed(*key))
+        .filter_map(|key| settings.controls.direction(key))
+        .collect();
+
+    if captured.0 || presses.is_empty() {
+        return;
+    }
+
+    for direction in presses {
+        history
+            .0
+            .push_front((direction, time.seconds_since_startup()));
+    }
+
+    history.0.truncate(SHOWN_INPUTS);
+}
+
+fn draw_input_display(
+    settings: Res<Settings>,
+    history: Res<InputHistory>,
+    mut query: Query<(&mut Text, &mut Visibility), With<InputDisplay>>,
+) {
+    if !settings.is_changed() && !history.is_changed() {
+        return;
+    }
+
+    for (mut text, mut visibility) in query.iter_mut() {
+        visibility.is_visible = settings.video.input_display;
+
+        for (i, section) in text.sections.iter_mut().enumerate() {
+            // Each press shows how long after the one before it came in.
+            section.value = match (history.0.get(i), history.0.get(i + 1)) {
+                (Some((direction, at)), Some((_, before))) => {
+                    format!("+{:.2}s {}\n", at - before, arrow(*direction))
+                }
+                (Some((direction, _)), None) => format!("{}\n", arrow(*direction)),
+                _ => String::new(),
+            };
+
+            let age = i as f32 / (SHOWN_INPUTS - 1) as f32;
+            section.style.color.set_a(1.0 - age * (1.0 - OLDEST_ALPHA));
+        }
+    }
+}
+
+pub struct InputDisplayPlugin;
+
+impl Plugin for InputDisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputHistory>()
+            .add_startup_system(setup_input_display)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(record_inputs))
+            .add_system(draw_input_display);
+    }
+}
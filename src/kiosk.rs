@@ -0,0 +1,362 @@
+//! Kiosk mode for demo machines, turned on with `--kiosk`. The game runs
+//! fullscreen and can't be quit from inside it. Left alone outside a run, it
+//! starts playing itself: a demo run steered towards the food, then a table
+//! of the best saved profiles, then another demo run, until a key is pressed.
+
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+
+use crate::cleanup::DespawnOnStateExit;
+use crate::layers::Layer;
+use crate::level::{ActiveLevel, Level};
+use crate::mode::GameMode;
+use crate::profile::Profiles;
+use crate::stats::Stats;
+use crate::transition::{ScreenFade, TransitionEvent};
+use crate::{
+    AppState, Direction, Food, NewRunEvent, Occupancy, Position, RunKind, SnakeHead, GRID_HEIGHT,
+    GRID_WIDTH, WIN_HEIGHT, WIN_WIDTH,
+};
+
+/// Seconds without a key or click, outside a run, before the demo starts.
+const IDLE_SECONDS: f32 = 60.0;
+/// Seconds a finished demo run stays up before the table replaces it.
+const GAME_OVER_SECONDS: f32 = 3.0;
+const TABLE_SECONDS: f32 = 8.0;
+const TABLE_ROWS: usize = 5;
+const TABLE_BACKDROP: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+const TABLE_FONT_SIZE: f32 = 32.0;
+const BANNER_Y: f32 = -200.0;
+const BANNER_FONT_SIZE: f32 = 28.0;
+
+pub struct Kiosk {
+    pub enabled: bool,
+    /// Set while the game is playing itself, so the run doesn't count.
+    pub attract: bool,
+    idle: Timer,
+    /// Seconds the current demo run has been over for.
+    over_for: f32,
+    /// Which of the table's pages is shown next.
+    page: usize,
+}
+
+impl Kiosk {
+    fn from_args() -> Self {
+        Self {
+            enabled: std::env::args().any(|arg| arg == "--kiosk"),
+            attract: false,
+            idle: Timer::from_seconds(IDLE_SECONDS, false),
+            over_for: 0.0,
+            page: 0,
+        }
+    }
+}
+
+/// One way of ranking the profiles in the high score table.
+#[derive(Clone, Copy)]
+enum TablePage {
+    BestScore,
+    Wins,
+    FoodEaten,
+}
+
+impl TablePage {
+    const ALL: [Self; 3] = [Self::BestScore, Self::Wins, Self::FoodEaten];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::BestScore => "High Scores",
+            Self::Wins => "Most Wins",
+            Self::FoodEaten => "Most Food Eaten",
+        }
+    }
+
+    fn value(&self, stats: &Stats) -> u32 {
+        match self {
+            Self::BestScore => stats.best_score,
+            Self::Wins => stats.wins,
+            Self::FoodEaten => stats.food_eaten,
+        }
+    }
+}
+
+#[derive(Component)]
+struct HighScoreTable;
+
+#[derive(Component)]
+struct AttractBanner;
+
+fn enter_fullscreen(kiosk: Res<Kiosk>, mut windows: ResMut<Windows>) {
+    if kiosk.enabled {
+        if let Some(window) = windows.get_primary_mut() {
+            window.set_mode(WindowMode::BorderlessFullscreen);
+        }
+    }
+}
+
+fn setup_attract_banner(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Press any key to play",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: BANNER_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, BANNER_Y, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(AttractBanner)
+        .insert(Layer::Overlay.at(5.0));
+}
+
+/// Starts a demo run: classic rules on a plain board, whatever was picked on
+/// the menu before.
+fn start_attract(
+    kiosk: &mut Kiosk,
+    game_mode: &mut GameMode,
+    run_kind: &mut RunKind,
+    active_level: &mut ActiveLevel,
+    new_run: &mut EventWriter<NewRunEvent>,
+    transitions: &mut EventWriter<TransitionEvent>,
+) {
+    kiosk.attract = true;
+    kiosk.over_for = 0.0;
+    *game_mode = GameMode::Classic;
+    *run_kind = RunKind::Free;
+    active_level.0 = Level::for_mode(GameMode::Classic);
+
+    new_run.send(NewRunEvent);
+    transitions.send(TransitionEvent(AppState::Playing));
+}
+
+/// Counts down to the demo while nobody's touching anything, and hands the
+/// game back to the menu as soon as someone does.
+#[allow(clippy::too_many_arguments)]
+fn watch_idle(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    app_state: Res<State<AppState>>,
+    fade: Res<ScreenFade>,
+    mut kiosk: ResMut<Kiosk>,
+    mut game_mode: ResMut<GameMode>,
+    mut run_kind: ResMut<RunKind>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut new_run: EventWriter<NewRunEvent>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if !kiosk.enabled || fade.is_active() {
+        return;
+    }
+
+    let touched = keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some();
+
+    if touched || *app_state.current() == AppState::Playing {
+        kiosk.idle.reset();
+
+        if touched && kiosk.attract {
+            kiosk.attract = false;
+            transitions.send(TransitionEvent(AppState::MainMenu));
+        }
+
+        return;
+    }
+
+    if !kiosk.attract && kiosk.idle.tick(time.delta()).just_finished() {
+        kiosk.idle.reset();
+        start_attract(
+            &mut kiosk,
+            &mut game_mode,
+            &mut run_kind,
+            &mut active_level,
+            &mut new_run,
+            &mut transitions,
+        );
+    }
+}
+
+/// Steers the demo run: towards the nearest food, the shortest way round the
+/// wrapping board, without turning into the snake or a wall where it can
+/// help it. Runs each movement tick, before the snake moves.
+pub fn autopilot(
+    kiosk: Res<Kiosk>,
+    occupancy: Res<Occupancy>,
+    food: Query<&Position, With<Food>>,
+    mut heads: Query<(&Position, &mut SnakeHead)>,
+) {
+    if !kiosk.attract {
+        return;
+    }
+
+    let (head_pos, mut head) = match heads.iter_mut().next() {
+        Some(head) => head,
+        None => return,
+    };
+
+    let wrapped = |a: i32, b: i32, size: u32| {
+        let d = (a - b).unsigned_abs();
+        d.min(size - d)
+    };
+    let distance = |pos: Position| {
+        food.iter()
+            .map(|f| wrapped(pos.x, f.x, GRID_WIDTH) + wrapped(pos.y, f.y, GRID_HEIGHT))
+            .min()
+            .unwrap_or(0)
+    };
+
+    // Carrying straight on comes first, so it wins any tie.
+    let choices = [
+        head.moved,
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+    let best = choices
+        .into_iter()
+        .filter(|dir| *dir != -head.moved)
+        .min_by_key(|dir| {
+            let next = head_pos.step(*dir);
+            (occupancy.0.contains_key(&next), distance(next))
+        });
+
+    if let Some(dir) = best {
+        head.turns.clear();
+        head.turns.push_back(dir);
+    }
+}
+
+fn show_attract_banner(
+    kiosk: Res<Kiosk>,
+    app_state: Res<State<AppState>>,
+    mut query: Query<&mut Visibility, With<AttractBanner>>,
+) {
+    let visible = kiosk.attract && *app_state.current() == AppState::Playing;
+
+    for mut visibility in query.iter_mut() {
+        if visibility.is_visible != visible {
+            visibility.is_visible = visible;
+        }
+    }
+}
+
+/// Once a demo run ends, holds on it for a moment, puts up the table, then
+/// starts the next one.
+#[allow(clippy::too_many_arguments)]
+fn attract_cycle(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    profiles: Res<Profiles>,
+    stats: Res<Stats>,
+    fade: Res<ScreenFade>,
+    mut kiosk: ResMut<Kiosk>,
+    mut game_mode: ResMut<GameMode>,
+    mut run_kind: ResMut<RunKind>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut new_run: EventWriter<NewRunEvent>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if !kiosk.attract || fade.is_active() {
+        return;
+    }
+
+    let before = kiosk.over_for;
+    kiosk.over_for += time.delta_seconds();
+
+    if before < GAME_OVER_SECONDS && kiosk.over_for >= GAME_OVER_SECONDS {
+        let page = TablePage::ALL[kiosk.page % TablePage::ALL.len()];
+        kiosk.page += 1;
+        spawn_table(&mut commands, &asset_server, &profiles, &stats, page);
+    }
+
+    if kiosk.over_for >= GAME_OVER_SECONDS + TABLE_SECONDS {
+        start_attract(
+            &mut kiosk,
+            &mut game_mode,
+            &mut run_kind,
+            &mut active_level,
+            &mut new_run,
+            &mut transitions,
+        );
+    }
+}
+
+/// The top saved profiles for `page`. The active profile's stats are taken
+/// live, since its save can be a little behind.
+fn spawn_table(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    profiles: &Profiles,
+    stats: &Stats,
+    page: TablePage,
+) {
+    let mut rows: Vec<(String, u32)> = profiles
+        .saved_stats()
+        .into_iter()
+        .filter(|(name, _)| name != profiles.active())
+        .map(|(name, stats)| (name, page.value(&stats)))
+        .chain([(profiles.active().to_string(), page.value(stats))])
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut table = format!("{}\n\n", page.title());
+    for (i, (name, value)) in rows.iter().take(TABLE_ROWS).enumerate() {
+        table.push_str(&format!("{}. {}  {}\n", i + 1, name, value));
+    }
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: TABLE_BACKDROP,
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(HighScoreTable)
+        .insert(DespawnOnStateExit(AppState::GameOver))
+        .insert(Layer::Overlay.at(3.0));
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                table,
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: TABLE_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            ..default()
+        })
+        .insert(HighScoreTable)
+        .insert(DespawnOnStateExit(AppState::GameOver))
+        .insert(Layer::Overlay.at(4.0));
+}
+
+pub struct KioskPlugin;
+
+impl Plugin for KioskPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Kiosk::from_args())
+            .add_startup_system(enter_fullscreen)
+            .add_startup_system(setup_attract_banner)
+            .add_system(watch_idle)
+            .add_system(show_attract_banner)
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(attract_cycle));
+    }
+}
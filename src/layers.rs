@@ -0,0 +1,65 @@
+//! Draw order. Everything drawn sits in one of a few layers, back to front:
+//! the board and what's painted on it, the things moving around over it, the
+//! HUD, and overlays like menus and fades that cover the lot. Within a layer,
+//! a higher order draws on top.
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+/// How much room each layer has for ordering things within it.
+const LAYER_DEPTH: f32 = 100.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Board,
+    Entities,
+    Hud,
+    Overlay,
+}
+
+impl Layer {
+    /// A place in this layer, from 0 at the back up to just under 100.
+    pub fn at(self, order: f32) -> DrawOrder {
+        DrawOrder { layer: self, order }
+    }
+}
+
+/// Where an entity is drawn, set as the z of its translation whenever it
+/// changes. The systems that place things on the board only ever move them
+/// in x and y, so it holds from then on.
+#[derive(Component, Clone, Copy)]
+pub struct DrawOrder {
+    pub layer: Layer,
+    pub order: f32,
+}
+
+impl DrawOrder {
+    fn z(&self) -> f32 {
+        self.layer as u8 as f32 * LAYER_DEPTH + self.order
+    }
+}
+
+/// A child's z is on top of its parent's, so it's set relative to that.
+fn apply_draw_order(
+    mut query: Query<(&DrawOrder, &mut Transform, Option<&Parent>), Changed<DrawOrder>>,
+    orders: Query<&DrawOrder>,
+) {
+    for (order, mut transform, parent) in query.iter_mut() {
+        let under = parent
+            .and_then(|parent| orders.get(parent.0).ok())
+            .map_or(0.0, DrawOrder::z);
+
+        transform.translation.z = order.z() - under;
+    }
+}
+
+pub struct LayersPlugin;
+
+impl Plugin for LayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            apply_draw_order.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
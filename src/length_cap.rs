@@ -0,0 +1,63 @@
+//! Endless mode's length cap. The snake never grows past a set length: food
+//! eaten once it's there still grows it, but the segment that takes it over
+//! drops straight off the tail and is left behind as a pickup worth a couple
+//! of points, so long sessions never fill the board up.
+
+use bevy::prelude::*;
+
+use crate::cleanup::DespawnOnGameOver;
+use crate::layers::Layer;
+use crate::mode::GameMode;
+use crate::scoring::{ScoreBreakdown, ScoreSource};
+use crate::{Position, Size, SnakeBody, SnakeHead, SnakePart};
+
+const LENGTH_CAP: usize = 30;
+const DROPPED_TAIL_COLOR: Color = Color::rgb(0.55, 0.8, 0.3);
+const DROPPED_TAIL_POINTS: u32 = 2;
+
+/// A segment dropped off the tail, waiting to be picked up again.
+#[derive(Component)]
+pub struct DroppedTail;
+
+/// Drops whatever's past the cap off the end of the tail.
+pub fn cap_length(mut commands: Commands, game_mode: Res<GameMode>, mut body: ResMut<SnakeBody>) {
+    if *game_mode != GameMode::Endless || body.0.len() <= LENGTH_CAP {
+        return;
+    }
+
+    // The segments past the cap were only just grown, and aren't spawned
+    // until the end of the stage, so they're changed over with commands too.
+    for segment in body.0.split_off(LENGTH_CAP) {
+        commands
+            .entity(segment)
+            .remove::<SnakePart>()
+            .insert(DroppedTail)
+            .insert(Sprite {
+                color: DROPPED_TAIL_COLOR,
+                ..default()
+            })
+            .insert(DespawnOnGameOver)
+            .insert(Size::square(0.6))
+            .insert(Layer::Entities.at(1.0));
+    }
+}
+
+/// Scores a dropped segment when the head runs over it, without growing.
+pub fn dropped_tail_pickup(
+    mut commands: Commands,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    dropped: Query<(Entity, &Position), With<DroppedTail>>,
+    heads: Query<&Position, With<SnakeHead>>,
+) {
+    for head in heads.iter() {
+        for (ent, position) in dropped.iter() {
+            if position != head {
+                continue;
+            }
+
+            commands.entity(ent).despawn();
+
+            breakdown.award(ScoreSource::Bonus, DROPPED_TAIL_POINTS);
+        }
+    }
+}
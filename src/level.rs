@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use rand::Rng;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::maze::maze_layout;
+use crate::mirror::mirror_layout;
+use crate::mode::GameMode;
+use crate::obstacles::{spawn_tile, walls_layout, Tile};
+use crate::puzzle::first_level;
+use crate::{spawn_food, Position, RenderFoodEvent, GRID_HEIGHT, GRID_WIDTH};
+
+pub const LEVELS_DIR: &str = "levels";
+
+/// A board layout, stored on disk as RON. The built in modes generate theirs
+/// in code, and custom levels are loaded with `--level <path>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    #[serde(default)]
+    pub spawn: Option<Position>,
+    #[serde(default)]
+    pub tiles: Vec<(Position, Tile)>,
+    #[serde(default)]
+    pub food: Vec<Position>,
+    /// Food to be eaten in this order, see [`crate::chain`].
+    #[serde(default)]
+    pub chain: Vec<Position>,
+    /// The fewest moves the level can be cleared in, for puzzle mode.
+    #[serde(default)]
+    pub par: Option<u32>,
+    /// A scene under `assets` spawned on top of the board, see
+    /// [`crate::scenes`].
+    #[serde(default)]
+    pub scene: Option<String>,
+    /// The mode this board was generated for, rebuilt from the run's seed at
+    /// the start of every free run. Never saved, so a board loaded from a
+    /// file, code or snapshot stays as it is.
+    #[serde(skip)]
+    pub generated: Option<GameMode>,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Ron(ron::Error),
+    Code(String),
+    Invalid(String),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Ron(e) => write!(f, "{}", e),
+            Self::Code(e) => write!(f, "{}", e),
+            Self::Invalid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for LevelError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::Error> for LevelError {
+    fn from(e: ron::Error) -> Self {
+        Self::Ron(e)
+    }
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let text = fs::read_to_string(path)?;
+        let level: Self = ron::from_str(&text)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    /// Catches the mistakes a hand edited file can make that would otherwise
+    /// put things off the board or stack tiles on top of each other.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        let on_board = |pos: &Position| {
+            (0..GRID_WIDTH as i32).contains(&pos.x) && (0..GRID_HEIGHT as i32).contains(&pos.y)
+        };
+
+        let positions = self
+            .spawn
+            .iter()
+            .chain(self.food.iter())
+            .chain(self.chain.iter());
+        if let Some(pos) = positions
+            .chain(self.tiles.iter().map(|(pos, _)| pos))
+            .find(|pos| !on_board(pos))
+        {
+            let msg = format!("({}, {}) is off the board", pos.x, pos.y);
+            return Err(LevelError::Invalid(msg));
+        }
+
+        let mut occupied = HashSet::new();
+        if let Some((pos, _)) = self.tiles.iter().find(|(pos, _)| !occupied.insert(*pos)) {
+            let msg = format!("more than one tile at ({}, {})", pos.x, pos.y);
+            return Err(LevelError::Invalid(msg));
+        }
+
+        if self.par == Some(0) {
+            return Err(LevelError::Invalid("par has to be at least 1".to_string()));
+        }
+
+        if let Some(scene) = self.scene.as_ref() {
+            if !Path::new("assets").join(scene).exists() {
+                let msg = format!("no scene at assets/{}", scene);
+                return Err(LevelError::Invalid(msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LevelError> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Keep each tile on a single line, so files stay readable by hand.
+        let config = PrettyConfig::new().depth_limit(2);
+        let text = ron::ser::to_string_pretty(self, config)?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn for_mode(mode: GameMode) -> Self {
+        Self::for_mode_with(mode, &mut rand::thread_rng())
+    }
+
+    /// Like [`Level::for_mode`], drawing any randomness from `rng`.
+    pub fn for_mode_with(mode: GameMode, rng: &mut impl Rng) -> Self {
+        let tiles = match mode {
+            GameMode::Puzzle => return first_level(),
+            GameMode::Walls => walls_layout(),
+            GameMode::Maze => maze_layout(rng),
+            GameMode::Mirror => mirror_layout(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            name: format!("{:?}", mode),
+            tiles,
+            generated: Some(mode),
+            ..default()
+        }
+    }
+
+    pub fn spawn(&self, commands: &mut Commands, render_event: &mut EventWriter<RenderFoodEvent>) {
+        for (position, tile) in self.tiles.iter() {
+            spawn_tile(commands, *position, *tile);
+        }
+
+        for position in self.food.iter() {
+            spawn_food(commands, *position);
+            render_event.send(RenderFoodEvent);
+        }
+    }
+}
+
+/// The level the current board was built from.
+pub struct ActiveLevel(pub Level);
+
+impl ActiveLevel {
+    /// Loads the level passed with `--level <path>`, or generates the board
+    /// for the selected mode when there isn't one.
+    pub fn from_args() -> Self {
+        let path = std::env::args().skip_while(|arg| arg != "--level").nth(1);
+
+        if let Some(path) = path {
+            match Level::load(&path) {
+                Ok(level) => return Self(level),
+                Err(e) => eprintln!("Failed to load level '{}': {}", path, e),
+            }
+        }
+
+        Self(Level::for_mode(GameMode::from_args()))
+    }
+}
+
+fn spawn_board(
+    mut commands: Commands,
+    active_level: Res<ActiveLevel>,
+    mut render_event: EventWriter<RenderFoodEvent>,
+) {
+    active_level.0.spawn(&mut commands, &mut render_event);
+}
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system_to_stage(StartupStage::PreStartup, spawn_board);
+    }
+}
@@ -0,0 +1,312 @@
+//! Compact, copy-pasteable level codes.
+//!
+//! A code is a small binary encoding of a [`Level`], wrapped in URL safe
+//! base64. Runs of identical tiles along a row are merged, so walls and
+//! borders cost a handful of bytes instead of one entry per cell.
+
+use crate::doors::KeyColor;
+use crate::level::{Level, LevelError};
+use crate::obstacles::Tile;
+use crate::terrain::TileEffect;
+use crate::{Direction, Position, GRID_HEIGHT, GRID_WIDTH};
+
+/// Version 2 added the par. Version 1 codes, with an optional trailing
+/// chain and no par, still load.
+const CODE_VERSION: u8 = 2;
+const NO_SPAWN: u8 = u8::MAX;
+const NO_PAR: u16 = u16::MAX;
+
+fn tile_to_bytes(tile: Tile) -> [u8; 2] {
+    match tile {
+        Tile::Wall => [0, 0],
+        Tile::Breakable => [1, 0],
+        Tile::Door(color) => [2, color as u8],
+        Tile::Key(color) => [3, color as u8],
+        Tile::Terrain(TileEffect::Ice) => [4, 0],
+        Tile::Terrain(TileEffect::Mud) => [5, 0],
+        Tile::Terrain(TileEffect::Conveyor(dir)) => [6, dir as u8],
+        Tile::Terrain(TileEffect::Wind(dir)) => [9, dir as u8],
+        Tile::Portal(channel) => [7, channel],
+        Tile::Gate(every) => [8, every],
+    }
+}
+
+fn tile_from_bytes(kind: u8, param: u8) -> Option<Tile> {
+    let color = || match param {
+        0 => Some(KeyColor::Blue),
+        1 => Some(KeyColor::Gold),
+        _ => None,
+    };
+    let direction = || match param {
+        0 => Some(Direction::Left),
+        1 => Some(Direction::Up),
+        2 => Some(Direction::Right),
+        3 => Some(Direction::Down),
+        _ => None,
+    };
+
+    let tile = match kind {
+        0 => Tile::Wall,
+        1 => Tile::Breakable,
+        2 => Tile::Door(color()?),
+        3 => Tile::Key(color()?),
+        4 => Tile::Terrain(TileEffect::Ice),
+        5 => Tile::Terrain(TileEffect::Mud),
+        6 => Tile::Terrain(TileEffect::Conveyor(direction()?)),
+        7 => Tile::Portal(param),
+        8 => Tile::Gate(param),
+        9 => Tile::Terrain(TileEffect::Wind(direction()?)),
+        _ => return None,
+    };
+
+    Some(tile)
+}
+
+/// Reads bytes off the front of a decoded code, failing on truncated input.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, LevelError> {
+        let (first, rest) = self
+            .0
+            .split_first()
+            .ok_or_else(|| LevelError::Code("the code is truncated".to_string()))?;
+
+        self.0 = rest;
+        Ok(*first)
+    }
+
+    fn u16(&mut self) -> Result<u16, LevelError> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], LevelError> {
+        if self.0.len() < len {
+            return Err(LevelError::Code("the code is truncated".to_string()));
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn position(&mut self) -> Result<Position, LevelError> {
+        grid_position(self.u8()?, self.u8()?)
+    }
+}
+
+fn grid_position(x: u8, y: u8) -> Result<Position, LevelError> {
+    if x as u32 >= GRID_WIDTH || y as u32 >= GRID_HEIGHT {
+        return Err(LevelError::Code(format!("({}, {}) is off the board", x, y)));
+    }
+
+    Ok(Position::new(x as i32, y as i32))
+}
+
+impl Level {
+    pub fn to_code(&self) -> String {
+        let name = self.name.as_bytes();
+        let name = &name[..name.len().min(u8::MAX as usize)];
+
+        let mut bytes = vec![CODE_VERSION, name.len() as u8];
+        bytes.extend_from_slice(name);
+
+        match self.spawn {
+            Some(spawn) => bytes.extend([spawn.x as u8, spawn.y as u8]),
+            None => bytes.extend([NO_SPAWN, NO_SPAWN]),
+        }
+
+        let mut tiles = self.tiles.clone();
+        tiles.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+        // Each run is (x, y, length, kind, param).
+        let mut runs: Vec<[u8; 5]> = Vec::new();
+        for (pos, tile) in tiles {
+            let [kind, param] = tile_to_bytes(tile);
+
+            if let Some(run) = runs.last_mut() {
+                let extends = run[1] as i32 == pos.y
+                    && run[0] as i32 + run[2] as i32 == pos.x
+                    && run[2] < u8::MAX
+                    && run[3..] == [kind, param];
+
+                if extends {
+                    run[2] += 1;
+                    continue;
+                }
+            }
+
+            runs.push([pos.x as u8, pos.y as u8, 1, kind, param]);
+        }
+
+        bytes.extend((runs.len() as u16).to_le_bytes());
+        runs.iter().for_each(|run| bytes.extend(run));
+
+        bytes.extend((self.food.len() as u16).to_le_bytes());
+        for food in self.food.iter() {
+            bytes.extend([food.x as u8, food.y as u8]);
+        }
+
+        bytes.extend((self.chain.len() as u16).to_le_bytes());
+        for link in self.chain.iter() {
+            bytes.extend([link.x as u8, link.y as u8]);
+        }
+
+        let par = self
+            .par
+            .map_or(NO_PAR, |par| par.min(NO_PAR as u32 - 1) as u16);
+        bytes.extend(par.to_le_bytes());
+
+        base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    pub fn from_code(code: &str) -> Result<Self, LevelError> {
+        let bytes = base64::decode_config(code.trim(), base64::URL_SAFE_NO_PAD)
+            .map_err(|e| LevelError::Code(e.to_string()))?;
+        let mut reader = Reader(&bytes);
+
+        let version = reader.u8()?;
+        if version == 0 || version > CODE_VERSION {
+            return Err(LevelError::Code(format!("unknown version {}", version)));
+        }
+
+        let name_len = reader.u8()? as usize;
+        let name = String::from_utf8_lossy(reader.bytes(name_len)?).into_owned();
+
+        let spawn = match (reader.u8()?, reader.u8()?) {
+            (NO_SPAWN, NO_SPAWN) => None,
+            (x, y) => Some(grid_position(x, y)?),
+        };
+
+        let mut tiles = Vec::new();
+        for _ in 0..reader.u16()? {
+            let start = reader.position()?;
+            let len = reader.u8()? as i32;
+            let (kind, param) = (reader.u8()?, reader.u8()?);
+            let tile = tile_from_bytes(kind, param)
+                .ok_or_else(|| LevelError::Code(format!("unknown tile {}:{}", kind, param)))?;
+
+            if start.x + len > GRID_WIDTH as i32 {
+                return Err(LevelError::Code(
+                    "a row of tiles runs off the board".to_string(),
+                ));
+            }
+
+            tiles.extend((start.x..start.x + len).map(|x| (Position::new(x, start.y), tile)));
+        }
+
+        let mut food = Vec::new();
+        for _ in 0..reader.u16()? {
+            food.push(reader.position()?);
+        }
+
+        // Version 1 left the chain off entirely when there wasn't one.
+        let mut chain = Vec::new();
+        if version > 1 || !reader.0.is_empty() {
+            for _ in 0..reader.u16()? {
+                chain.push(reader.position()?);
+            }
+        }
+
+        let par = match version {
+            1 => None,
+            _ => match reader.u16()? {
+                NO_PAR => None,
+                par => Some(par as u32),
+            },
+        };
+
+        if !reader.0.is_empty() {
+            return Err(LevelError::Code(
+                "unexpected data after the level".to_string(),
+            ));
+        }
+
+        let level = Self {
+            name,
+            spawn,
+            tiles,
+            food,
+            chain,
+            par,
+            scene: None,
+            generated: None,
+        };
+
+        level.validate()?;
+        Ok(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level() -> Level {
+        Level {
+            name: "Test".to_string(),
+            spawn: Some(Position::new(3, 4)),
+            tiles: vec![
+                (Position::new(0, 0), Tile::Wall),
+                (Position::new(1, 0), Tile::Wall),
+                (Position::new(2, 0), Tile::Wall),
+                (Position::new(5, 0), Tile::Door(KeyColor::Gold)),
+                (
+                    Position::new(1, 2),
+                    Tile::Terrain(TileEffect::Conveyor(Direction::Down)),
+                ),
+                (Position::new(6, 2), Tile::Gate(4)),
+            ],
+            food: vec![Position::new(7, 7)],
+            chain: vec![Position::new(2, 5), Position::new(8, 1)],
+            par: Some(12),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let level = level();
+        let decoded = Level::from_code(&level.to_code()).unwrap();
+
+        assert_eq!(decoded.name, level.name);
+        assert_eq!(decoded.spawn, level.spawn);
+        assert_eq!(decoded.tiles, level.tiles);
+        assert_eq!(decoded.food, level.food);
+        assert_eq!(decoded.chain, level.chain);
+        assert_eq!(decoded.par, level.par);
+    }
+
+    #[test]
+    fn round_trips_without_par_or_chain() {
+        let level = Level {
+            chain: Vec::new(),
+            par: None,
+            ..level()
+        };
+        let decoded = Level::from_code(&level.to_code()).unwrap();
+
+        assert!(decoded.chain.is_empty());
+        assert_eq!(decoded.par, None);
+    }
+
+    #[test]
+    fn reads_version_one() {
+        let bytes = [1, 1, b'a', NO_SPAWN, NO_SPAWN, 0, 0, 1, 0, 2, 3];
+        let code = base64::encode_config(bytes, base64::URL_SAFE_NO_PAD);
+        let decoded = Level::from_code(&code).unwrap();
+
+        assert_eq!(decoded.name, "a");
+        assert_eq!(decoded.spawn, None);
+        assert_eq!(decoded.food, vec![Position::new(2, 3)]);
+        assert!(decoded.chain.is_empty());
+        assert_eq!(decoded.par, None);
+    }
+
+    #[test]
+    fn rejects_truncated_codes() {
+        let code = level().to_code();
+
+        assert!(Level::from_code(&code[..code.len() - 4]).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::level::{ActiveLevel, Level, LevelError, LEVELS_DIR};
+use crate::settings::Settings;
+use crate::transition::{ScreenFade, TransitionEvent};
+use crate::{AppState, NewRunEvent, RunKind, BG_COLOR, WIN_HEIGHT, WIN_WIDTH};
+
+const MENU_FONT_SIZE: f32 = 22.0;
+const SELECTED_COLOR: Color = Color::rgb(0.9, 0.8, 0.2);
+const INVALID_COLOR: Color = Color::rgb(0.8, 0.2, 0.2);
+const MENU_HELP: &str = "Up/Down select  Enter play  Esc back";
+
+/// A file found in the levels directory, kept even when it fails to load so
+/// the menu can say what's wrong with it.
+struct LevelEntry {
+    file: String,
+    level: Result<Level, LevelError>,
+}
+
+/// Every level file in [`LEVELS_DIR`], sorted by file name.
+#[derive(Default)]
+struct CustomLevels {
+    entries: Vec<LevelEntry>,
+    selected: usize,
+    return_to: Option<AppState>,
+}
+
+impl CustomLevels {
+    fn scan(&mut self) {
+        // Make sure there's somewhere to drop level files, even on a first run.
+        let _ = fs::create_dir_all(LEVELS_DIR);
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(LEVELS_DIR)
+            .map(|dir| dir.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+
+        paths.retain(|path| path.extension().and_then(|ext| ext.to_str()) == Some("ron"));
+        paths.sort();
+
+        self.entries = paths
+            .into_iter()
+            .map(|path| LevelEntry {
+                file: path.file_name().unwrap().to_string_lossy().into_owned(),
+                level: Level::load(&path),
+            })
+            .collect();
+
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+}
+
+#[derive(Component)]
+struct LevelSelectOnly;
+
+#[derive(Component)]
+struct LevelMenuText;
+
+fn discover_levels(mut levels: ResMut<CustomLevels>) {
+    levels.scan();
+}
+
+fn open_level_select(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut levels: ResMut<CustomLevels>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if fade.is_active() || !settings.controls.hotkey(&keyboard_input, KeyCode::L) {
+        return;
+    }
+
+    let current = app_state.current().clone();
+    if let AppState::Paused | AppState::GameOver = current {
+        levels.return_to = Some(current);
+        transitions.send(TransitionEvent(AppState::LevelSelect));
+    }
+}
+
+fn enter_level_select(mut commands: Commands, mut levels: ResMut<CustomLevels>) {
+    // Pick up anything saved since startup, e.g. from the editor.
+    levels.scan();
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: BG_COLOR,
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(LevelSelectOnly)
+        .insert(Layer::Overlay.at(1.0));
+
+    commands
+        .spawn_bundle(Text2dBundle::default())
+        .insert(LevelMenuText)
+        .insert(LevelSelectOnly)
+        .insert(Layer::Overlay.at(2.0));
+}
+
+fn exit_level_select(mut commands: Commands, query: Query<Entity, With<LevelSelectOnly>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+fn level_select_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    mut levels: ResMut<CustomLevels>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut run_kind: ResMut<RunKind>,
+    mut transitions: EventWriter<TransitionEvent>,
+    mut new_run: EventWriter<NewRunEvent>,
+) {
+    let count = levels.entries.len();
+
+    if fade.is_active() {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let back = levels.return_to.take().unwrap_or(AppState::MainMenu);
+        transitions.send(TransitionEvent(back));
+        return;
+    }
+
+    if count == 0 {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        levels.selected = (levels.selected + count - 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        levels.selected = (levels.selected + 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Return) {
+        if let Ok(level) = &levels.entries[levels.selected].level {
+            active_level.0 = level.clone();
+            *run_kind = RunKind::Free;
+            levels.return_to = None;
+            // The menu covers the whole board, so the swap happens out of sight.
+            new_run.send(NewRunEvent);
+            transitions.send(TransitionEvent(AppState::Playing));
+        }
+    }
+}
+
+fn update_level_menu(
+    asset_server: Res<AssetServer>,
+    levels: Res<CustomLevels>,
+    spawned: Query<(), Added<LevelMenuText>>,
+    mut query: Query<&mut Text, With<LevelMenuText>>,
+) {
+    if !levels.is_changed() && spawned.is_empty() {
+        return;
+    }
+
+    let style = |color| TextStyle {
+        font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+        font_size: MENU_FONT_SIZE,
+        color,
+    };
+
+    let mut sections = vec![TextSection {
+        value: "Custom Levels\n\n".to_string(),
+        style: style(Color::WHITE),
+    }];
+
+    if levels.entries.is_empty() {
+        sections.push(TextSection {
+            value: format!("No level files found in {}/\n", LEVELS_DIR),
+            style: style(Color::GRAY),
+        });
+    }
+
+    for (i, entry) in levels.entries.iter().enumerate() {
+        let selected = i == levels.selected;
+        let marker = if selected { "> " } else { "  " };
+        let (value, color) = match &entry.level {
+            Ok(level) => {
+                let color = if selected {
+                    SELECTED_COLOR
+                } else {
+                    Color::WHITE
+                };
+                (
+                    format!("{}{} ({})\n", marker, level.name, entry.file),
+                    color,
+                )
+            }
+            Err(e) => (format!("{}{}: {}\n", marker, entry.file, e), INVALID_COLOR),
+        };
+
+        sections.push(TextSection {
+            value,
+            style: style(color),
+        });
+    }
+
+    sections.push(TextSection {
+        value: format!("\n{}", MENU_HELP),
+        style: style(Color::GRAY),
+    });
+
+    for mut text in query.iter_mut() {
+        *text = Text {
+            sections: sections.clone(),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        };
+    }
+}
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CustomLevels>()
+            .add_startup_system(discover_levels)
+            .add_system(open_level_select)
+            .add_system_set(
+                SystemSet::on_enter(AppState::LevelSelect).with_system(enter_level_select),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::LevelSelect).with_system(exit_level_select),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::LevelSelect)
+                    .with_system(level_select_input)
+                    .with_system(update_level_menu.after(level_select_input)),
+            );
+    }
+}
@@ -1,8 +1,105 @@
+mod achievements;
+mod animation;
+mod autosave;
+mod background;
+mod boards;
+mod boost;
+mod boss;
+mod campaign;
+mod chain;
+mod cheats;
+mod chroma_key;
+mod cleanup;
+mod close_call;
+mod combo;
+mod console;
+mod corners;
+mod daily;
+mod danger;
+mod death;
+mod decay;
+mod doors;
+mod editor;
+mod event_log;
+mod face;
+mod food_bias;
+mod gates;
+mod ghost;
+mod grid;
+mod hud;
+mod hunger;
+mod hunter;
+mod input_display;
+mod kiosk;
+mod layers;
+mod length_cap;
+mod level;
+mod level_code;
+mod level_select;
+mod maze;
+mod menu;
+mod mini_replay;
+mod mirror;
+mod mode;
+mod music;
+mod observation;
+mod obstacles;
+mod pace;
+mod path_analysis;
+mod portals;
+mod profile;
+mod progression;
+mod puzzle;
+mod quit;
+mod replay;
+mod run_events;
+mod run_export;
+mod sandbox;
+mod scenes;
+mod scoring;
+mod seed;
+mod settings;
+mod shrink;
+mod snapshot;
+mod speed_curve;
+mod splash;
+mod split;
+mod stats;
+mod stream;
+mod sync;
+mod tail;
+mod terrain;
+mod theme;
+mod timer;
+mod toast;
+mod trail;
+mod transition;
+mod ui_scale;
+mod victory;
+mod viewport;
+mod windowing;
+mod wrap;
+
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ops::Neg;
-
-use bevy::core::FixedTimestep;
+use std::time::Duration;
+
+use bevy::ecs::event::Events;
+use bevy::ecs::query::{FilterFetch, WorldQuery};
+use bevy::ecs::schedule::ShouldRun;
+use bevy::ecs::system::CommandQueue;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ElementState;
 use bevy::prelude::*;
-use rand::prelude::random;
+use bevy::window::WindowFocused;
+use bevy::winit::{UpdateMode, WinitSettings};
+use boards::{BoardId, BoardLayout};
+use console::{parse_arg, ConsoleCommandsExt};
+use layers::Layer;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use terrain::TileEffect;
 
 const WIN_HEIGHT: f32 = 600.;
 const WIN_WIDTH: f32 = WIN_HEIGHT + 100.;
@@ -14,14 +111,70 @@ const GRID_HEIGHT: u32 = 30;
 const GRID_WIDTH: u32 = 35;
 const FOOD_WIN_AMOUNT: u32 = 50;
 const FALL_BEHIND_LOSS_AMOUNT: u32 = 15;
+const FOOD_SPAWN_INTERVAL: f64 = 3.0;
+const IDLE_MAX_WAIT_MS: u64 = 250;
+const MOVEMENT_STEP: f64 = 0.10;
+/// Turns pressed faster than the snake moves wait their turn, up to this many.
+const TURN_QUEUE_LEN: usize = 3;
+const DEFAULT_SPAWN: Position = Position { x: 3, y: 3 };
 
 #[derive(Default)]
 struct LastTailPosition(Option<Position>);
 
+/// The numbers a run is balanced around. Always the defaults, apart from in
+/// the sandbox where they can be tweaked live.
+#[derive(Debug, Clone, PartialEq)]
+struct Rules {
+    /// Seconds between pieces of food dropping in.
+    food_interval: f64,
+    /// Segments grown for each piece of food.
+    growth: u32,
+    /// Food eaten that wins the run, in place of the mode's own condition.
+    food_to_win: Option<u32>,
+    /// Uneaten food on the board that loses the run.
+    food_to_lose: u32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            food_interval: FOOD_SPAWN_INTERVAL,
+            growth: 1,
+            food_to_win: None,
+            food_to_lose: FALL_BEHIND_LOSS_AMOUNT,
+        }
+    }
+}
+
 #[derive(Component)]
 struct SnakePart;
 
-fn spawn_snake_part(mut commands: Commands, position: Position) -> Entity {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Occupant {
+    Body,
+    Wall,
+}
+
+/// What's in each solid cell as a movement tick starts, so the snake can
+/// look around it without scanning every entity on the board.
+#[derive(Default)]
+struct Occupancy(HashMap<Position, Occupant>);
+
+fn index_occupancy(
+    mut occupancy: ResMut<Occupancy>,
+    parts: Query<&Position, (With<SnakePart>, Without<SnakeHead>)>,
+    walls: Query<&Position, With<obstacles::Wall>>,
+) {
+    occupancy.0.clear();
+    occupancy
+        .0
+        .extend(parts.iter().map(|position| (*position, Occupant::Body)));
+    occupancy
+        .0
+        .extend(walls.iter().map(|position| (*position, Occupant::Wall)));
+}
+
+fn spawn_snake_part(commands: &mut Commands, position: Position) -> Entity {
     commands
         .spawn_bundle(SpriteBundle {
             sprite: Sprite {
@@ -33,26 +186,84 @@ fn spawn_snake_part(mut commands: Commands, position: Position) -> Entity {
         .insert(SnakePart)
         .insert(position)
         .insert(Size::square(0.7))
-        .insert(UiFixedZ(99.0))
+        .insert(Layer::Entities.at(6.0))
         .id()
 }
 
+/// A snake's segments, head first. The player's snake is kept as a
+/// resource, any others carry one as a component.
 #[derive(Default, Component)]
 struct SnakeBody(Vec<Entity>);
 
+impl SnakeBody {
+    /// Where every segment is, head first.
+    fn positions<F>(&self, positions: &Query<&mut Position, F>) -> Vec<Position>
+    where
+        F: WorldQuery,
+        F::Fetch: FilterFetch,
+    {
+        self.0.iter().map(|e| *positions.get(*e).unwrap()).collect()
+    }
+
+    /// Pulls every segment behind the head into the place the one ahead of it
+    /// held in `before`, once the head itself has moved.
+    fn follow<F>(&self, before: &[Position], positions: &mut Query<&mut Position, F>)
+    where
+        F: WorldQuery,
+        F::Fetch: FilterFetch,
+    {
+        for (pos, segment) in before.iter().zip(self.0.iter().skip(1)) {
+            *positions.get_mut(*segment).unwrap() = *pos;
+        }
+    }
+}
+
 #[derive(Component)]
 struct RenderedFood(u32);
 
 #[derive(Component)]
 struct DevouredFood(u32);
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize)]
 enum GameStatus {
     InProgress,
     Won,
     Lost,
 }
 
+impl GameStatus {
+    /// Ends the run as `outcome`, unless it's already over: whatever ends it
+    /// first is how it ended. Returns whether this was the end of it.
+    fn end(&mut self, outcome: GameStatus) -> bool {
+        if !matches!(self, GameStatus::InProgress) {
+            return false;
+        }
+
+        *self = outcome;
+        true
+    }
+}
+
+/// The one place a [`GameOverEvent`] comes from. Anything that ends a run
+/// only sets its status, through [`GameStatus::end`], and this sends the
+/// event once for the first ending it sees.
+fn announce_game_over(
+    statuses: Query<&GameStatus, Changed<GameStatus>>,
+    mut over: Local<bool>,
+    mut event_writer: EventWriter<GameOverEvent>,
+) {
+    for status in statuses.iter() {
+        match status {
+            GameStatus::InProgress => *over = false,
+            _ if !*over => {
+                *over = true;
+                event_writer.send(GameOverEvent(status.clone()));
+            }
+            _ => (),
+        }
+    }
+}
+
 #[derive(Bundle)]
 struct GameState {
     status: GameStatus,
@@ -74,6 +285,261 @@ fn setup_game_state(mut commands: Commands) {
     commands.spawn_bundle(GameState::new());
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum AppState {
+    Splash,
+    MainMenu,
+    Credits,
+    Stats,
+    Playing,
+    Paused,
+    GameOver,
+    Editor,
+    LevelSelect,
+    ConfirmQuit,
+    Replay,
+}
+
+impl AppState {
+    const ALL: [Self; 11] = [
+        Self::Splash,
+        Self::MainMenu,
+        Self::Credits,
+        Self::Stats,
+        Self::Playing,
+        Self::Paused,
+        Self::GameOver,
+        Self::Editor,
+        Self::LevelSelect,
+        Self::ConfirmQuit,
+        Self::Replay,
+    ];
+}
+
+fn toggle_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<settings::Settings>,
+    captured: Res<settings::KeyboardCaptured>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let pressed = [KeyCode::Escape, KeyCode::P]
+        .into_iter()
+        .any(|key| settings.controls.hotkey(&keyboard_input, key));
+
+    if captured.0 || !pressed {
+        return;
+    }
+
+    // Esc on the pause screen asks to quit instead, see `quit`.
+    let next = match app_state.current() {
+        AppState::Playing => AppState::Paused,
+        AppState::Paused if keyboard_input.just_pressed(KeyCode::P) => AppState::Playing,
+        _ => return,
+    };
+
+    // A run ending this frame has already queued the game over screen, which
+    // takes precedence.
+    let _ = app_state.set(next);
+}
+
+/// Pauses the run when the window loses focus, so alt-tabbing away doesn't
+/// leave the snake heading into a wall unattended.
+fn pause_on_focus_loss(
+    settings: Res<settings::GameplaySettings>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let lost_focus = focus_events.iter().any(|event| !event.focused);
+
+    if lost_focus && settings.auto_pause && *app_state.current() == AppState::Playing {
+        // A pause key pressed in the same frame may have queued this already.
+        let _ = app_state.set(AppState::Paused);
+    }
+}
+
+/// Entities that animate for as long as they're around.
+type Animations<'w, 's> =
+    Query<'w, 's, (), Or<(With<mini_replay::MiniReplay>, With<victory::Confetti>)>>;
+
+/// Drops the event loop into low power mode whenever the game isn't being
+/// played, so a static pause or game over screen doesn't burn a full core.
+/// Anything still animating, like a screen fade, the death or win sequence,
+/// confetti or the mini replay, needs every frame, whatever the state.
+fn throttle_when_idle(
+    app_state: Res<State<AppState>>,
+    fade: Res<transition::ScreenFade>,
+    death: Option<Res<death::DeathSequence>>,
+    win: Option<Res<victory::WinSequence>>,
+    animations: Animations,
+    mut last: Local<Option<(AppState, bool)>>,
+    mut winit_settings: ResMut<WinitSettings>,
+) {
+    let animating = fade.is_active() || death.is_some() || win.is_some() || !animations.is_empty();
+    let current = Some((app_state.current().clone(), animating));
+
+    if *last == current {
+        return;
+    }
+    *last = current;
+
+    let update_mode = || match app_state.current() {
+        AppState::Playing | AppState::Replay => UpdateMode::Continuous,
+        _ if animating => UpdateMode::Continuous,
+        _ => UpdateMode::ReactiveLowPower {
+            max_wait: Duration::from_millis(IDLE_MAX_WAIT_MS),
+        },
+    };
+
+    winit_settings.focused_mode = update_mode();
+    winit_settings.unfocused_mode = update_mode();
+}
+
+#[derive(Default)]
+struct TimestepState {
+    accumulator: f64,
+    looping: bool,
+    paused: bool,
+}
+
+impl TimestepState {
+    /// Advances a fixed timestep that only accumulates time while the game is
+    /// being played. The first frame after a pause is skipped, since its delta
+    /// covers the whole time spent paused and would otherwise trigger a burst
+    /// of catch up ticks.
+    fn advance(&mut self, time: &Time, app_state: &State<AppState>, step: f64) -> ShouldRun {
+        if *app_state.current() != AppState::Playing {
+            self.paused = true;
+            return ShouldRun::No;
+        }
+
+        if self.paused {
+            self.paused = false;
+        } else if !self.looping {
+            self.accumulator += time.delta_seconds_f64();
+        }
+
+        if self.accumulator >= step {
+            self.accumulator -= step;
+            self.looping = true;
+            ShouldRun::YesAndCheckAgain
+        } else {
+            self.looping = false;
+            ShouldRun::No
+        }
+    }
+}
+
+fn playing_timestep(
+    step: f64,
+) -> impl FnMut(Res<Time>, Res<State<AppState>>, Local<TimestepState>) -> ShouldRun {
+    move |time, app_state, mut timestep| timestep.advance(&time, &app_state, step)
+}
+
+/// Seconds between snake movement ticks.
+struct TickRate {
+    base: f64,
+    boosted: bool,
+    slowed: bool,
+    surging: bool,
+    /// Slowed down by a cheat code.
+    crawling: bool,
+    /// How far the speed curve has brought the step down, as a fraction.
+    ramp: f64,
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self {
+            base: MOVEMENT_STEP,
+            boosted: false,
+            slowed: false,
+            surging: false,
+            crawling: false,
+            ramp: 1.0,
+        }
+    }
+}
+
+impl TickRate {
+    fn step(&self) -> f64 {
+        let mut step = self.base * self.ramp;
+
+        if self.boosted {
+            step /= 2.0;
+        }
+
+        if self.surging {
+            step /= 2.0;
+        }
+
+        if self.slowed {
+            step *= 2.0;
+        }
+
+        if self.crawling {
+            step *= 2.0;
+        }
+
+        step
+    }
+}
+
+fn food_timestep(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<mode::GameMode>,
+    rules: Res<Rules>,
+    mut timestep: Local<TimestepState>,
+) -> ShouldRun {
+    // Puzzles only ever have the food they start with.
+    if *game_mode == mode::GameMode::Puzzle {
+        return ShouldRun::No;
+    }
+
+    timestep.advance(&time, &app_state, rules.food_interval)
+}
+
+/// Ticks on a timer, except in puzzle mode, where the snake makes one move
+/// for each direction pressed instead.
+fn movement_timestep(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<mode::GameMode>,
+    tick_rate: Res<TickRate>,
+    moves: Res<puzzle::PuzzleMoves>,
+    mut timestep: Local<TimestepState>,
+) -> ShouldRun {
+    if *game_mode != mode::GameMode::Puzzle {
+        return timestep.advance(&time, &app_state, tick_rate.step());
+    }
+
+    match *app_state.current() {
+        AppState::Playing if !moves.queued.is_empty() => ShouldRun::Yes,
+        _ => ShouldRun::No,
+    }
+}
+
+fn show_paused_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Paused",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            ..default()
+        })
+        .insert(cleanup::DespawnOnStateExit(AppState::Paused))
+        .insert(Layer::Hud.at(2.0));
+}
+
 #[derive(Component)]
 struct ScoreText;
 
@@ -99,38 +565,46 @@ fn setup_score_text(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         })
         .insert(ScoreText)
-        .insert(UiFixedZ(101.0));
+        .insert(hud::AnimatedNumber::new("Score: "))
+        .insert(Layer::Hud.at(1.0));
 }
 
 fn score_update_system(
-    mut text_query: Query<&mut Text, With<ScoreText>>,
+    breakdown: Res<scoring::ScoreBreakdown>,
+    mut score_query: Query<&mut hud::AnimatedNumber, With<ScoreText>>,
     devoured: Query<&DevouredFood>,
 ) {
-    for mut text in text_query.iter_mut() {
-        if let Some(count) = devoured.iter().next() {
-            text.sections[0].value = format!("Score: {}", count.0);
+    for mut score in score_query.iter_mut() {
+        if let Some(eaten) = devoured.iter().next() {
+            score.set(breakdown.score(eaten));
         }
     }
 }
 
 fn update_game_status(
+    game_mode: Res<mode::GameMode>,
+    run_kind: Res<RunKind>,
+    rules: Res<Rules>,
+    body: Res<SnakeBody>,
     mut status_query: Query<&mut GameStatus>,
     mut query: Query<(&RenderedFood, &DevouredFood)>,
-    mut event_writer: EventWriter<GameOverEvent>,
 ) {
     if let Some((rendered, devoured)) = query.iter_mut().next() {
         if let Some(mut status) = status_query.iter_mut().next() {
-            if devoured.0 >= FOOD_WIN_AMOUNT {
-                *status = GameStatus::Won;
-            } else if rendered.0 >= FALL_BEHIND_LOSS_AMOUNT {
-                *status = GameStatus::Lost;
-            }
-
-            if let GameStatus::InProgress = *status {
-                return;
+            let condition = match rules.food_to_win {
+                Some(target) => Some(mode::WinCondition::FoodDevoured(target)),
+                None => run_kind.win_condition(*game_mode),
+            };
+            let won = matches!(
+                condition,
+                Some(condition) if condition.is_met(devoured.0, body.0.len())
+            );
+
+            if won {
+                status.end(GameStatus::Won);
+            } else if rendered.0 >= rules.food_to_lose {
+                status.end(GameStatus::Lost);
             }
-
-            event_writer.send(GameOverEvent((*status).clone()));
         }
 
         // println!("Rendered: {}", rendered.0);
@@ -139,17 +613,7 @@ fn update_game_status(
     }
 }
 
-#[derive(Component)]
-struct UiFixedZ(f32);
-
-fn ui_apply_fixed_z(mut query: Query<(&mut Transform, &mut GlobalTransform, &UiFixedZ)>) {
-    for (mut transform, mut global_transform, fixed) in query.iter_mut() {
-        transform.translation.z = fixed.0;
-        global_transform.translation.z = fixed.0;
-    }
-}
-
-#[derive(PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 enum Direction {
     Left,
     Up,
@@ -173,28 +637,85 @@ impl Neg for Direction {
 #[derive(Component)]
 struct Food;
 
+/// Where everything random in a run comes from. Reseeded for each run, from
+/// the date for the daily challenge so everyone gets the same one.
+struct GameRng(StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+/// The seed [`GameRng`] was last seeded with, kept so a run can be replayed.
+#[derive(Default)]
+struct RunSeed(u64);
+
+fn random_position(rng: &mut impl Rng) -> Position {
+    let mut get_random_pos = |bound: u32| (rng.gen::<f32>() * bound as f32) as i32;
+    Position::new(get_random_pos(GRID_WIDTH), get_random_pos(GRID_HEIGHT))
+}
+
+/// Rerolls until it lands on a cell that isn't in `occupied`.
+fn random_free_position(rng: &mut impl Rng, occupied: &HashSet<Position>) -> Position {
+    loop {
+        let position = random_position(rng);
+        if !occupied.contains(&position) {
+            return position;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn food_spawner(
     mut commands: Commands,
+    mut rng: ResMut<GameRng>,
     mut render_event: EventWriter<RenderFoodEvent>,
-    query: Query<&GameStatus>,
+    gameplay: Res<settings::GameplaySettings>,
+    query: Query<(&GameStatus, &DevouredFood)>,
+    walls: Query<&Position, With<obstacles::Wall>>,
+    heads: Query<&Position, (With<SnakeHead>, Without<BoardId>)>,
+    parts: Query<&Position, (With<SnakePart>, Without<BoardId>)>,
 ) {
     let mut should_draw = true;
+    let mut score = 0;
 
-    if let Some(status) = query.iter().next() {
+    if let Some((status, devoured)) = query.iter().next() {
         match status {
             GameStatus::InProgress => (),
             _ => should_draw = false,
         }
+        score = devoured.0;
     }
 
     if !should_draw {
         return;
     }
 
-    let get_random_pos = |bound: u32| (random::<f32>() * bound as f32) as i32;
-    let x = get_random_pos(GRID_WIDTH);
-    let y = get_random_pos(GRID_HEIGHT);
+    let walls = walls.iter().copied().collect();
+    let position = match gameplay.food_bias {
+        // Draws from the RNG exactly as before, so seeds play out the same.
+        food_bias::FoodBias::Off => random_free_position(&mut rng.0, &walls),
+        ref bias => {
+            let head = heads.iter().next().copied();
+            // Unlike the plain draw, this one never lands under the snake.
+            let mut occupied = walls.clone();
+            occupied.extend(parts.iter().copied());
+
+            let drawn =
+                food_bias::biased_free_position(&mut rng.0, bias, score, &occupied, &walls, head);
+            match drawn {
+                Some(position) => position,
+                None => return,
+            }
+        }
+    };
+
+    spawn_food(&mut commands, position);
+    render_event.send(RenderFoodEvent);
+}
 
+fn spawn_food(commands: &mut Commands, position: Position) {
     commands
         .spawn_bundle(SpriteBundle {
             sprite: Sprite {
@@ -204,27 +725,52 @@ fn food_spawner(
             ..default()
         })
         .insert(Food)
-        .insert(Position::new(x, y))
+        .insert(cleanup::DespawnOnGameOver)
+        .insert(animation::Pulse(animation::FOOD_PULSE))
+        .insert(position)
         .insert(Size::square(0.8))
-        .insert(UiFixedZ(1.0));
-
-    render_event.send(RenderFoodEvent);
+        .insert(Layer::Entities.at(1.0));
 }
 
 fn handle_render_event(
     mut rendered: Query<&mut RenderedFood>,
     mut render_event: EventReader<RenderFoodEvent>,
 ) {
-    if render_event.iter().next().is_some() {
-        if let Some(mut count) = rendered.iter_mut().next() {
-            count.0 += 1;
-        }
+    let spawned = render_event.iter().count() as u32;
+
+    if let Some(mut count) = rendered.iter_mut().next() {
+        count.0 += spawned;
     }
 }
 
 struct GrowthEvent;
 struct RenderFoodEvent;
+/// Sent once a run, when it ends, by [`announce_game_over`].
 struct GameOverEvent(GameStatus);
+struct NewRunEvent;
+
+/// What the current run is being played for, which decides how it's seeded
+/// and how it's won.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum RunKind {
+    #[default]
+    Free,
+    /// The daily challenge for the given day since the epoch.
+    Daily(u32),
+    /// A campaign stage, counting from 1.
+    Campaign(u32),
+}
+
+impl RunKind {
+    /// How the run is won on score, or `None` when something else decides
+    /// it, like a boss fight.
+    fn win_condition(&self, game_mode: mode::GameMode) -> Option<mode::WinCondition> {
+        match *self {
+            Self::Campaign(stage) => campaign::win_condition(stage),
+            _ => game_mode.win_condition(),
+        }
+    }
+}
 
 #[derive(Component)]
 struct GameOverText;
@@ -262,35 +808,173 @@ fn show_end_game_text(mut commands: Commands, status: &GameStatus, asset_server:
             ..default()
         })
         .insert(GameOverText)
-        .insert(UiFixedZ(102.0));
+        .insert(cleanup::DespawnOnStateExit(AppState::GameOver))
+        .insert(Layer::Hud.at(2.0));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn game_over(
     mut commands: Commands,
     mut reader: EventReader<GameOverEvent>,
-    food: Query<Entity, With<Food>>,
-    segments: Query<Entity, With<SnakeBody>>,
+    mut app_state: ResMut<State<AppState>>,
+    settings: Res<settings::Settings>,
+    body: Res<SnakeBody>,
+    heads: Query<(&Position, &SnakeHead)>,
     asset_server: Res<AssetServer>,
 ) {
     if let Some(event) = reader.iter().next() {
-        for ent in food.iter().chain(segments.iter()) {
-            commands.entity(ent).despawn();
+        // Food and anything else tagged with `DespawnOnGameOver` goes on the
+        // way in; the snake stays for the sequences below. The game over
+        // screen wins out over anything else queued this frame, and this
+        // only fails when it's already up.
+        if app_state.overwrite_set(AppState::GameOver).is_err() {
+            return;
+        }
+
+        // A lost run holds on the collision and crumbles away first, and a
+        // won one takes a lap of honour. Either sequence puts up the text
+        // once it's done.
+        if !settings.accessibility.reduced_motion {
+            let head = heads.iter().next();
+
+            match event.0 {
+                GameStatus::Lost => {
+                    let focus = head.map(|(position, _)| *position);
+                    commands.insert_resource(death::DeathSequence::new(
+                        &body,
+                        event.0.clone(),
+                        focus,
+                    ));
+                    return;
+                }
+                GameStatus::Won => {
+                    let direction = head.map_or(Direction::Up, |(_, head)| head.moved);
+                    commands.insert_resource(victory::WinSequence::new(direction));
+                    return;
+                }
+                GameStatus::InProgress => (),
+            }
         }
 
         show_end_game_text(commands, &event.0, asset_server);
-        // TODO: Add a menu to start a new game
     }
 }
 
+/// Everything that belongs to a single run, thrown away when a new one starts.
+type RunEntities<'w, 's> = Query<
+    'w,
+    's,
+    Entity,
+    Or<(
+        With<SnakePart>,
+        With<Food>,
+        With<obstacles::LevelTile>,
+        With<obstacles::Hammer>,
+        With<obstacles::HammerPowerUp>,
+        With<hunter::Hunter>,
+        With<trail::TrailMark>,
+        With<boss::Boss>,
+        With<boss::BossBar>,
+        With<split::SplitPickup>,
+        With<split::Offshoot>,
+        With<split::OffshootPart>,
+        With<shrink::ShrinkPickup>,
+        With<length_cap::DroppedTail>,
+        With<run_events::SpeedSurge>,
+    )>,
+>;
+
+/// Resets the board, the snake and the score, then rebuilds the active level.
+#[allow(clippy::too_many_arguments)]
+fn new_run(
+    mut commands: Commands,
+    mut reader: EventReader<NewRunEvent>,
+    mut active_level: ResMut<level::ActiveLevel>,
+    run_kind: Res<RunKind>,
+    game_mode: Res<mode::GameMode>,
+    mut rng: ResMut<GameRng>,
+    mut body: ResMut<SnakeBody>,
+    mut game_state: Query<(&mut GameStatus, &mut DevouredFood, &mut RenderedFood)>,
+    entities: RunEntities,
+    mut render_event: EventWriter<RenderFoodEvent>,
+) {
+    if reader.iter().next().is_none() {
+        return;
+    }
+
+    for ent in entities.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+
+    for (mut status, mut devoured, mut rendered) in game_state.iter_mut() {
+        *status = GameStatus::InProgress;
+        devoured.0 = 0;
+        rendered.0 = 0;
+    }
+
+    let seed = match *run_kind {
+        RunKind::Daily(day) => day as u64,
+        _ => rand::random(),
+    };
+    rng.0 = StdRng::seed_from_u64(seed);
+    commands.insert_resource(RunSeed(seed));
+
+    // A generated board, like a maze, is new every run, and drawn from the
+    // seed so the seed alone brings it back.
+    if let (RunKind::Free, Some(mode)) = (*run_kind, active_level.0.generated) {
+        active_level.0 = level::Level::for_mode_with(mode, &mut rng.0);
+    }
+
+    commands.remove_resource::<death::DeathSequence>();
+    commands.remove_resource::<victory::WinSequence>();
+    commands.insert_resource(LastTailPosition::default());
+    commands.insert_resource(TickRate::default());
+    commands.insert_resource(hunger::Hunger::default());
+    commands.insert_resource(boost::Stamina::default());
+    commands.insert_resource(combo::Combo::default());
+    commands.insert_resource(terrain::WindClock::default());
+    commands.insert_resource(decay::ScoreDecay::default());
+    commands.insert_resource(scoring::ScoreBreakdown::default());
+    commands.insert_resource(doors::HeldKeys::default());
+
+    let level = &active_level.0;
+    *body = new_snake(&mut commands, level.spawn.unwrap_or(DEFAULT_SPAWN));
+    level.spawn(&mut commands, &mut render_event);
+
+    if *game_mode == mode::GameMode::Puzzle {
+        return;
+    }
+
+    // Match startup, where the first piece of food is dropped straight away.
+    let occupied = level.tiles.iter().map(|(position, _)| *position).collect();
+    spawn_food(&mut commands, random_free_position(&mut rng.0, &occupied));
+    render_event.send(RenderFoodEvent);
+}
+
 #[derive(Component)]
 struct SnakeHead {
     direction: Direction,
+    moved: Direction,
+    /// Turns pressed since the last move, one taken per movement tick.
+    turns: VecDeque<Direction>,
 }
 
 impl SnakeHead {
     pub fn new() -> Self {
         Self {
             direction: Direction::Up,
+            moved: Direction::Up,
+            turns: VecDeque::new(),
+        }
+    }
+
+    /// Queues a turn, unless it would do nothing or reverse the snake into
+    /// itself once the turns ahead of it have been taken.
+    fn queue_turn(&mut self, dir: Direction) {
+        let last = self.turns.back().copied().unwrap_or(self.direction);
+
+        if self.turns.len() < TURN_QUEUE_LEN && dir != last && dir != -last {
+            self.turns.push_back(dir);
         }
     }
 }
@@ -321,24 +1005,42 @@ fn inc_and_dec(devoured: &mut Query<&mut DevouredFood>, rendered: &mut Query<&mu
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn snake_growth(
-    commands: Commands,
+    mut commands: Commands,
     last_tail_position: Res<LastTailPosition>,
+    rules: Res<Rules>,
+    tick_rate: Res<TickRate>,
     mut body: ResMut<SnakeBody>,
+    mut hunger: ResMut<hunger::Hunger>,
     mut growth_reader: EventReader<GrowthEvent>,
     mut devoured: Query<&mut DevouredFood>,
     mut rendered: Query<&mut RenderedFood>,
 ) {
     if growth_reader.iter().next().is_some() {
-        body.0
-            .push(spawn_snake_part(commands, last_tail_position.0.unwrap()));
+        for _ in 0..rules.growth {
+            let part = spawn_snake_part(&mut commands, last_tail_position.0.unwrap());
+            commands
+                .entity(part)
+                .insert_bundle(animation::GrowIn::over_ticks(tick_rate.step()));
+            body.0.push(part);
+        }
+        hunger.feed();
 
         inc_and_dec(&mut devoured, &mut rendered);
     }
 }
 
-fn spawn_snake(mut commands: Commands, mut body: ResMut<SnakeBody>) {
-    *body = SnakeBody(vec![
+fn spawn_snake(
+    mut commands: Commands,
+    mut body: ResMut<SnakeBody>,
+    active_level: Res<level::ActiveLevel>,
+) {
+    *body = new_snake(&mut commands, active_level.0.spawn.unwrap_or(DEFAULT_SPAWN));
+}
+
+fn new_snake(commands: &mut Commands, head: Position) -> SnakeBody {
+    SnakeBody(vec![
         commands
             .spawn_bundle(SpriteBundle {
                 sprite: Sprite {
@@ -349,41 +1051,61 @@ fn spawn_snake(mut commands: Commands, mut body: ResMut<SnakeBody>) {
             })
             .insert(SnakeHead::new())
             .insert(SnakePart)
-            .insert(Position { x: 3, y: 3 })
+            .insert(head)
             .insert(Size::square(0.8))
-            .insert(UiFixedZ(100.0))
+            .insert(Layer::Entities.at(7.0))
             .id(),
-        spawn_snake_part(commands, Position { x: 3, y: 2 }),
-    ]);
+        spawn_snake_part(commands, head.step(Direction::Down)),
+    ])
 }
 
-fn snake_movement_input(keyboard_input: Res<Input<KeyCode>>, mut heads: Query<&mut SnakeHead>) {
-    if let Some(mut head) = heads.iter_mut().next() {
-        let dir: Direction = if keyboard_input.pressed(KeyCode::Left) {
-            Direction::Left
-        } else if keyboard_input.pressed(KeyCode::Down) {
-            Direction::Down
-        } else if keyboard_input.pressed(KeyCode::Up) {
-            Direction::Up
-        } else if keyboard_input.pressed(KeyCode::Right) {
-            Direction::Right
-        } else {
-            head.direction
-        };
+/// Queues turns in the order their keys went down, read from the raw key
+/// events since `Input` forgets the order of presses within a frame. When
+/// several arrows are mashed at once the one pressed last is where the snake
+/// ends up heading.
+fn snake_movement_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<settings::Settings>,
+    captured: Res<settings::KeyboardCaptured>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut heads: Query<&mut SnakeHead>,
+) {
+    let presses: Vec<KeyCode> = key_events
+        .iter()
+        .filter(|event| event.state == ElementState::Pressed)
+        .filter_map(|event| event.key_code)
+        // Held keys repeat their press events, only the first one counts.
+        .filter(|key| keyboard_input.just_pressed(*key))
+        .collect();
+
+    if captured.0 {
+        return;
+    }
 
-        if dir != -head.direction {
-            head.direction = dir;
+    if let Some(mut head) = heads.iter_mut().next() {
+        for dir in presses
+            .into_iter()
+            .filter_map(|key| settings.controls.direction(key))
+        {
+            head.queue_turn(dir);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn snake_movement(
+    mut commands: Commands,
     body: ResMut<SnakeBody>,
     mut game_status: Query<&mut GameStatus>,
-    mut heads: Query<(Entity, &SnakeHead)>,
-    mut positions: Query<&mut Position>,
+    mut heads: Query<(Entity, &mut SnakeHead, Option<&wrap::Grace>)>,
+    mut positions: Query<&mut Position, With<SnakePart>>,
+    terrain: Query<(&Position, &TileEffect), Without<SnakePart>>,
+    occupancy: Res<Occupancy>,
+    cheats: Res<cheats::Cheats>,
+    mut tick_rate: ResMut<TickRate>,
     mut last_tail_position: ResMut<LastTailPosition>,
-    mut event_writer: EventWriter<GameOverEvent>,
+    mut wind: ResMut<terrain::WindClock>,
+    mut close_calls: EventWriter<close_call::CloseCallEvent>,
 ) {
     if let Some(status) = game_status.iter().next() {
         match status {
@@ -392,92 +1114,109 @@ fn snake_movement(
         }
     }
 
-    if let Some((head_entity, head)) = heads.iter_mut().next() {
-        let body_positions = body
-            .0
-            .iter()
-            .map(|e| *positions.get_mut(*e).unwrap())
-            .collect::<Vec<Position>>();
+    let gust = wind.tick();
+
+    if let Some((head_entity, mut head, grace)) = heads.iter_mut().next() {
+        if let Some(dir) = head.turns.pop_front() {
+            head.direction = dir;
+        }
+
+        if grace.is_some() {
+            commands.entity(head_entity).remove::<wrap::Grace>();
+        }
+
+        let body_positions = body.positions(&positions);
 
         let mut head_pos = positions.get_mut(head_entity).unwrap();
-        if body_positions[1..].contains(&head_pos) {
-            let mut status = game_status.iter_mut().next().unwrap();
-            *status = GameStatus::Lost;
-            event_writer.send(GameOverEvent((*status).clone()));
+        if body_positions[1..].contains(&head_pos) && !cheats.invincible && grace.is_none() {
+            game_status.iter_mut().next().unwrap().end(GameStatus::Lost);
+            // Stay put on the collision rather than carrying on through it.
+            return;
         }
 
-        match &head.direction {
-            Direction::Left => {
-                if head_pos.x <= 0 {
-                    head_pos.x = GRID_WIDTH as i32;
-                }
+        let terrain_at = |pos: Position| {
+            terrain
+                .iter()
+                .find(|(tile_pos, _)| **tile_pos == pos)
+                .map(|(_, effect)| *effect)
+        };
 
-                head_pos.x -= 1;
-            }
-            Direction::Right => {
-                if head_pos.x >= GRID_WIDTH as i32 - 1 {
-                    head_pos.x = -1;
-                }
+        // Ice holds the snake to the direction it was already moving in.
+        let direction = match terrain_at(*head_pos) {
+            Some(TileEffect::Ice) => head.moved,
+            _ => head.direction,
+        };
 
-                head_pos.x += 1;
+        // Turning away at the last moment from something right in front of
+        // the head is a close call, as long as the turn doesn't hit anything.
+        let ahead = head_pos.step(head.moved);
+        if let Some(occupant) = occupancy.0.get(&ahead) {
+            if direction != head.moved && !occupancy.0.contains_key(&head_pos.step(direction)) {
+                close_calls.send(close_call::CloseCallEvent(*occupant));
             }
-            Direction::Up => {
-                if head_pos.y >= GRID_HEIGHT as i32 - 1 {
-                    head_pos.y = -1;
-                }
+        }
 
-                head_pos.y += 1;
-            }
-            Direction::Down => {
-                if head_pos.y <= 0 {
-                    head_pos.y = GRID_HEIGHT as i32;
-                }
+        *head_pos = head_pos.step(direction);
+        match terrain_at(*head_pos) {
+            Some(TileEffect::Conveyor(push)) => *head_pos = head_pos.step(push),
+            Some(TileEffect::Wind(push)) if gust => *head_pos = head_pos.step(push),
+            _ => (),
+        }
 
-                head_pos.y -= 1;
-            }
-        };
+        tick_rate.slowed = terrain_at(*head_pos) == Some(TileEffect::Mud);
+        head.moved = direction;
 
-        body_positions
-            .iter()
-            .zip(body.0.iter().skip(1))
-            .for_each(|(pos, segment)| {
-                *positions.get_mut(*segment).unwrap() = *pos;
-            });
+        body.follow(&body_positions, &mut positions);
 
         *last_tail_position = LastTailPosition(Some(*body_positions.last().unwrap()));
     }
 }
 
-fn size_scaling(windows: Res<Windows>, mut q: Query<(&Size, &mut Transform)>) {
-    let window = windows.get_primary().unwrap();
+fn size_scaling(mut q: Query<(&Size, &mut Transform)>) {
+    let cell = viewport::cell_size();
 
     for (sprite_size, mut transform) in q.iter_mut() {
-        transform.scale = Vec3::new(
-            sprite_size.width / GRID_WIDTH as f32 * window.width() as f32,
-            sprite_size.height / GRID_HEIGHT as f32 * window.height() as f32,
-            1.0,
-        );
+        transform.scale = Vec3::new(sprite_size.width * cell, sprite_size.height * cell, 1.0);
     }
 }
 
-fn calculate_grid_position(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
-    let tile_size = bound_window / bound_game;
-    pos / bound_game * bound_window - (bound_window / 2.) + (tile_size / 2.)
+fn calculate_grid_position(pos: f32, bound_board: f32, bound_game: f32) -> f32 {
+    let tile_size = bound_board / bound_game;
+    pos / bound_game * bound_board - (bound_board / 2.) + (tile_size / 2.)
+}
+
+/// The inverse of [`calculate_grid_position`], mapping a cursor position in
+/// window coordinates back to the grid cell under it on the first board.
+fn cursor_to_grid(cursor: Vec2, window: &Window, layout: &BoardLayout) -> Option<Position> {
+    let world = viewport::cursor_to_world(cursor, window, layout);
+    let cell = (world + viewport::board_size() / 2.0) / viewport::cell_size();
+    let (x, y) = (cell.x.floor() as i32, cell.y.floor() as i32);
+
+    if (0..GRID_WIDTH as i32).contains(&x) && (0..GRID_HEIGHT as i32).contains(&y) {
+        Some(Position::new(x, y))
+    } else {
+        None
+    }
 }
 
-fn position_translation(windows: Res<Windows>, mut q: Query<(&Position, &mut Transform)>) {
-    let window = windows.get_primary().unwrap();
+fn position_translation(mut q: Query<(&Position, &mut Transform, Option<&BoardId>)>) {
+    let board = viewport::board_size();
 
-    for (pos, mut transform) in q.iter_mut() {
-        transform.translation = Vec3::new(
-            calculate_grid_position(pos.x as f32, window.width() as f32, GRID_WIDTH as f32),
-            calculate_grid_position(pos.y as f32, window.height() as f32, GRID_HEIGHT as f32),
-            0.0,
-        );
+    // The z is left to the entity's draw order.
+    for (pos, mut transform, id) in q.iter_mut() {
+        let origin = id.map_or(Vec2::ZERO, BoardId::origin);
+
+        transform.translation.x =
+            origin.x + calculate_grid_position(pos.x as f32, board.x, GRID_WIDTH as f32);
+        transform.translation.y =
+            origin.y + calculate_grid_position(pos.y as f32, board.y, GRID_HEIGHT as f32);
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect,
+)]
+#[reflect(Component)]
 struct Position {
     x: i32,
     y: i32,
@@ -487,9 +1226,21 @@ impl Position {
     fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
+
+    /// The neighboring cell in `direction`, wrapping around the board edges.
+    fn step(self, direction: Direction) -> Self {
+        let (width, height) = (GRID_WIDTH as i32, GRID_HEIGHT as i32);
+
+        match direction {
+            Direction::Left => Self::new((self.x - 1 + width) % width, self.y),
+            Direction::Right => Self::new((self.x + 1) % width, self.y),
+            Direction::Up => Self::new(self.x, (self.y + 1) % height),
+            Direction::Down => Self::new(self.x, (self.y - 1 + height) % height),
+        }
+    }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 struct Size {
     width: f32,
     height: f32,
@@ -504,6 +1255,71 @@ impl Size {
     }
 }
 
+fn spawn_food_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let position = match args {
+        [x, y] => Position::new(parse_arg(x)?, parse_arg(y)?),
+        _ => return Err("Usage: spawn_food <x> <y>".to_string()),
+    };
+
+    if !(0..GRID_WIDTH as i32).contains(&position.x)
+        || !(0..GRID_HEIGHT as i32).contains(&position.y)
+    {
+        return Err(format!("{}, {} is off the board", position.x, position.y));
+    }
+
+    let mut queue = CommandQueue::default();
+    spawn_food(&mut Commands::new(&mut queue, world), position);
+    queue.apply(world);
+    world
+        .resource_mut::<Events<RenderFoodEvent>>()
+        .send(RenderFoodEvent);
+    world.resource_mut::<cheats::Cheats>().used = true;
+
+    Ok(format!("Spawned food at {}, {}", position.x, position.y))
+}
+
+fn set_speed_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let step: f64 = match args {
+        [step] => parse_arg(step)?,
+        _ => return Err("Usage: set_speed <seconds per move>".to_string()),
+    };
+
+    if step <= 0.0 {
+        return Err("The step has to be more than 0".to_string());
+    }
+
+    world.resource_mut::<TickRate>().base = step;
+    world.resource_mut::<cheats::Cheats>().used = true;
+    Ok(format!("Moving every {}s until the next run", step))
+}
+
+fn win_command(world: &mut World, _: &[&str]) -> Result<String, String> {
+    let mut statuses = world.query::<&mut GameStatus>();
+    let won = statuses
+        .iter_mut(world)
+        .next()
+        .is_some_and(|mut status| status.end(GameStatus::Won));
+
+    if !won {
+        return Err("There's no run in progress".to_string());
+    }
+
+    world.resource_mut::<cheats::Cheats>().used = true;
+
+    Ok("Won".to_string())
+}
+
+fn seed_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let seed: u64 = match args {
+        [seed] => parse_arg(seed)?,
+        _ => return Err("Usage: seed <number>".to_string()),
+    };
+
+    world.resource_mut::<GameRng>().0 = StdRng::seed_from_u64(seed);
+    world.insert_resource(RunSeed(seed));
+    Ok(format!("Seeded with {}", seed))
+}
+
 fn setup_camera(mut commands: Commands) {
     commands.spawn_bundle(OrthographicCameraBundle::new_2d());
 }
@@ -516,11 +1332,20 @@ fn main() {
         .insert_resource(ClearColor(BG_COLOR))
         .insert_resource(LastTailPosition::default())
         .insert_resource(SnakeBody::default())
+        .insert_resource(mode::GameMode::from_args())
+        .insert_resource(level::ActiveLevel::from_args())
+        .init_resource::<TickRate>()
+        .init_resource::<GameRng>()
+        .init_resource::<RunSeed>()
+        .init_resource::<RunKind>()
+        .init_resource::<Occupancy>()
+        .init_resource::<Rules>()
+        .init_resource::<terrain::WindClock>()
         .insert_resource(WindowDescriptor {
             height: WIN_HEIGHT,
             width: WIN_WIDTH,
             title: "Snake!".to_string(),
-            resizable: false,
+            resizable: true,
             present_mode: bevy::window::PresentMode::Fifo,
             ..default()
         })
@@ -532,27 +1357,172 @@ fn main() {
         )
         .add_system_set(
             SystemSet::new()
-                .with_run_criteria(FixedTimestep::step(3.0))
+                .with_run_criteria(food_timestep)
                 .with_system(food_spawner),
         )
+        .add_state(AppState::Splash)
+        .add_system(toggle_pause.after(game_over))
+        .add_system(pause_on_focus_loss.after(toggle_pause))
+        .add_system(throttle_when_idle)
+        .add_system_set(SystemSet::on_enter(AppState::Paused).with_system(show_paused_text))
         .add_system(handle_render_event.after(food_spawner))
         .add_system(snake_movement_input.before(snake_movement))
         .add_system(score_update_system.after(snake_movement))
-        .add_system(game_over.after(snake_movement))
+        .add_system(game_over)
+        .add_system_to_stage(CoreStage::PostUpdate, announce_game_over)
+        .add_system(new_run)
         .add_system_set(
             SystemSet::new()
-                .with_run_criteria(FixedTimestep::step(0.10))
+                .with_run_criteria(movement_timestep)
+                .with_system(puzzle::take_move.before(snake_movement))
+                .with_system(index_occupancy.before(snake_movement))
+                .with_system(
+                    kiosk::autopilot
+                        .after(index_occupancy)
+                        .before(snake_movement),
+                )
                 .with_system(snake_movement)
-                .with_system(snake_eating.after(snake_movement))
+                .with_system(wrap::detect_wrap.after(snake_movement))
+                .with_system(
+                    portals::portal_travel
+                        .after(snake_movement)
+                        .after(wrap::detect_wrap),
+                )
+                .with_system(snake_eating.after(portals::portal_travel))
+                .with_system(mirror::mirror_eating.after(portals::portal_travel))
+                .with_system(mirror::mirror_collision.after(portals::portal_travel))
+                .with_system(chain::eat_chain.after(portals::portal_travel))
+                .with_system(trail::leave_trail.after(portals::portal_travel))
+                .with_system(
+                    split::split_pickup
+                        .after(portals::portal_travel)
+                        .before(snake_growth),
+                )
+                .with_system(
+                    shrink::shrink_pickup
+                        .after(portals::portal_travel)
+                        .after(split::split_pickup)
+                        .before(snake_growth),
+                )
+                .with_system(split::offshoot_movement.after(split::split_pickup))
+                .with_system(split::offshoot_absorb.after(split::offshoot_movement))
                 .with_system(snake_growth.after(snake_eating))
-                .with_system(update_game_status.after(snake_growth)),
+                .with_system(length_cap::cap_length.after(snake_growth))
+                .with_system(length_cap::dropped_tail_pickup.after(portals::portal_travel))
+                .with_system(hunger::hunger_drain.after(snake_growth))
+                .with_system(boost::stamina_drain.after(snake_movement))
+                .with_system(hunter::hunter_spawner.after(snake_growth))
+                .with_system(hunter::hunter_movement.after(portals::portal_travel))
+                .with_system(boss::boss_movement.after(portals::portal_travel))
+                .with_system(obstacles::hammer_pickup.after(portals::portal_travel))
+                .with_system(doors::key_pickup.after(portals::portal_travel))
+                .with_system(
+                    gates::cycle_gates
+                        .after(portals::portal_travel)
+                        .before(obstacles::wall_collision),
+                )
+                .with_system(
+                    obstacles::wall_collision
+                        .after(obstacles::hammer_pickup)
+                        .after(doors::key_pickup),
+                )
+                .with_system(update_game_status.after(snake_growth))
+                .with_system(puzzle::puzzle_status.after(update_game_status))
+                .with_system(
+                    event_log::log_tick_events
+                        .after(puzzle::puzzle_status)
+                        .after(hunger::hunger_drain)
+                        .after(hunter::hunter_movement)
+                        .after(obstacles::wall_collision),
+                )
+                .with_system(
+                    observation::publish_observation
+                        .after(puzzle::puzzle_status)
+                        .after(hunter::hunter_movement)
+                        .after(obstacles::wall_collision),
+                )
+                .with_system(run_export::record_tick.after(observation::publish_observation))
+                .with_system(path_analysis::record_path.after(observation::publish_observation)),
         )
         .add_startup_system(spawn_snake)
         .add_startup_system(setup_game_state)
-        .add_system_to_stage(CoreStage::Last, ui_apply_fixed_z)
         .add_plugins(DefaultPlugins)
+        .add_plugin(settings::SettingsPlugin)
+        .add_plugin(timer::TimerPlugin)
+        .add_plugin(layers::LayersPlugin)
+        .add_plugin(cleanup::CleanupPlugin)
+        .add_plugin(windowing::WindowingPlugin)
+        .add_plugin(viewport::ViewportPlugin)
+        .add_plugin(kiosk::KioskPlugin)
+        .add_plugin(chroma_key::ChromaKeyPlugin)
+        .add_plugin(background::BackgroundPlugin)
+        .add_plugin(theme::ThemePlugin)
+        .add_plugin(animation::AnimationPlugin)
+        .add_plugin(tail::TailPlugin)
+        .add_plugin(corners::CornersPlugin)
+        .add_plugin(face::FacePlugin)
+        .add_plugin(wrap::WrapPlugin)
+        .add_plugin(death::DeathPlugin)
+        .add_plugin(victory::VictoryPlugin)
+        .add_plugin(transition::TransitionPlugin)
+        .add_plugin(splash::SplashPlugin)
+        .add_plugin(menu::MenuPlugin)
+        .add_plugin(quit::QuitPlugin)
+        .add_plugin(event_log::EventLogPlugin)
+        .add_plugin(observation::ObservationPlugin)
+        .add_plugin(stream::StreamPlugin)
+        .add_plugin(run_export::RunExportPlugin)
+        .add_plugin(mini_replay::MiniReplayPlugin)
+        .add_plugin(ghost::GhostPlugin)
+        .add_plugin(mirror::MirrorPlugin)
+        .add_plugin(replay::ReplayPlugin)
+        .add_plugin(path_analysis::PathAnalysisPlugin)
+        .add_plugin(seed::SeedPlugin)
+        .add_plugin(speed_curve::SpeedCurvePlugin)
+        .add_plugin(input_display::InputDisplayPlugin)
+        .add_plugin(stats::StatsPlugin)
+        .add_plugin(pace::PacePlugin)
+        .add_plugin(achievements::AchievementsPlugin)
+        .add_plugin(progression::ProgressionPlugin)
+        .add_plugin(daily::DailyPlugin)
+        .add_plugin(campaign::CampaignPlugin)
+        .add_plugin(chain::ChainPlugin)
+        .add_plugin(cheats::CheatsPlugin)
+        .add_plugin(boss::BossPlugin)
+        .add_plugin(console::ConsolePlugin)
+        .console_command("spawn_food", "spawn_food <x> <y>", spawn_food_command)
+        .console_command("set_speed", "set_speed <seconds>", set_speed_command)
+        .console_command("win", "win", win_command)
+        .console_command("seed", "seed <number>", seed_command)
+        .add_plugin(close_call::CloseCallPlugin)
+        .add_plugin(combo::ComboPlugin)
+        .add_plugin(scoring::ScoringPlugin)
+        .add_plugin(toast::ToastPlugin)
+        .add_plugin(run_events::RunEventsPlugin)
+        .add_plugin(split::SplitPlugin)
+        .add_plugin(decay::DecayPlugin)
+        .add_plugin(shrink::ShrinkPlugin)
+        .add_plugin(puzzle::PuzzlePlugin)
+        .add_plugin(profile::ProfilePlugin)
+        .add_plugin(autosave::AutosavePlugin)
+        .add_plugin(snapshot::SnapshotPlugin)
+        .add_plugin(grid::GridPlugin)
+        .add_plugin(hud::HudPlugin)
+        .add_plugin(ui_scale::UiScalePlugin)
+        .add_plugin(hunger::HungerPlugin)
+        .add_plugin(boost::BoostPlugin)
+        .add_plugin(obstacles::ObstaclesPlugin)
+        .add_plugin(doors::DoorsPlugin)
+        .add_plugin(level::LevelPlugin)
+        .add_plugin(scenes::ScenesPlugin)
+        .add_plugin(editor::EditorPlugin)
+        .add_plugin(sandbox::SandboxPlugin)
+        .add_plugin(level_select::LevelSelectPlugin)
+        .add_plugin(music::MusicPlugin)
+        .add_plugin(danger::DangerPlugin)
         .add_event::<GrowthEvent>()
         .add_event::<RenderFoodEvent>()
         .add_event::<GameOverEvent>()
+        .add_event::<NewRunEvent>()
         .run()
 }
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::obstacles::Tile;
+use crate::{Position, GRID_HEIGHT, GRID_WIDTH};
+
+/// Rooms are laid out on a lattice of this many cells, one of which is the
+/// wall line. Corridors end up four cells wide, leaving space to turn around.
+const ROOM_SIZE: i32 = 5;
+/// Chance of knocking out an extra wall after carving, so the maze has loops
+/// instead of dead ends the snake can't back out of.
+const EXTRA_OPENING_CHANCE: f64 = 0.3;
+/// Interior walls below and left of this are never placed, leaving an open
+/// area around the snake's spawn point.
+const SPAWN_CLEARING: i32 = 10;
+
+type Room = (i32, i32);
+
+fn passage(a: Room, b: Room) -> (Room, Room) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Carves a spanning tree of passages between rooms with a recursive
+/// backtracker, so every room is reachable from every other.
+fn carve_passages(cols: i32, rows: i32, rng: &mut impl Rng) -> HashSet<(Room, Room)> {
+    let mut visited = HashSet::from([(0, 0)]);
+    let mut stack = vec![(0, 0)];
+    let mut passages = HashSet::new();
+
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited: Vec<Room> = [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(|&(nx, ny)| nx >= 0 && ny >= 0 && nx < cols && ny < rows)
+            .filter(|room| !visited.contains(room))
+            .collect();
+
+        match unvisited.choose(rng) {
+            Some(&next) => {
+                visited.insert(next);
+                passages.insert(passage((x, y), next));
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    for x in 0..cols {
+        for y in 0..rows {
+            for neighbor in [(x + 1, y), (x, y + 1)] {
+                if neighbor.0 < cols && neighbor.1 < rows && rng.gen_bool(EXTRA_OPENING_CHANCE) {
+                    passages.insert(passage((x, y), neighbor));
+                }
+            }
+        }
+    }
+
+    passages
+}
+
+/// A freshly generated maze of walls. The left and bottom wall lines are
+/// solid, so the maze is closed off instead of wrapping around the edges.
+pub fn maze_layout(rng: &mut impl Rng) -> Vec<(Position, Tile)> {
+    let cols = GRID_WIDTH as i32 / ROOM_SIZE;
+    let rows = GRID_HEIGHT as i32 / ROOM_SIZE;
+    let passages = carve_passages(cols, rows, rng);
+    let mut layout = Vec::new();
+
+    for x in 0..GRID_WIDTH as i32 {
+        for y in 0..GRID_HEIGHT as i32 {
+            let (on_column, on_row) = (x % ROOM_SIZE == 0, y % ROOM_SIZE == 0);
+            let (col, row) = (x / ROOM_SIZE, y / ROOM_SIZE);
+            let in_clearing = (1..SPAWN_CLEARING).contains(&x) && (1..SPAWN_CLEARING).contains(&y);
+
+            let is_wall = match (on_column, on_row) {
+                (false, false) => false,
+                _ if in_clearing => false,
+                (true, true) => true,
+                (true, false) => {
+                    col == 0 || !passages.contains(&passage((col - 1, row), (col, row)))
+                }
+                (false, true) => {
+                    row == 0 || !passages.contains(&passage((col, row - 1), (col, row)))
+                }
+            };
+
+            if is_wall {
+                layout.push((Position::new(x, y), Tile::Wall));
+            }
+        }
+    }
+
+    layout
+}
@@ -0,0 +1,667 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::achievements::{Achievements, Unlockable};
+use crate::animation::RainbowSkin;
+use crate::campaign::{is_boss_stage, stage_level, Campaign};
+use crate::daily::{daily_level, today, DailyStreak};
+use crate::kiosk::Kiosk;
+use crate::layers::Layer;
+use crate::level::{ActiveLevel, Level};
+use crate::mode::GameMode;
+use crate::profile::{is_profile_name_char, Profiles, SwitchProfileEvent, MAX_PROFILE_NAME_LEN};
+use crate::progression::Progression;
+use crate::puzzle::Medal;
+use crate::settings::{CosmeticSettings, KeyboardCaptured, Settings};
+use crate::snapshot::ContinueRunEvent;
+use crate::stats::Stats;
+use crate::theme::{Theme, ThemeChoice};
+use crate::toast::ToastEvent;
+use crate::transition::{ScreenFade, TransitionEvent};
+use crate::{
+    AppState, NewRunEvent, RunKind, BG_COLOR, GRID_HEIGHT, GRID_WIDTH, WIN_HEIGHT, WIN_WIDTH,
+};
+
+const MENU_FONT_SIZE: f32 = 32.0;
+const DETAIL_FONT_SIZE: f32 = 18.0;
+const SELECTED_COLOR: Color = Color::rgb(0.9, 0.8, 0.2);
+const CREDITS_FONT_SIZE: f32 = 20.0;
+const HEATMAP_CELL_SIZE: f32 = 12.0;
+const HEATMAP_Y: f32 = -60.0;
+const HEATMAP_COLD_COLOR: Color = Color::rgb(0.12, 0.12, 0.16);
+const HEATMAP_WARM_COLOR: Color = Color::rgb(0.35, 0.1, 0.1);
+const HEATMAP_HOT_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+const CREDITS: &str = "Controls\n\
+     Arrow keys or the chosen control preset steer, Shift boosts, P or Esc pauses\n\
+     E opens the level editor, L picks a custom level\n\
+     V vsync, F frame rate cap, G grid lines, H hunger\n\
+     A auto-pause, M reduced motion\n\n\
+     Credits\n\
+     Made by Jonxslays and contributors\n\
+     Built with Bevy\n\
+     Fira Sans by Mozilla, under the SIL Open Font License\n\n\
+     Esc back";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Play,
+    Continue,
+    Campaign,
+    Daily,
+    Mode,
+    Profile,
+    Controls,
+    Rainbow,
+    Theme,
+    CustomLevels,
+    Stats,
+    Credits,
+    Quit,
+}
+
+const MENU_ITEMS: [(MenuItem, &str); 13] = [
+    (MenuItem::Play, "Play"),
+    (MenuItem::Continue, "Continue"),
+    (MenuItem::Campaign, "Campaign"),
+    (MenuItem::Daily, "Daily Challenge"),
+    (MenuItem::Mode, "Mode"),
+    (MenuItem::Profile, "Profile"),
+    (MenuItem::Controls, "Controls"),
+    (MenuItem::Rainbow, "Rainbow snake"),
+    (MenuItem::Theme, "Theme"),
+    (MenuItem::CustomLevels, "Custom Levels"),
+    (MenuItem::Stats, "Stats"),
+    (MenuItem::Credits, "Credits"),
+    (MenuItem::Quit, "Quit"),
+];
+
+#[derive(Default)]
+struct MenuSelection(usize);
+
+/// The campaign stage picked on the menu, or `None` for the furthest one
+/// unlocked.
+#[derive(Default)]
+struct CampaignSelection(Option<u32>);
+
+impl CampaignSelection {
+    fn stage(&self, campaign: &Campaign) -> u32 {
+        self.0.unwrap_or(u32::MAX).min(campaign.last_playable())
+    }
+}
+
+/// The name being typed for a new profile, while the prompt is open.
+#[derive(Default)]
+struct NewProfileName(Option<String>);
+
+/// Everything spawned for the main menu or credits, torn down on exit.
+#[derive(Component)]
+struct MenuOnly;
+
+#[derive(Component)]
+struct MainMenuText;
+
+fn spawn_menu_panel(commands: &mut Commands) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: BG_COLOR,
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(MenuOnly)
+        .insert(Layer::Overlay.at(1.0));
+}
+
+fn enter_main_menu(mut commands: Commands) {
+    spawn_menu_panel(&mut commands);
+
+    commands
+        .spawn_bundle(Text2dBundle::default())
+        .insert(MainMenuText)
+        .insert(MenuOnly)
+        .insert(Layer::Overlay.at(2.0));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn main_menu_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    profiles: Res<Profiles>,
+    mut new_profile: ResMut<NewProfileName>,
+    mut selection: ResMut<MenuSelection>,
+    campaign: Res<Campaign>,
+    campaign_selection: Res<CampaignSelection>,
+    mut run_kind: ResMut<RunKind>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut game_mode: ResMut<GameMode>,
+    mut transitions: EventWriter<TransitionEvent>,
+    mut switch_profile: EventWriter<SwitchProfileEvent>,
+    mut new_run: EventWriter<NewRunEvent>,
+    mut app_exit: EventWriter<AppExit>,
+    kiosk: Res<Kiosk>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    // Also skip the frame a name was just confirmed, or its Enter would open
+    // the prompt straight back up.
+    if fade.is_active() || new_profile.0.is_some() || new_profile.is_changed() {
+        return;
+    }
+
+    let count = MENU_ITEMS.len();
+    let item = MENU_ITEMS[selection.0].0;
+
+    if item == MenuItem::Profile {
+        if keyboard_input.just_pressed(KeyCode::Left) {
+            switch_profile.send(SwitchProfileEvent(profiles.neighbour(-1).to_string()));
+        } else if keyboard_input.just_pressed(KeyCode::Right) {
+            switch_profile.send(SwitchProfileEvent(profiles.neighbour(1).to_string()));
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        selection.0 = (selection.0 + count - 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        selection.0 = (selection.0 + 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Return) {
+        match item {
+            MenuItem::Play => {
+                // Swap a daily or campaign board back out for the mode's own.
+                if *run_kind != RunKind::Free {
+                    *run_kind = RunKind::Free;
+                    active_level.0 = Level::for_mode(*game_mode);
+                }
+
+                new_run.send(NewRunEvent);
+                transitions.send(TransitionEvent(AppState::Playing));
+            }
+            // Handled by `continue_input`.
+            MenuItem::Continue => (),
+            MenuItem::Campaign => {
+                let stage = campaign_selection.stage(&campaign);
+                *run_kind = RunKind::Campaign(stage);
+                active_level.0 = stage_level(stage);
+                *game_mode = GameMode::Classic;
+
+                new_run.send(NewRunEvent);
+                transitions.send(TransitionEvent(AppState::Playing));
+            }
+            MenuItem::Daily => {
+                let day = today();
+                *run_kind = RunKind::Daily(day);
+                active_level.0 = daily_level(day);
+                *game_mode = GameMode::Classic;
+
+                new_run.send(NewRunEvent);
+                transitions.send(TransitionEvent(AppState::Playing));
+            }
+            MenuItem::Profile => new_profile.0 = Some(String::new()),
+            // Options, handled by `menu_option_input`.
+            MenuItem::Mode | MenuItem::Controls | MenuItem::Rainbow | MenuItem::Theme => (),
+            MenuItem::CustomLevels => transitions.send(TransitionEvent(AppState::LevelSelect)),
+            MenuItem::Stats => transitions.send(TransitionEvent(AppState::Stats)),
+            MenuItem::Credits => transitions.send(TransitionEvent(AppState::Credits)),
+            MenuItem::Quit if kiosk.enabled => {
+                toasts.send(ToastEvent::info("Quitting is turned off"))
+            }
+            MenuItem::Quit => app_exit.send(AppExit),
+        }
+    }
+}
+
+/// Steps `from` along with `cycle` until it lands on something unlocked.
+/// The first option of everything that cycles is never locked, so this stops.
+fn next_unlocked<T: Unlockable>(
+    achievements: &Achievements,
+    from: T,
+    cycle: impl Fn(&T) -> T,
+) -> T {
+    let mut next = cycle(&from);
+    while !achievements.unlocks(&next) {
+        next = cycle(&next);
+    }
+    next
+}
+
+/// Left and Right step through the selected option, Enter steps forwards.
+#[allow(clippy::too_many_arguments)]
+fn menu_option_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    selection: Res<MenuSelection>,
+    achievements: Res<Achievements>,
+    campaign: Res<Campaign>,
+    mut campaign_selection: ResMut<CampaignSelection>,
+    mut settings: ResMut<Settings>,
+    mut cosmetics: ResMut<CosmeticSettings>,
+    mut game_mode: ResMut<GameMode>,
+    mut active_level: ResMut<ActiveLevel>,
+) {
+    let offset = if keyboard_input.just_pressed(KeyCode::Left) {
+        -1
+    } else if keyboard_input.any_just_pressed([KeyCode::Right, KeyCode::Return]) {
+        1
+    } else {
+        return;
+    };
+
+    if fade.is_active() {
+        return;
+    }
+
+    match MENU_ITEMS[selection.0].0 {
+        // Enter starts the stage instead, see `main_menu_input`.
+        MenuItem::Campaign if offset < 0 || !keyboard_input.just_pressed(KeyCode::Return) => {
+            let last = campaign.last_playable() as isize;
+            let stage = campaign_selection.stage(&campaign) as isize;
+            campaign_selection.0 = Some(((stage - 1 + offset).rem_euclid(last) + 1) as u32);
+        }
+        MenuItem::Mode => {
+            let mode = next_unlocked(&achievements, *game_mode, |mode| mode.cycle(offset));
+            if mode != *game_mode {
+                *game_mode = mode;
+                active_level.0 = Level::for_mode(mode);
+            }
+        }
+        MenuItem::Controls => settings.controls.preset = settings.controls.preset.cycle(offset),
+        MenuItem::Rainbow if achievements.unlocks(&RainbowSkin) => {
+            cosmetics.rainbow = !cosmetics.rainbow;
+        }
+        MenuItem::Theme => {
+            cosmetics.theme =
+                next_unlocked(&achievements, cosmetics.theme, |theme| theme.cycle(offset));
+        }
+        _ => (),
+    }
+}
+
+fn continue_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    selection: Res<MenuSelection>,
+    new_profile: Res<NewProfileName>,
+    mut continue_run: EventWriter<ContinueRunEvent>,
+) {
+    if fade.is_active() || new_profile.0.is_some() || new_profile.is_changed() {
+        return;
+    }
+
+    if MENU_ITEMS[selection.0].0 == MenuItem::Continue
+        && keyboard_input.just_pressed(KeyCode::Return)
+    {
+        continue_run.send(ContinueRunEvent);
+    }
+}
+
+/// A greyed out line listing what's still locked among `options`, with what
+/// it takes to unlock each one.
+fn locked_line<T: Unlockable>(
+    achievements: &Achievements,
+    options: impl IntoIterator<Item = (T, &'static str)>,
+) -> Option<String> {
+    let locked: Vec<String> = options
+        .into_iter()
+        .filter(|(option, _)| !achievements.unlocks(option))
+        .filter_map(|(option, name)| Some(format!("{} ({})", name, option.required()?.condition())))
+        .collect();
+
+    (!locked.is_empty()).then(|| format!("Locked: {}\n", locked.join(", ")))
+}
+
+fn new_profile_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut typed: EventReader<ReceivedCharacter>,
+    mut new_profile: ResMut<NewProfileName>,
+    mut captured: ResMut<KeyboardCaptured>,
+    mut switch_profile: EventWriter<SwitchProfileEvent>,
+) {
+    let typed: Vec<char> = typed.iter().map(|event| event.char).collect();
+    let name = match new_profile.0.as_mut() {
+        Some(name) => name,
+        None => return,
+    };
+
+    name.extend(typed.into_iter().filter(|c| is_profile_name_char(*c)));
+    name.truncate(MAX_PROFILE_NAME_LEN);
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        name.pop();
+    }
+
+    let done = if keyboard_input.just_pressed(KeyCode::Return) {
+        if !name.is_empty() {
+            switch_profile.send(SwitchProfileEvent(name.clone()));
+        }
+
+        true
+    } else {
+        keyboard_input.just_pressed(KeyCode::Escape)
+    };
+
+    if done {
+        new_profile.0 = None;
+    }
+
+    captured.0 = !done;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_main_menu(
+    asset_server: Res<AssetServer>,
+    selection: Res<MenuSelection>,
+    profiles: Res<Profiles>,
+    new_profile: Res<NewProfileName>,
+    settings: Res<Settings>,
+    cosmetics: Res<CosmeticSettings>,
+    achievements: Res<Achievements>,
+    game_mode: Res<GameMode>,
+    progression: Res<Progression>,
+    streak: Res<DailyStreak>,
+    campaign: Res<Campaign>,
+    campaign_selection: Res<CampaignSelection>,
+    spawned: Query<(), Added<MainMenuText>>,
+    mut query: Query<&mut Text, With<MainMenuText>>,
+) {
+    let changed = selection.is_changed()
+        || profiles.is_changed()
+        || new_profile.is_changed()
+        || settings.is_changed()
+        || cosmetics.is_changed()
+        || achievements.is_changed()
+        || game_mode.is_changed()
+        || progression.is_changed()
+        || streak.is_changed()
+        || campaign.is_changed()
+        || campaign_selection.is_changed();
+    if !changed && spawned.is_empty() {
+        return;
+    }
+
+    let style = |font, font_size, color| TextStyle {
+        font: asset_server.load(font),
+        font_size,
+        color,
+    };
+
+    let (level, xp, needed) = progression.level();
+    let mut sections = vec![
+        TextSection {
+            value: "Snake!\n".to_string(),
+            style: style("fonts/FiraSans-Bold.ttf", 64.0, Color::rgb(0.0, 0.7, 0.0)),
+        },
+        TextSection {
+            value: format!("Level {}  ({}/{} XP)\n\n", level, xp, needed),
+            style: style("fonts/FiraSans-Regular.ttf", DETAIL_FONT_SIZE, Color::GRAY),
+        },
+    ];
+
+    for (i, (item, label)) in MENU_ITEMS.iter().enumerate() {
+        let selected = i == selection.0;
+        let mut color = if selected {
+            SELECTED_COLOR
+        } else {
+            Color::WHITE
+        };
+        // Shown under the selected option, listing what it can't be set to yet.
+        let mut locked = None;
+
+        let label = match (item, &new_profile.0) {
+            (MenuItem::Campaign, _) => {
+                let stage = campaign_selection.stage(&campaign);
+                let boss = if is_boss_stage(stage) { ", boss" } else { "" };
+                let done = if campaign.is_complete() {
+                    " (complete)"
+                } else {
+                    ""
+                };
+
+                if selected {
+                    format!("< Stage {}{} >{}", stage, boss, done)
+                } else {
+                    format!("{}: Stage {}{}", label, stage, done)
+                }
+            }
+            (MenuItem::Daily, _) => {
+                let day = today();
+                match (streak.current(day), streak.done_on(day)) {
+                    (0, _) => label.to_string(),
+                    (days, true) => format!("{} (done, {} day streak)", label, days),
+                    (days, false) => format!("{} ({} day streak)", label, days),
+                }
+            }
+            (MenuItem::Mode, _) => {
+                if selected {
+                    locked = locked_line(
+                        &achievements,
+                        [GameMode::Length, GameMode::Walls, GameMode::Maze]
+                            .map(|mode| (mode, mode.name())),
+                    );
+                    format!("< {} >", game_mode.name())
+                } else {
+                    format!("{}: {}", label, game_mode.name())
+                }
+            }
+            (MenuItem::Profile, Some(name)) => format!("New profile: {}_", name),
+            (MenuItem::Profile, None) if selected => format!("< {} >", profiles.active()),
+            (MenuItem::Profile, None) => format!("{}: {}", label, profiles.active()),
+            (MenuItem::Controls, _) if selected => {
+                format!("< {} >", settings.controls.preset.name())
+            }
+            (MenuItem::Controls, _) => format!("{}: {}", label, settings.controls.preset.name()),
+            (MenuItem::Rainbow, _) if !achievements.unlocks(&RainbowSkin) => {
+                color = Color::GRAY;
+                let condition = RainbowSkin.required().unwrap().condition();
+                format!("{} (locked: {})", label, condition)
+            }
+            (MenuItem::Rainbow, _) if cosmetics.rainbow => format!("{}: On", label),
+            (MenuItem::Rainbow, _) => format!("{}: Off", label),
+            (MenuItem::Theme, _) => {
+                let theme = match cosmetics.theme {
+                    ThemeChoice::Auto => format!("Auto ({})", cosmetics.theme.resolve().name()),
+                    ThemeChoice::Fixed(theme) => theme.name().to_string(),
+                };
+
+                if selected {
+                    locked = locked_line(
+                        &achievements,
+                        [Theme::Halloween, Theme::Winter]
+                            .map(|theme| (ThemeChoice::Fixed(theme), theme.name())),
+                    );
+                    format!("< {} >", theme)
+                } else {
+                    format!("{}: {}", label, theme)
+                }
+            }
+            _ => label.to_string(),
+        };
+
+        sections.push(TextSection {
+            value: format!("{}\n", label),
+            style: style("fonts/FiraSans-Regular.ttf", MENU_FONT_SIZE, color),
+        });
+
+        if let Some(locked) = locked {
+            sections.push(TextSection {
+                value: locked,
+                style: style("fonts/FiraSans-Regular.ttf", DETAIL_FONT_SIZE, Color::GRAY),
+            });
+        }
+    }
+
+    for mut text in query.iter_mut() {
+        *text = Text {
+            sections: sections.clone(),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        };
+    }
+}
+
+/// Leaves a finished run for the main menu.
+fn game_over_to_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if !fade.is_active() && keyboard_input.just_pressed(KeyCode::Escape) {
+        transitions.send(TransitionEvent(AppState::MainMenu));
+    }
+}
+
+fn enter_credits(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_menu_panel(&mut commands);
+
+    let about = format!("Snake v{}\n\n{}", env!("CARGO_PKG_VERSION"), CREDITS);
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                about,
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: CREDITS_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            ..default()
+        })
+        .insert(MenuOnly)
+        .insert(Layer::Overlay.at(2.0));
+}
+
+/// Lifetime totals over a heatmap of where food has been eaten, brighter
+/// where more of it has.
+fn enter_stats(mut commands: Commands, asset_server: Res<AssetServer>, stats: Res<Stats>) {
+    spawn_menu_panel(&mut commands);
+
+    let medals = Medal::ALL
+        .iter()
+        .map(|medal| format!("{} {}", medal.name(), stats.medals(*medal)))
+        .collect::<Vec<_>>()
+        .join("    ");
+
+    let totals = format!(
+        "Runs played {}    Wins {}    Best score {}    Food eaten {}\n\
+         Puzzle medals: {}\n\n\
+         Where food was eaten\n\n\
+         Esc back",
+        stats.runs_played, stats.wins, stats.best_score, stats.food_eaten, medals
+    );
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                totals,
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: CREDITS_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Top,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, WIN_HEIGHT / 2.0 - HEATMAP_CELL_SIZE, 0.0),
+            ..default()
+        })
+        .insert(MenuOnly)
+        .insert(Layer::Overlay.at(2.0));
+
+    let size = Vec2::new(
+        GRID_WIDTH as f32 * HEATMAP_CELL_SIZE,
+        GRID_HEIGHT as f32 * HEATMAP_CELL_SIZE,
+    );
+    let origin = Vec2::new(0.0, HEATMAP_Y) - (size - Vec2::splat(HEATMAP_CELL_SIZE)) / 2.0;
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: HEATMAP_COLD_COLOR,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, HEATMAP_Y, 0.0),
+            ..default()
+        })
+        .insert(MenuOnly)
+        .insert(Layer::Overlay.at(2.0));
+
+    let (most, cells) = stats.heatmap();
+
+    for (position, count) in cells.filter(|(_, count)| *count > 0) {
+        let heat = count as f32 / most as f32;
+        let [r1, g1, b1, _] = HEATMAP_WARM_COLOR.as_rgba_f32();
+        let [r2, g2, b2, _] = HEATMAP_HOT_COLOR.as_rgba_f32();
+        let offset = Vec2::new(position.x as f32, position.y as f32) * HEATMAP_CELL_SIZE;
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(
+                        r1 + (r2 - r1) * heat,
+                        g1 + (g2 - g1) * heat,
+                        b1 + (b2 - b1) * heat,
+                    ),
+                    custom_size: Some(Vec2::splat(HEATMAP_CELL_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation((origin + offset).extend(0.0)),
+                ..default()
+            })
+            .insert(MenuOnly)
+            .insert(Layer::Overlay.at(2.5));
+    }
+}
+
+fn credits_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    fade: Res<ScreenFade>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if !fade.is_active() && keyboard_input.just_pressed(KeyCode::Escape) {
+        transitions.send(TransitionEvent(AppState::MainMenu));
+    }
+}
+
+fn exit_menu(mut commands: Commands, query: Query<Entity, With<MenuOnly>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuSelection>()
+            .init_resource::<NewProfileName>()
+            .init_resource::<CampaignSelection>()
+            .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(enter_main_menu))
+            .add_system_set(SystemSet::on_exit(AppState::MainMenu).with_system(exit_menu))
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu)
+                    .with_system(new_profile_input)
+                    .with_system(main_menu_input.after(new_profile_input))
+                    .with_system(menu_option_input)
+                    .with_system(continue_input.after(new_profile_input))
+                    .with_system(update_main_menu.after(main_menu_input)),
+            )
+            .add_system_set(SystemSet::on_enter(AppState::Credits).with_system(enter_credits))
+            .add_system_set(SystemSet::on_exit(AppState::Credits).with_system(exit_menu))
+            .add_system_set(SystemSet::on_update(AppState::Credits).with_system(credits_input))
+            .add_system_set(SystemSet::on_enter(AppState::Stats).with_system(enter_stats))
+            .add_system_set(SystemSet::on_exit(AppState::Stats).with_system(exit_menu))
+            .add_system_set(SystemSet::on_update(AppState::Stats).with_system(credits_input))
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver).with_system(game_over_to_menu),
+            );
+    }
+}
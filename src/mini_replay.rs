@@ -0,0 +1,186 @@
+//! A small looping replay of the last few seconds of a lost run, shown under
+//! the game over text so it's clear what went wrong. It's drawn from the
+//! board [`Observation`] taken on every tick.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::cleanup::DespawnOnStateExit;
+use crate::layers::Layer;
+use crate::observation::{Cell, Observation, ObservationEvent};
+use crate::obstacles::WALL_COLOR;
+use crate::{
+    AppState, GameOverText, GameStatus, NewRunEvent, FOOD_COLOR, MOVEMENT_STEP, SNAKE_BODY_COLOR,
+    SNAKE_HEAD_COLOR,
+};
+
+/// Ticks kept for the replay, three seconds at the normal speed.
+const REPLAY_TICKS: usize = 30;
+/// Frames spent on the final tick before the replay loops.
+const REPLAY_HOLD_FRAMES: usize = 10;
+const REPLAY_CELL_SIZE: f32 = 5.0;
+const REPLAY_Y: f32 = -130.0;
+const REPLAY_BG_COLOR: Color = Color::rgb(0.12, 0.12, 0.12);
+const REPLAY_BORDER_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+const REPLAY_BORDER: f32 = 2.0;
+
+/// The board on each of the latest ticks of the run, oldest first.
+#[derive(Default)]
+struct ReplayBuffer(VecDeque<Observation>);
+
+#[derive(Component)]
+pub struct MiniReplay {
+    frames: Vec<Observation>,
+    frame: usize,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct MiniReplayCell;
+
+fn buffer_observations(
+    mut buffer: ResMut<ReplayBuffer>,
+    mut new_runs: EventReader<NewRunEvent>,
+    mut observations: EventReader<ObservationEvent>,
+) {
+    if new_runs.iter().next().is_some() {
+        buffer.0.clear();
+    }
+
+    for ObservationEvent(board) in observations.iter() {
+        buffer.0.push_back(board.clone());
+
+        while buffer.0.len() > REPLAY_TICKS {
+            buffer.0.pop_front();
+        }
+    }
+}
+
+/// Opens the replay alongside the text for a lost run, which waits for the
+/// death sequence to finish.
+fn show_mini_replay(
+    mut commands: Commands,
+    buffer: Res<ReplayBuffer>,
+    texts: Query<(), Added<GameOverText>>,
+    statuses: Query<&GameStatus>,
+) {
+    let lost = matches!(statuses.iter().next(), Some(GameStatus::Lost));
+
+    if texts.is_empty() || !lost || buffer.0.is_empty() {
+        return;
+    }
+
+    let frames: Vec<Observation> = buffer.0.iter().cloned().collect();
+    let size = Vec2::new(
+        frames[0].width as f32 * REPLAY_CELL_SIZE,
+        frames[0].height as f32 * REPLAY_CELL_SIZE,
+    );
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: REPLAY_BORDER_COLOR,
+                custom_size: Some(size + Vec2::splat(REPLAY_BORDER * 2.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, REPLAY_Y, 0.0),
+            ..default()
+        })
+        .insert(Layer::Hud.at(2.0))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: REPLAY_BG_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Layer::Hud.at(2.1));
+        })
+        .insert(MiniReplay {
+            frames,
+            frame: 0,
+            timer: Timer::from_seconds(MOVEMENT_STEP as f32, true),
+        })
+        .insert(DespawnOnStateExit(AppState::GameOver));
+}
+
+pub fn cell_color(cell: u8) -> Option<Color> {
+    match cell {
+        c if c == Cell::Body as u8 => Some(SNAKE_BODY_COLOR),
+        c if c == Cell::Head as u8 => Some(SNAKE_HEAD_COLOR),
+        c if c == Cell::Food as u8 => Some(FOOD_COLOR),
+        c if c == Cell::Wall as u8 => Some(WALL_COLOR),
+        _ => None,
+    }
+}
+
+/// Steps through the frames, holding on the last one for a moment before
+/// starting over.
+fn play_mini_replay(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut replays: Query<(Entity, &mut MiniReplay, ChangeTrackers<MiniReplay>)>,
+    cells: Query<Entity, With<MiniReplayCell>>,
+) {
+    for (ent, mut replay, tracker) in replays.iter_mut() {
+        if !replay.timer.tick(time.delta()).just_finished() && !tracker.is_added() {
+            continue;
+        }
+
+        let (frame, last) = (replay.frame, replay.frames.len() - 1);
+        replay.frame = (frame + 1) % (replay.frames.len() + REPLAY_HOLD_FRAMES);
+        let board = &replay.frames[frame.min(last)];
+
+        for cell in cells.iter() {
+            commands.entity(cell).despawn();
+        }
+
+        let origin = Vec2::new(
+            -(board.width as f32 - 1.0) * REPLAY_CELL_SIZE / 2.0,
+            -(board.height as f32 - 1.0) * REPLAY_CELL_SIZE / 2.0,
+        );
+
+        commands.entity(ent).with_children(|parent| {
+            for (i, cell) in board.cells.iter().enumerate() {
+                let color = match cell_color(*cell) {
+                    Some(color) => color,
+                    None => continue,
+                };
+
+                let (x, y) = (i as u32 % board.width, i as u32 / board.width);
+                let offset = origin + Vec2::new(x as f32, y as f32) * REPLAY_CELL_SIZE;
+
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color,
+                            custom_size: Some(Vec2::splat(REPLAY_CELL_SIZE)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(offset.extend(0.0)),
+                        ..default()
+                    })
+                    .insert(MiniReplayCell)
+                    .insert(Layer::Hud.at(2.2));
+            }
+        });
+    }
+}
+
+pub struct MiniReplayPlugin;
+
+impl Plugin for MiniReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayBuffer>()
+            .add_system(buffer_observations)
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(show_mini_replay)
+                    .with_system(play_mini_replay.after(show_mini_replay)),
+            );
+    }
+}
@@ -0,0 +1,151 @@
+//! Mirror mode: one snake with a head at each side of the board. The second
+//! head and the body behind it are the first snake reflected left to right,
+//! so they move as one from the same keys, Left turning one head each way.
+//! Either head can eat, growing both, and either running into the other's
+//! body, or a wall, ends the run. A wall runs down the middle column, where
+//! the two heads would otherwise meet.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::cheats::Cheats;
+use crate::layers::DrawOrder;
+use crate::mode::GameMode;
+use crate::obstacles::{Tile, Wall};
+use crate::{
+    position_translation, Food, GameStatus, GrowthEvent, Position, Size, SnakeBody, SnakeHead,
+    SnakePart, GRID_HEIGHT, GRID_WIDTH,
+};
+
+/// The reflection of a part of the snake, on the far side of the board.
+#[derive(Component)]
+struct MirrorOf(Entity);
+
+/// The same cell seen in the mirror, down the middle of the board.
+pub fn mirror_position(position: Position) -> Position {
+    Position::new(GRID_WIDTH as i32 - 1 - position.x, position.y)
+}
+
+/// The wall down the middle column, which the heads would cross at the same
+/// time.
+pub fn mirror_layout() -> Vec<(Position, Tile)> {
+    let middle = GRID_WIDTH as i32 / 2;
+
+    (0..GRID_HEIGHT as i32)
+        .map(|y| (Position::new(middle, y), Tile::Wall))
+        .collect()
+}
+
+/// Keeps a reflection of every part of the snake, however it's grown or been
+/// cut short, drawn the same way as the part it reflects.
+fn sync_mirror(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    body: Res<SnakeBody>,
+    originals: Query<(&Position, &Sprite, &Size, &DrawOrder), Without<MirrorOf>>,
+    mut mirrors: Query<(Entity, &MirrorOf, &mut Position, &mut Sprite)>,
+) {
+    let parts: HashSet<Entity> = match *game_mode {
+        GameMode::Mirror => body.0.iter().copied().collect(),
+        _ => HashSet::new(),
+    };
+    let mut mirrored = HashSet::new();
+
+    for (ent, MirrorOf(original), mut position, mut sprite) in mirrors.iter_mut() {
+        match originals.get(*original) {
+            Ok((original_position, original_sprite, _, _)) if parts.contains(original) => {
+                *position = mirror_position(*original_position);
+                sprite.color = original_sprite.color;
+                mirrored.insert(*original);
+            }
+            _ => commands.entity(ent).despawn(),
+        }
+    }
+
+    for part in parts.difference(&mirrored) {
+        let (position, sprite, size, order) = match originals.get(*part) {
+            Ok(original) => original,
+            Err(_) => continue,
+        };
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: sprite.color,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(MirrorOf(*part))
+            .insert(mirror_position(*position))
+            .insert(*size)
+            .insert(*order);
+    }
+}
+
+/// Eats whatever's under the mirrored head, just as [`crate::snake_eating`]
+/// does for the real one.
+pub fn mirror_eating(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut growth_writer: EventWriter<GrowthEvent>,
+    food_positions: Query<(Entity, &Position), With<Food>>,
+    head_positions: Query<&Position, With<SnakeHead>>,
+) {
+    if *game_mode != GameMode::Mirror {
+        return;
+    }
+
+    for head in head_positions.iter().map(|head| mirror_position(*head)) {
+        for (ent, food) in food_positions.iter() {
+            if *food == head {
+                commands.entity(ent).despawn();
+                growth_writer.send(GrowthEvent);
+            }
+        }
+    }
+}
+
+/// Ends the run when the mirrored head lands on a wall or any part of the
+/// real snake. That covers the real head landing on the mirrored body too,
+/// which is the same thing seen from the other side.
+pub fn mirror_collision(
+    game_mode: Res<GameMode>,
+    cheats: Res<Cheats>,
+    heads: Query<&Position, With<SnakeHead>>,
+    parts: Query<&Position, With<SnakePart>>,
+    walls: Query<&Position, With<Wall>>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    if *game_mode != GameMode::Mirror || cheats.invincible {
+        return;
+    }
+
+    let head = match heads.iter().next() {
+        Some(head) => mirror_position(*head),
+        None => return,
+    };
+
+    let hit = parts
+        .iter()
+        .chain(walls.iter())
+        .any(|position| *position == head);
+
+    if hit {
+        if let Some(mut status) = game_status.iter_mut().next() {
+            status.end(GameStatus::Lost);
+        }
+    }
+}
+
+pub struct MirrorPlugin;
+
+impl Plugin for MirrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            sync_mirror.before(position_translation),
+        );
+    }
+}
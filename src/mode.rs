@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::FOOD_WIN_AMOUNT;
+
+const LENGTH_WIN_AMOUNT: usize = 40;
+
+/// The rule set for a run, picked with `--mode <name>` on the command line or
+/// from the main menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    Classic,
+    Length,
+    Walls,
+    Maze,
+    /// No way to win, but walls and food can be placed with the mouse.
+    Sandbox,
+    /// Hand made boards, cleared in a set number of moves, where the snake
+    /// only moves when a direction is pressed.
+    Puzzle,
+    /// Two heads on one snake, one the mirror image of the other.
+    Mirror,
+    /// No way to win, and a cap on the snake's length, see
+    /// [`crate::length_cap`].
+    Endless,
+    /// Classic rules, but the score drains away, see [`crate::decay`].
+    Decay,
+}
+
+impl GameMode {
+    const ALL: [Self; 9] = [
+        Self::Classic,
+        Self::Length,
+        Self::Walls,
+        Self::Maze,
+        Self::Sandbox,
+        Self::Puzzle,
+        Self::Mirror,
+        Self::Endless,
+        Self::Decay,
+    ];
+
+    pub fn from_args() -> Self {
+        let mut args = std::env::args().skip_while(|arg| arg != "--mode").skip(1);
+
+        match args.next().as_deref() {
+            None | Some("classic") => Self::Classic,
+            Some("length") => Self::Length,
+            Some("walls") => Self::Walls,
+            Some("maze") => Self::Maze,
+            Some("sandbox") => Self::Sandbox,
+            Some("puzzle") => Self::Puzzle,
+            Some("mirror") => Self::Mirror,
+            Some("endless") => Self::Endless,
+            Some("decay") => Self::Decay,
+            Some(other) => {
+                eprintln!("Unknown mode '{}', falling back to classic", other);
+                Self::Classic
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Length => "Length",
+            Self::Walls => "Walls",
+            Self::Maze => "Maze",
+            Self::Sandbox => "Sandbox",
+            Self::Puzzle => "Puzzle",
+            Self::Mirror => "Mirror",
+            Self::Endless => "Endless",
+            Self::Decay => "Decay",
+        }
+    }
+
+    /// The mode `offset` places along from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        let count = Self::ALL.len() as isize;
+        let current = Self::ALL.iter().position(|m| m == self).unwrap() as isize;
+        Self::ALL[(current + offset).rem_euclid(count) as usize]
+    }
+
+    pub fn win_condition(&self) -> Option<WinCondition> {
+        match self {
+            Self::Classic | Self::Walls | Self::Maze | Self::Mirror | Self::Decay => {
+                Some(WinCondition::FoodDevoured(FOOD_WIN_AMOUNT))
+            }
+            Self::Length => Some(WinCondition::SnakeLength(LENGTH_WIN_AMOUNT)),
+            // Puzzles keep track of their own, see `puzzle::puzzle_status`.
+            Self::Sandbox | Self::Puzzle | Self::Endless => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinCondition {
+    FoodDevoured(u32),
+    SnakeLength(usize),
+}
+
+impl WinCondition {
+    pub fn is_met(&self, devoured: u32, length: usize) -> bool {
+        match *self {
+            Self::FoodDevoured(target) => devoured >= target,
+            Self::SnakeLength(target) => length >= target,
+        }
+    }
+
+    /// How far along the run is towards winning, from 0 to 1.
+    pub fn progress(&self, devoured: u32, length: usize) -> f32 {
+        let progress = match *self {
+            Self::FoodDevoured(target) => devoured as f32 / target as f32,
+            Self::SnakeLength(target) => length as f32 / target as f32,
+        };
+
+        progress.clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use bevy::audio::AudioSink;
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+use crate::{AppState, RenderedFood, Rules, SnakeBody};
+
+/// Looping stems of the same track, quietest layer first. They all play the
+/// whole time, the director just decides how loud each one is.
+const MUSIC_STEMS: [&str; 3] = ["music/base.ogg", "music/pulse.ogg", "music/lead.ogg"];
+const STEM_FADE_PER_SECOND: f32 = 0.25;
+/// Snake length at which every stem is playing.
+const FULL_INTENSITY_LENGTH: usize = 30;
+
+struct Stem {
+    sink: Handle<AudioSink>,
+    volume: f32,
+}
+
+#[derive(Default)]
+struct MusicDirector {
+    stems: Vec<Stem>,
+}
+
+fn start_music(
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    audio: Res<Audio>,
+    sinks: Res<Assets<AudioSink>>,
+    mut director: ResMut<MusicDirector>,
+) {
+    for (i, path) in MUSIC_STEMS.iter().enumerate() {
+        // The stems aren't bundled, so play whatever has been dropped in.
+        if !Path::new("assets").join(path).exists() {
+            continue;
+        }
+
+        let volume = if i == 0 {
+            settings.audio.music_volume
+        } else {
+            0.0
+        };
+        let settings = PlaybackSettings {
+            repeat: true,
+            volume,
+            speed: 1.0,
+        };
+
+        let sink = audio.play_with_settings(asset_server.load(*path), settings);
+        director.stems.push(Stem {
+            sink: sinks.get_handle(sink),
+            volume,
+        });
+    }
+}
+
+/// How tense the run is, from 0 to 1. Grows with the snake, and spikes as
+/// uneaten food piles up towards the loss threshold.
+fn intensity(body: &SnakeBody, rules: &Rules, rendered: Option<&RenderedFood>) -> f32 {
+    let growth = body.0.len() as f32 / FULL_INTENSITY_LENGTH as f32;
+    let danger = rendered.map_or(0.0, |rendered| {
+        rendered.0 as f32 / rules.food_to_lose as f32
+    });
+
+    growth.max(danger).clamp(0.0, 1.0)
+}
+
+/// Fades the upper stems in one after another as the intensity climbs, and
+/// back out to the base layer whenever the game isn't being played.
+#[allow(clippy::too_many_arguments)]
+fn music_director(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    rules: Res<Rules>,
+    body: Res<SnakeBody>,
+    rendered: Query<&RenderedFood>,
+    sinks: Res<Assets<AudioSink>>,
+    mut director: ResMut<MusicDirector>,
+) {
+    let intensity = match app_state.current() {
+        AppState::Playing => intensity(&body, &rules, rendered.iter().next()),
+        _ => 0.0,
+    };
+
+    let layers = director.stems.len().saturating_sub(1) as f32;
+    let fade = STEM_FADE_PER_SECOND * time.delta_seconds();
+
+    let volume = settings.audio.music_volume;
+
+    for (i, stem) in director.stems.iter_mut().enumerate() {
+        let target = match i {
+            0 => volume,
+            _ => (intensity * layers - (i - 1) as f32).clamp(0.0, 1.0) * volume,
+        };
+
+        // Sinks only show up once the stem has loaded and started playing.
+        let sink = match sinks.get(&stem.sink) {
+            Some(sink) if stem.volume != target => sink,
+            _ => continue,
+        };
+
+        stem.volume += (target - stem.volume).clamp(-fade, fade);
+        sink.set_volume(stem.volume);
+    }
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicDirector>()
+            .add_startup_system(start_music)
+            .add_system(music_director);
+    }
+}
@@ -0,0 +1,157 @@
+//! A compact snapshot of the board, taken at the end of every movement tick
+//! and sent out as an [`ObservationEvent`]. It's the observation format for
+//! AI tooling and anything drawing the game outside of the window: one byte
+//! per cell, row by row from the bottom left. Start the game with
+//! `--observations <path>` to have every one appended to a file as well.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::obstacles::Wall;
+use crate::{Food, Position, SnakeHead, SnakePart, GRID_HEIGHT, GRID_WIDTH};
+
+/// What a cell holds, as it's stored in [`Observation::cells`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Cell {
+    Empty = 0,
+    Body = 1,
+    Head = 2,
+    Food = 3,
+    Wall = 4,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Observation {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height` bytes, each one a [`Cell`].
+    pub cells: Vec<u8>,
+}
+
+impl Observation {
+    /// Encodes the board. Where things overlap the head wins, then the body,
+    /// then food, then walls.
+    pub fn encode<'a>(
+        head: Option<Position>,
+        body: impl IntoIterator<Item = &'a Position>,
+        food: impl IntoIterator<Item = &'a Position>,
+        walls: impl IntoIterator<Item = &'a Position>,
+    ) -> Self {
+        let mut observation = Self {
+            width: GRID_WIDTH,
+            height: GRID_HEIGHT,
+            cells: vec![Cell::Empty as u8; (GRID_WIDTH * GRID_HEIGHT) as usize],
+        };
+
+        let layers = [
+            (Cell::Wall, walls.into_iter().collect::<Vec<_>>()),
+            (Cell::Food, food.into_iter().collect()),
+            (Cell::Body, body.into_iter().collect()),
+        ];
+
+        for (cell, positions) in layers {
+            for position in positions {
+                observation.set(*position, cell);
+            }
+        }
+
+        if let Some(head) = head {
+            observation.set(head, Cell::Head);
+        }
+
+        observation
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        let in_bounds = (0..self.width as i32).contains(&position.x)
+            && (0..self.height as i32).contains(&position.y);
+
+        in_bounds.then(|| (position.y as u32 * self.width + position.x as u32) as usize)
+    }
+
+    fn set(&mut self, position: Position, cell: Cell) {
+        if let Some(i) = self.index(position) {
+            self.cells[i] = cell as u8;
+        }
+    }
+}
+
+/// The board as it stood at the end of a movement tick.
+pub struct ObservationEvent(pub Observation);
+
+/// Runs last on each movement tick, once everything has moved.
+pub fn publish_observation(
+    heads: Query<&Position, With<SnakeHead>>,
+    parts: Query<&Position, (With<SnakePart>, Without<SnakeHead>)>,
+    food: Query<&Position, With<Food>>,
+    walls: Query<&Position, With<Wall>>,
+    mut writer: EventWriter<ObservationEvent>,
+) {
+    writer.send(ObservationEvent(Observation::encode(
+        heads.iter().next().copied(),
+        parts.iter(),
+        food.iter(),
+        walls.iter(),
+    )));
+}
+
+/// Where observations are written, if anywhere.
+#[derive(Default)]
+struct ObservationFile(Option<BufWriter<File>>);
+
+impl ObservationFile {
+    fn from_args() -> Self {
+        let path = std::env::args()
+            .skip_while(|arg| arg != "--observations")
+            .nth(1);
+
+        let writer =
+            path.and_then(
+                |path| match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(file) => Some(BufWriter::new(file)),
+                    Err(e) => {
+                        eprintln!("Failed to open observation file '{}': {}", path, e);
+                        None
+                    }
+                },
+            );
+
+        Self(writer)
+    }
+}
+
+/// Appends the raw cells of each observation, so every `width * height`
+/// bytes of the file is one tick.
+fn record_observations(
+    mut file: ResMut<ObservationFile>,
+    mut reader: EventReader<ObservationEvent>,
+) {
+    let writer = match file.0.as_mut() {
+        Some(writer) => writer,
+        None => return,
+    };
+
+    let written = reader
+        .iter()
+        .try_for_each(|ObservationEvent(observation)| writer.write_all(&observation.cells))
+        .and_then(|_| writer.flush());
+
+    if let Err(e) = written {
+        eprintln!("Failed to write observations, disabling them: {}", e);
+        file.0 = None;
+    }
+}
+
+pub struct ObservationPlugin;
+
+impl Plugin for ObservationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ObservationFile::from_args())
+            .add_event::<ObservationEvent>()
+            .add_system(record_observations);
+    }
+}
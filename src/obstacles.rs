@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cheats::Cheats;
+use crate::doors::{spawn_door, spawn_key, KeyColor};
+use crate::gates::spawn_gate;
+use crate::layers::Layer;
+use crate::portals::spawn_portal;
+use crate::terrain::{spawn_terrain, TileEffect};
+use crate::timer::{Clock, TickTimer};
+use crate::toast::{ToastEvent, ToastKind};
+use crate::{
+    playing_timestep, random_free_position, Direction, GameRng, GameStatus, Position, Size,
+    SnakeHead,
+};
+
+pub const WALL_COLOR: Color = Color::rgb(0.3, 0.35, 0.5);
+const BREAKABLE_COLOR: Color = Color::rgb(0.55, 0.4, 0.25);
+const CRACKED_COLOR: Color = Color::rgb(0.4, 0.3, 0.2);
+const HAMMER_COLOR: Color = Color::rgb(0.9, 0.8, 0.2);
+const BREAKABLE_BITES: u8 = 2;
+const HAMMER_SPAWN_INTERVAL: f64 = 12.0;
+const HAMMER_DURATION: f32 = 8.0;
+
+/// A solid grid cell; running the head into one ends the game.
+#[derive(Component)]
+pub struct Wall;
+
+/// A wall that can be chewed through while a hammer is active.
+#[derive(Component)]
+pub struct Breakable {
+    bites_left: u8,
+}
+
+/// A pickup that lets the snake bite through breakable walls for a while.
+#[derive(Component)]
+pub struct Hammer;
+
+/// A hammer that's been picked up, for as long as its timer runs.
+#[derive(Component)]
+pub struct HammerPowerUp;
+
+pub fn spawn_wall(commands: &mut Commands, position: Position, breakable: bool) -> Entity {
+    let mut wall = commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: if breakable {
+                BREAKABLE_COLOR
+            } else {
+                WALL_COLOR
+            },
+            ..default()
+        },
+        ..default()
+    });
+
+    wall.insert(Wall)
+        .insert(position)
+        .insert(Size::square(1.0))
+        .insert(Layer::Board.at(2.0));
+
+    if breakable {
+        wall.insert(Breakable {
+            bites_left: BREAKABLE_BITES,
+        });
+    }
+
+    wall.id()
+}
+
+/// Anything a board layout can place on a grid cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tile {
+    Wall,
+    Breakable,
+    Door(KeyColor),
+    Key(KeyColor),
+    Terrain(TileEffect),
+    Portal(u8),
+    /// Opens and shuts every so many ticks, see [`crate::gates`].
+    Gate(u8),
+}
+
+/// Remembers which layout tile an entity was spawned from, so the board can
+/// be edited and written back out as a level.
+#[derive(Component)]
+pub struct LevelTile(pub Tile);
+
+pub fn spawn_tile(commands: &mut Commands, position: Position, tile: Tile) {
+    let ent = match tile {
+        Tile::Wall => spawn_wall(commands, position, false),
+        Tile::Breakable => spawn_wall(commands, position, true),
+        Tile::Door(color) => spawn_door(commands, position, color),
+        Tile::Key(color) => spawn_key(commands, position, color),
+        Tile::Terrain(effect) => spawn_terrain(commands, position, effect),
+        Tile::Portal(channel) => spawn_portal(commands, position, channel),
+        Tile::Gate(every) => spawn_gate(commands, position, every),
+    };
+
+    commands.entity(ent).insert(LevelTile(tile));
+}
+
+/// Two long barriers with breakable sections to dig shortcuts through, a
+/// couple of locked doors whose keys are tucked away in the corners, and some
+/// patches of ice, mud and conveyor belts in between.
+pub fn walls_layout() -> Vec<(Position, Tile)> {
+    let mut layout = Vec::new();
+
+    for x in 5..30 {
+        let upper = match x {
+            27..=29 => Tile::Door(KeyColor::Gold),
+            16..=18 => Tile::Breakable,
+            _ => Tile::Wall,
+        };
+        let lower = match x {
+            5..=7 => Tile::Door(KeyColor::Blue),
+            8..=10 | 24..=26 => Tile::Breakable,
+            _ => Tile::Wall,
+        };
+
+        layout.push((Position::new(x, 10), upper));
+        layout.push((Position::new(x, 20), lower));
+    }
+
+    layout.push((Position::new(31, 26), Tile::Key(KeyColor::Blue)));
+    layout.push((Position::new(2, 15), Tile::Key(KeyColor::Gold)));
+
+    for x in 12..23 {
+        for y in 13..18 {
+            layout.push((Position::new(x, y), Tile::Terrain(TileEffect::Ice)));
+        }
+    }
+
+    for x in 3..9 {
+        for y in 24..29 {
+            layout.push((Position::new(x, y), Tile::Terrain(TileEffect::Mud)));
+        }
+    }
+
+    for x in 10..26 {
+        let conveyor = TileEffect::Conveyor(Direction::Right);
+        layout.push((Position::new(x, 5), Tile::Terrain(conveyor)));
+    }
+
+    layout
+}
+
+fn hammer_spawner(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    power_up: Query<(), With<HammerPowerUp>>,
+    hammers: Query<Entity, With<Hammer>>,
+    breakables: Query<Entity, With<Breakable>>,
+    walls: Query<&Position, With<Wall>>,
+) {
+    if !power_up.is_empty() || !hammers.is_empty() || breakables.is_empty() {
+        return;
+    }
+
+    let occupied: HashSet<Position> = walls.iter().copied().collect();
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: HAMMER_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Hammer)
+        .insert(random_free_position(&mut rng.0, &occupied))
+        .insert(Size::square(0.6))
+        .insert(Layer::Board.at(1.0));
+}
+
+pub fn hammer_pickup(
+    mut commands: Commands,
+    hammers: Query<(Entity, &Position), With<Hammer>>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for head in heads.iter() {
+        for (ent, position) in hammers.iter() {
+            if position == head {
+                commands.entity(ent).despawn();
+                commands
+                    .spawn()
+                    .insert(HammerPowerUp)
+                    .insert(TickTimer::despawning(HAMMER_DURATION, Clock::Playing));
+                toasts.send(ToastEvent::new(
+                    ToastKind::PowerUp,
+                    "Hammer! Bite through walls",
+                ));
+            }
+        }
+    }
+}
+
+/// Ends the game when the head runs into a wall, unless the wall is breakable
+/// and a hammer is active, in which case the head takes a bite out of it.
+pub fn wall_collision(
+    mut commands: Commands,
+    power_up: Query<(), With<HammerPowerUp>>,
+    cheats: Res<Cheats>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut walls: Query<(Entity, &Position, &mut Sprite, Option<&mut Breakable>), With<Wall>>,
+    mut game_status: Query<&mut GameStatus>,
+) {
+    for head in heads.iter() {
+        for (ent, position, mut sprite, breakable) in walls.iter_mut() {
+            if position != head {
+                continue;
+            }
+
+            match breakable {
+                Some(mut breakable) if !power_up.is_empty() => {
+                    breakable.bites_left -= 1;
+                    sprite.color = CRACKED_COLOR;
+
+                    if breakable.bites_left == 0 {
+                        commands.entity(ent).despawn();
+                    }
+                }
+                _ if cheats.invincible => (),
+                _ => {
+                    if let Some(mut status) = game_status.iter_mut().next() {
+                        status.end(GameStatus::Lost);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct ObstaclesPlugin;
+
+impl Plugin for ObstaclesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::new()
+                .with_run_criteria(playing_timestep(HAMMER_SPAWN_INTERVAL))
+                .with_system(hammer_spawner),
+        );
+    }
+}
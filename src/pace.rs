@@ -0,0 +1,120 @@
+//! Compares the run so far against the personal best, shown as e.g.
+//! `+3 vs PB` under the stamina bar. The score is sampled every few seconds
+//! and the samples from the best run are kept with the stats.
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::scoring::ScoreBreakdown;
+use crate::stats::Stats;
+use crate::{AppState, DevouredFood, NewRunEvent};
+
+const PACE_INTERVAL_SECONDS: f32 = 5.0;
+const PACE_X: f32 = 250.0;
+const PACE_Y: f32 = 252.0;
+const PACE_FONT_SIZE: f32 = 20.0;
+const AHEAD_COLOR: Color = Color::rgb(0.4, 0.9, 0.4);
+const BEHIND_COLOR: Color = Color::rgb(0.9, 0.4, 0.4);
+
+/// The score at every interval of the current run so far.
+pub struct RunPace {
+    timer: Timer,
+    pub samples: Vec<u32>,
+}
+
+impl Default for RunPace {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(PACE_INTERVAL_SECONDS, true),
+            samples: Vec::new(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct PaceText;
+
+fn setup_pace_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: PACE_FONT_SIZE,
+                    color: AHEAD_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(PACE_X, PACE_Y, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(PaceText)
+        .insert(Layer::Hud.at(1.0));
+}
+
+fn reset_pace(mut pace: ResMut<RunPace>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        *pace = RunPace::default();
+    }
+}
+
+fn sample_pace(
+    time: Res<Time>,
+    breakdown: Res<ScoreBreakdown>,
+    mut pace: ResMut<RunPace>,
+    devoured: Query<&DevouredFood>,
+) {
+    if pace.timer.tick(time.delta()).just_finished() {
+        let score = devoured
+            .iter()
+            .next()
+            .map_or(0, |eaten| breakdown.score(eaten));
+        pace.samples.push(score);
+    }
+}
+
+/// Only changes on a new sample, so it reads as a steady split rather than
+/// flickering with every piece of food.
+fn update_pace_text(
+    pace: Res<RunPace>,
+    stats: Res<Stats>,
+    mut query: Query<(&mut Text, &mut Visibility), With<PaceText>>,
+) {
+    if !pace.is_changed() {
+        return;
+    }
+
+    let index = pace.samples.len().checked_sub(1);
+    let difference =
+        index.and_then(|i| Some(pace.samples[i] as i64 - *stats.best_pace.get(i)? as i64));
+
+    for (mut text, mut visibility) in query.iter_mut() {
+        visibility.is_visible = difference.is_some();
+
+        if let Some(difference) = difference {
+            text.sections[0].value = format!("{:+} vs PB", difference);
+            text.sections[0].style.color = if difference < 0 {
+                BEHIND_COLOR
+            } else {
+                AHEAD_COLOR
+            };
+        }
+    }
+}
+
+pub struct PacePlugin;
+
+impl Plugin for PacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunPace>()
+            .add_startup_system(setup_pace_text)
+            .add_system(reset_pace)
+            .add_system(update_pace_text)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(sample_pace));
+    }
+}
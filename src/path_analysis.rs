@@ -0,0 +1,179 @@
+//! The whole route the head took through a finished run, drawn over the board
+//! with Tab on the game over screen. The line runs from blue at the start to
+//! red at the end, with a dot wherever food was eaten and a cross where a
+//! lost run crashed.
+
+use bevy::prelude::*;
+
+use crate::cleanup::DespawnOnStateExit;
+use crate::layers::Layer;
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::viewport::{board_size, cell_size};
+use crate::{
+    calculate_grid_position, AppState, GameStatus, GrowthEvent, NewRunEvent, Position, SnakeHead,
+    GRID_HEIGHT, GRID_WIDTH,
+};
+
+const PATH_THICKNESS: f32 = 3.0;
+const PATH_START_COLOR: Color = Color::rgb(0.2, 0.5, 1.0);
+const PATH_END_COLOR: Color = Color::rgb(1.0, 0.3, 0.2);
+const EATEN_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+const CRASH_COLOR: Color = Color::rgb(1.0, 0.1, 0.1);
+
+/// Every cell the head has been on this run, and the ones it ate on.
+#[derive(Default)]
+pub struct HeadPath {
+    cells: Vec<Position>,
+    eaten: Vec<Position>,
+}
+
+#[derive(Component)]
+struct PathMarker;
+
+fn reset_path(mut path: ResMut<HeadPath>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        *path = HeadPath::default();
+    }
+}
+
+/// Runs at the end of each movement tick, once the head is where it's going.
+pub fn record_path(
+    mut path: ResMut<HeadPath>,
+    mut growth_reader: EventReader<GrowthEvent>,
+    heads: Query<&Position, With<SnakeHead>>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    if path.cells.last() != Some(&head) {
+        path.cells.push(head);
+    }
+
+    for _ in growth_reader.iter() {
+        path.eaten.push(head);
+    }
+}
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let [r1, g1, b1, _] = from.as_rgba_f32();
+    let [r2, g2, b2, _] = to.as_rgba_f32();
+
+    Color::rgb(r1 + (r2 - r1) * t, g1 + (g2 - g1) * t, b1 + (b2 - b1) * t)
+}
+
+fn spawn_marker(commands: &mut Commands, at: Vec2, size: Vec2, color: Color, order: f32) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(at.extend(0.0)),
+            ..default()
+        })
+        .insert(PathMarker)
+        .insert(DespawnOnStateExit(AppState::GameOver))
+        .insert(Layer::Entities.at(order));
+}
+
+fn draw_path(commands: &mut Commands, path: &HeadPath, crashed: bool) {
+    let board = board_size();
+    let cell = Vec2::splat(cell_size());
+    let centre = |position: &Position| {
+        Vec2::new(
+            calculate_grid_position(position.x as f32, board.x, GRID_WIDTH as f32),
+            calculate_grid_position(position.y as f32, board.y, GRID_HEIGHT as f32),
+        )
+    };
+
+    let steps = path.cells.len().saturating_sub(1).max(1) as f32;
+
+    for (i, pair) in path.cells.windows(2).enumerate() {
+        let (from, to) = (pair[0], pair[1]);
+
+        // Wrapping around an edge or through a portal isn't a step across
+        // the board, so there's nothing to join up.
+        if (from.x - to.x).abs() + (from.y - to.y).abs() != 1 {
+            continue;
+        }
+
+        let size = if from.x == to.x {
+            Vec2::new(PATH_THICKNESS, cell.y + PATH_THICKNESS)
+        } else {
+            Vec2::new(cell.x + PATH_THICKNESS, PATH_THICKNESS)
+        };
+        let color = lerp_color(PATH_START_COLOR, PATH_END_COLOR, i as f32 / steps);
+
+        spawn_marker(
+            commands,
+            (centre(&from) + centre(&to)) / 2.0,
+            size,
+            color,
+            2.0,
+        );
+    }
+
+    for position in path.eaten.iter() {
+        spawn_marker(commands, centre(position), cell * 0.4, EATEN_COLOR, 2.5);
+    }
+
+    if let (true, Some(last)) = (crashed, path.cells.last()) {
+        let at = centre(last);
+        let arm = cell.x.min(cell.y);
+
+        spawn_marker(
+            commands,
+            at,
+            Vec2::new(arm, PATH_THICKNESS),
+            CRASH_COLOR,
+            3.0,
+        );
+        spawn_marker(
+            commands,
+            at,
+            Vec2::new(PATH_THICKNESS, arm),
+            CRASH_COLOR,
+            3.0,
+        );
+    }
+}
+
+fn toggle_path(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    path: Res<HeadPath>,
+    statuses: Query<&GameStatus>,
+    markers: Query<Entity, With<PathMarker>>,
+) {
+    if captured.0 || !settings.controls.hotkey(&keyboard_input, KeyCode::Tab) {
+        return;
+    }
+
+    if markers.is_empty() {
+        let crashed = matches!(statuses.iter().next(), Some(GameStatus::Lost));
+        draw_path(&mut commands, &path, crashed);
+    } else {
+        hide_path(commands, markers);
+    }
+}
+
+fn hide_path(mut commands: Commands, markers: Query<Entity, With<PathMarker>>) {
+    for ent in markers.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+pub struct PathAnalysisPlugin;
+
+impl Plugin for PathAnalysisPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeadPath>()
+            .add_system(reset_path)
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(toggle_path));
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::GameplaySettings;
+use crate::wrap::Grace;
+use crate::{Position, Size, SnakeHead};
+
+const PORTAL_COLORS: [Color; 2] = [Color::rgb(0.1, 0.8, 0.9), Color::rgb(0.95, 0.5, 0.1)];
+
+/// Stepping onto a portal moves the head to the other portal on its channel.
+#[derive(Component)]
+pub struct Portal(u8);
+
+pub fn spawn_portal(commands: &mut Commands, position: Position, channel: u8) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: PORTAL_COLORS[channel as usize % PORTAL_COLORS.len()],
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Portal(channel))
+        .insert(position)
+        .insert(Size::square(0.9))
+        .insert(Layer::Board.at(0.7))
+        .id()
+}
+
+pub fn portal_travel(
+    mut commands: Commands,
+    gameplay: Res<GameplaySettings>,
+    mut heads: Query<(Entity, &mut Position), With<SnakeHead>>,
+    portals: Query<(&Position, &Portal), Without<SnakeHead>>,
+) {
+    for (ent, mut head) in heads.iter_mut() {
+        let entered = portals.iter().find(|(position, _)| **position == *head);
+
+        if let Some((entrance, portal)) = entered {
+            let exit = portals
+                .iter()
+                .find(|(position, other)| other.0 == portal.0 && position != &entrance);
+
+            if let Some((exit, _)) = exit {
+                *head = *exit;
+
+                if gameplay.wrap_grace {
+                    commands.entity(ent).insert(Grace);
+                }
+            }
+        }
+    }
+}
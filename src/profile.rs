@@ -0,0 +1,277 @@
+//! Named player profiles, each with its own gameplay options, cosmetics and
+//! stats in a file of its own under `save/profiles`. The active one is picked on the main menu.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::Achievements;
+use crate::autosave::{load_file, save_file, SAVE_DIR};
+use crate::campaign::Campaign;
+use crate::daily::DailyStreak;
+use crate::progression::Progression;
+use crate::settings::{CosmeticSettings, GameplaySettings};
+use crate::stats::Stats;
+use crate::sync::{timestamp, ProfileSync};
+
+const PROFILES_DIR: &str = "profiles";
+const LAST_PROFILE_FILE: &str = "last_profile.txt";
+const DEFAULT_PROFILE: &str = "Player";
+pub const MAX_PROFILE_NAME_LEN: usize = 16;
+
+/// Everything stored for one profile.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileData {
+    /// When this copy was saved, so the newer of a local and a synced copy
+    /// can be told apart.
+    saved_at: u64,
+    gameplay: GameplaySettings,
+    cosmetics: CosmeticSettings,
+    stats: Stats,
+    achievements: Achievements,
+    progression: Progression,
+    daily_streak: DailyStreak,
+    campaign: Campaign,
+}
+
+impl ProfileData {
+    fn apply(self, commands: &mut Commands) {
+        commands.insert_resource(self.gameplay);
+        commands.insert_resource(self.cosmetics);
+        commands.insert_resource(self.stats);
+        commands.insert_resource(self.achievements);
+        commands.insert_resource(self.progression);
+        commands.insert_resource(self.daily_streak);
+        commands.insert_resource(self.campaign);
+    }
+}
+
+/// The live resources that make up a profile.
+#[derive(SystemParam)]
+pub struct ProfileResources<'w, 's> {
+    gameplay: Res<'w, GameplaySettings>,
+    cosmetics: Res<'w, CosmeticSettings>,
+    stats: Res<'w, Stats>,
+    achievements: Res<'w, Achievements>,
+    progression: Res<'w, Progression>,
+    daily_streak: Res<'w, DailyStreak>,
+    campaign: Res<'w, Campaign>,
+    #[system_param(ignore)]
+    _marker: std::marker::PhantomData<&'s ()>,
+}
+
+impl<'w, 's> ProfileResources<'w, 's> {
+    pub fn capture(&self) -> ProfileData {
+        ProfileData {
+            saved_at: timestamp(),
+            gameplay: self.gameplay.clone(),
+            cosmetics: self.cosmetics.clone(),
+            stats: self.stats.clone(),
+            achievements: self.achievements.clone(),
+            progression: self.progression.clone(),
+            daily_streak: self.daily_streak.clone(),
+            campaign: self.campaign.clone(),
+        }
+    }
+}
+
+/// Asks to save the current profile and switch to the named one, creating it
+/// if it doesn't exist yet.
+pub struct SwitchProfileEvent(pub String);
+
+pub struct Profiles {
+    names: Vec<String>,
+    active: usize,
+    sync: ProfileSync,
+    /// When the active profile's local copy was saved, to weigh the synced
+    /// copy against.
+    loaded_at: u64,
+    /// The synced copy of the active profile, while it's downloading.
+    fetching: Option<Task<Option<ProfileData>>>,
+}
+
+impl Profiles {
+    fn path(name: &str) -> PathBuf {
+        Path::new(PROFILES_DIR).join(format!("{}.ron", name))
+    }
+
+    /// Finds every saved profile, starting on whichever was used last.
+    fn scan(sync: ProfileSync) -> Self {
+        let dir = Path::new(SAVE_DIR).join(PROFILES_DIR);
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .map(|dir| dir.flatten().map(|entry| entry.path()).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("ron"))
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .collect();
+
+        names.sort();
+
+        let last = fs::read_to_string(Path::new(SAVE_DIR).join(LAST_PROFILE_FILE))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|_| DEFAULT_PROFILE.to_string());
+
+        let mut profiles = Self {
+            names,
+            active: 0,
+            sync,
+            loaded_at: 0,
+            fetching: None,
+        };
+        profiles.select(&last);
+        profiles
+    }
+
+    fn select(&mut self, name: &str) {
+        self.active = match self.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+
+        let last = Path::new(SAVE_DIR).join(LAST_PROFILE_FILE);
+        if let Err(e) = fs::create_dir_all(SAVE_DIR).and_then(|_| fs::write(last, name)) {
+            eprintln!("Failed to remember the last profile: {}", e);
+        }
+    }
+
+    pub fn active(&self) -> &str {
+        &self.names[self.active]
+    }
+
+    /// The profile `offset` places away from the active one, wrapping around.
+    pub fn neighbour(&self, offset: isize) -> &str {
+        let count = self.names.len() as isize;
+        let index = (self.active as isize + offset).rem_euclid(count);
+        &self.names[index as usize]
+    }
+
+    /// Loads the active profile's local copy. The synced one is fetched
+    /// separately, see [`Profiles::fetch`].
+    fn load(&mut self) -> Option<ProfileData> {
+        let local = load_file::<ProfileData>(&Self::path(self.active()));
+        self.loaded_at = local.as_ref().map_or(0, |local| local.saved_at);
+        local
+    }
+
+    /// Starts downloading the active profile's synced copy, dropping any
+    /// download for the one before.
+    fn fetch(&mut self, pool: &AsyncComputeTaskPool) {
+        self.fetching = self.sync.fetch(pool, self.active());
+    }
+
+    /// The stats in every profile's local save, for comparing them.
+    pub fn saved_stats(&self) -> Vec<(String, Stats)> {
+        self.names
+            .iter()
+            .filter_map(|name| {
+                let data = load_file::<ProfileData>(&Self::path(name))?;
+                Some((name.clone(), data.stats))
+            })
+            .collect()
+    }
+
+    /// Saves the active profile. The upload is held back while the synced
+    /// copy is still on its way, so it isn't replaced before it's compared.
+    pub fn save(&self, data: &ProfileData) {
+        save_file(&Self::path(self.active()), data);
+
+        if self.fetching.is_none() {
+            self.sync.push(self.active(), data);
+        }
+    }
+
+    /// Blocks until any upload still in flight is done.
+    pub fn finish_sync(&self) {
+        self.sync.finish();
+    }
+}
+
+/// Characters allowed in profile names, which double as file names.
+pub fn is_profile_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+fn fetch_profile(pool: Res<AsyncComputeTaskPool>, mut profiles: ResMut<Profiles>) {
+    profiles.fetch(&pool);
+}
+
+fn switch_profile(
+    mut commands: Commands,
+    pool: Res<AsyncComputeTaskPool>,
+    mut reader: EventReader<SwitchProfileEvent>,
+    mut profiles: ResMut<Profiles>,
+    resources: ProfileResources,
+) {
+    let name = match reader.iter().last() {
+        Some(SwitchProfileEvent(name)) if name != profiles.active() => name,
+        _ => return,
+    };
+
+    profiles.save(&resources.capture());
+    profiles.select(name);
+
+    profiles.load().unwrap_or_default().apply(&mut commands);
+    profiles.fetch(&pool);
+}
+
+/// Swaps in the synced copy of the active profile once it's downloaded, when
+/// it was saved more recently than the local one.
+fn apply_synced_profile(mut commands: Commands, mut profiles: ResMut<Profiles>) {
+    // Checked first so waiting on nothing doesn't mark the profiles changed.
+    if profiles.fetching.is_none() {
+        return;
+    }
+
+    let task = match profiles.fetching.as_mut() {
+        Some(task) => task,
+        None => return,
+    };
+    let remote = match future::block_on(future::poll_once(task)) {
+        Some(remote) => remote,
+        None => return,
+    };
+    profiles.fetching = None;
+
+    match remote {
+        Some(remote) if remote.saved_at > profiles.loaded_at => {
+            save_file(&Profiles::path(profiles.active()), &remote);
+            profiles.loaded_at = remote.saved_at;
+            remote.apply(&mut commands);
+        }
+        _ => (),
+    }
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let mut profiles = Profiles::scan(ProfileSync::from_args());
+
+        if let Some(data) = profiles.load() {
+            app.insert_resource(data.gameplay)
+                .insert_resource(data.cosmetics)
+                .insert_resource(data.stats)
+                .insert_resource(data.achievements)
+                .insert_resource(data.progression)
+                .insert_resource(data.daily_streak)
+                .insert_resource(data.campaign);
+        }
+
+        app.insert_resource(profiles)
+            .add_event::<SwitchProfileEvent>()
+            .add_startup_system(fetch_profile)
+            .add_system(switch_profile)
+            .add_system(apply_synced_profile.after(switch_profile));
+    }
+}
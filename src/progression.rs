@@ -0,0 +1,115 @@
+//! Experience earned at the end of every run, adding up to a player level
+//! that's kept in the profile and shown on the main menu.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::kiosk::Kiosk;
+use crate::toast::ToastEvent;
+use crate::{AppState, DevouredFood, GameOverEvent, GameStatus, NewRunEvent};
+
+const XP_PER_FOOD: u32 = 10;
+const XP_PER_SECOND: u32 = 1;
+const XP_WIN_BONUS: u32 = 100;
+/// Going from level `n` to `n + 1` takes `n` times this much.
+const XP_PER_LEVEL: u32 = 100;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progression {
+    pub xp: u32,
+}
+
+impl Progression {
+    /// The player level, starting from 1, along with the XP earned towards
+    /// the next one and how much that level needs in total.
+    pub fn level(&self) -> (u32, u32, u32) {
+        let mut level = 1;
+        let mut remaining = self.xp;
+
+        while remaining >= level * XP_PER_LEVEL {
+            remaining -= level * XP_PER_LEVEL;
+            level += 1;
+        }
+
+        (level, remaining, level * XP_PER_LEVEL)
+    }
+}
+
+/// How long the current run has been played for, not counting pauses.
+#[derive(Default)]
+pub struct RunClock(pub Duration);
+
+fn reset_run_clock(mut clock: ResMut<RunClock>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        clock.0 = Duration::ZERO;
+    }
+}
+
+fn tick_run_clock(time: Res<Time>, mut clock: ResMut<RunClock>) {
+    clock.0 += time.delta();
+}
+
+fn award_xp(
+    clock: Res<RunClock>,
+    devoured: Query<&DevouredFood>,
+    kiosk: Res<Kiosk>,
+    mut progression: ResMut<Progression>,
+    mut reader: EventReader<GameOverEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let event = match reader.iter().next() {
+        Some(event) if !kiosk.attract => event,
+        _ => return,
+    };
+
+    let score = devoured.iter().next().map_or(0, |count| count.0);
+    let mut earned = score * XP_PER_FOOD + clock.0.as_secs() as u32 * XP_PER_SECOND;
+    if let GameStatus::Won = event.0 {
+        earned += XP_WIN_BONUS;
+    }
+
+    let (before, _, _) = progression.level();
+    progression.xp += earned;
+    let (after, _, _) = progression.level();
+
+    toasts.send(ToastEvent::info(format!("+{} XP", earned)));
+    if after > before {
+        toasts.send(ToastEvent::info(format!(
+            "Level up! You're now level {}",
+            after
+        )));
+    }
+}
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Progression>()
+            .init_resource::<RunClock>()
+            .add_system(reset_run_clock)
+            .add_system(award_xp)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(tick_run_clock));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(xp: u32) -> (u32, u32, u32) {
+        Progression { xp }.level()
+    }
+
+    #[test]
+    fn levels_need_more_xp_each_time() {
+        assert_eq!(level(0), (1, 0, 100));
+        assert_eq!(level(99), (1, 99, 100));
+        assert_eq!(level(100), (2, 0, 200));
+        assert_eq!(level(250), (2, 150, 200));
+        assert_eq!(level(300), (3, 0, 300));
+    }
+}
@@ -0,0 +1,478 @@
+//! Puzzle mode: the snake only moves when a direction is pressed, one cell
+//! per press, and each hand made board has to be cleared of food within a set
+//! number of moves. Solving one moves on to the next for the following run.
+//! Each board has a par, the fewest moves it can be solved in, and solving it
+//! earns a medal for how close to par it came, kept with the profile's stats.
+//! Moves can be undone one at a time, all the way back to the start, so a
+//! board is about working out the route rather than steering it.
+
+use std::collections::VecDeque;
+
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ElementState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cheats::Cheats;
+use crate::layers::Layer;
+use crate::level::{ActiveLevel, Level};
+use crate::mode::GameMode;
+use crate::obstacles::Tile;
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::stats::Stats;
+use crate::toast::ToastEvent;
+use crate::{
+    spawn_food, spawn_snake_part, AppState, DevouredFood, Direction, Food, GameStatus, NewRunEvent,
+    Position, RenderedFood, SnakeBody, SnakeHead, SnakePart, TURN_QUEUE_LEN, WIN_WIDTH,
+};
+
+/// Padding from the left edge of the window.
+const MOVES_PADDING: f32 = 15.0;
+const MOVES_Y: f32 = 252.0;
+const MOVES_FONT_SIZE: f32 = 24.0;
+/// A board is failed after this many times its par in moves.
+const MOVE_LIMIT_PARS: u32 = 2;
+
+/// A cell on the board, as `(x, y)`.
+type Cell = (i32, i32);
+
+/// A hand made board. Walls are straight runs, from one cell to another.
+struct Puzzle {
+    name: &'static str,
+    spawn: Cell,
+    walls: &'static [(Cell, Cell)],
+    food: &'static [Cell],
+    /// The fewest moves that clear the board.
+    par: u32,
+}
+
+impl Puzzle {
+    fn level(&self) -> Level {
+        let cell = |(x, y): Cell| Position::new(x, y);
+
+        let tiles = self
+            .walls
+            .iter()
+            .flat_map(|&((x1, y1), (x2, y2))| {
+                (x1.min(x2)..=x1.max(x2))
+                    .flat_map(move |x| (y1.min(y2)..=y1.max(y2)).map(move |y| (x, y)))
+            })
+            .map(|at| (cell(at), Tile::Wall))
+            .collect();
+
+        Level {
+            name: self.name.to_string(),
+            spawn: Some(cell(self.spawn)),
+            tiles,
+            food: self.food.iter().copied().map(cell).collect(),
+            chain: Vec::new(),
+            par: Some(self.par),
+            scene: None,
+            generated: None,
+        }
+    }
+}
+
+/// Each one starts with the snake heading up, so the first move can't be
+/// down.
+const PUZZLES: [Puzzle; 4] = [
+    Puzzle {
+        name: "First steps",
+        spawn: (17, 10),
+        walls: &[],
+        food: &[(17, 14), (21, 14), (21, 18)],
+        par: 12,
+    },
+    Puzzle {
+        name: "Around the wall",
+        spawn: (17, 8),
+        walls: &[((14, 12), (20, 12))],
+        food: &[(17, 16)],
+        par: 16,
+    },
+    Puzzle {
+        name: "Zigzag",
+        spawn: (5, 5),
+        walls: &[((7, 4), (7, 7))],
+        food: &[(5, 9), (9, 9), (9, 5), (13, 5)],
+        par: 16,
+    },
+    Puzzle {
+        name: "The long way round",
+        spawn: (17, 3),
+        walls: &[],
+        food: &[(17, 27)],
+        par: 8,
+    },
+];
+
+/// The first puzzle, for when puzzle mode is picked.
+pub fn first_level() -> Level {
+    PUZZLES[0].level()
+}
+
+/// Earned by solving a board, for how close to par it took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Medal {
+    Bronze,
+    /// Within a quarter over par.
+    Silver,
+    /// On par, or better.
+    Gold,
+}
+
+impl Medal {
+    pub const ALL: [Self; 3] = [Self::Gold, Self::Silver, Self::Bronze];
+
+    fn for_moves(taken: u32, par: u32) -> Self {
+        if taken <= par {
+            Self::Gold
+        } else if taken * 4 <= par * 5 {
+            Self::Silver
+        } else {
+            Self::Bronze
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gold => "Gold",
+            Self::Silver => "Silver",
+            Self::Bronze => "Bronze",
+        }
+    }
+}
+
+/// Where `level` comes in the list of puzzles, if it's one of them.
+fn puzzle_index(level: &Level) -> Option<usize> {
+    PUZZLES.iter().position(|puzzle| puzzle.name == level.name)
+}
+
+/// The board as it was before a move, to go back to on undo. Puzzle boards
+/// only change by the snake moving and eating, so that's all it holds.
+struct Snapshot {
+    /// Every segment, head first.
+    snake: Vec<Position>,
+    moved: Direction,
+    food: Vec<Position>,
+    devoured: u32,
+    rendered: u32,
+}
+
+/// Moves pressed but not yet made, and a snapshot from before each one made
+/// so far this run.
+#[derive(Default)]
+pub struct PuzzleMoves {
+    pub queued: VecDeque<Direction>,
+    history: Vec<Snapshot>,
+}
+
+impl PuzzleMoves {
+    fn taken(&self) -> u32 {
+        self.history.len() as u32
+    }
+}
+
+fn reset_moves(mut moves: ResMut<PuzzleMoves>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        *moves = PuzzleMoves::default();
+    }
+}
+
+/// Every press of a direction is a move, straight on included, as long as it
+/// doesn't double back on the snake.
+#[allow(clippy::too_many_arguments)]
+fn puzzle_input(
+    game_mode: Res<GameMode>,
+    app_state: Res<State<AppState>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    mut key_events: EventReader<KeyboardInput>,
+    heads: Query<&SnakeHead>,
+    mut moves: ResMut<PuzzleMoves>,
+) {
+    let presses: Vec<Direction> = key_events
+        .iter()
+        .filter(|event| event.state == ElementState::Pressed)
+        .filter_map(|event| event.key_code)
+        .filter(|key| keyboard_input.just_pressed(*key))
+        .filter_map(|key| settings.controls.direction(key))
+        .collect();
+
+    if *game_mode != GameMode::Puzzle || *app_state.current() != AppState::Playing || captured.0 {
+        return;
+    }
+
+    let head = match heads.iter().next() {
+        Some(head) => head,
+        None => return,
+    };
+
+    for dir in presses {
+        let last = moves.queued.back().copied().unwrap_or(head.moved);
+
+        if moves.queued.len() < TURN_QUEUE_LEN && dir != -last {
+            moves.queued.push_back(dir);
+        }
+    }
+}
+
+/// Hands the next move to the snake, keeping a snapshot of the board first.
+/// The movement tick only runs in puzzle mode when one is queued, so this
+/// always has one to take.
+pub fn take_move(
+    body: Res<SnakeBody>,
+    mut moves: ResMut<PuzzleMoves>,
+    mut heads: Query<&mut SnakeHead>,
+    parts: Query<&Position, With<SnakePart>>,
+    food: Query<&Position, With<Food>>,
+    counts: Query<(&DevouredFood, &RenderedFood)>,
+) {
+    let (mut head, (devoured, rendered)) = match (heads.iter_mut().next(), counts.iter().next()) {
+        (Some(head), Some(counts)) => (head, counts),
+        _ => return,
+    };
+
+    let dir = match moves.queued.pop_front() {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    moves.history.push(Snapshot {
+        snake: body
+            .0
+            .iter()
+            .filter_map(|e| parts.get(*e).ok())
+            .copied()
+            .collect(),
+        moved: head.moved,
+        food: food.iter().copied().collect(),
+        devoured: devoured.0,
+        rendered: rendered.0,
+    });
+
+    head.turns.clear();
+    head.turns.push_back(dir);
+}
+
+/// `U` puts the board back how it was before the last move, as many times
+/// as there are moves to undo.
+#[allow(clippy::too_many_arguments)]
+fn undo_move(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    game_mode: Res<GameMode>,
+    app_state: Res<State<AppState>>,
+    mut moves: ResMut<PuzzleMoves>,
+    mut body: ResMut<SnakeBody>,
+    mut heads: Query<&mut SnakeHead>,
+    mut positions: Query<&mut Position, With<SnakePart>>,
+    food: Query<Entity, With<Food>>,
+    mut counts: Query<(&GameStatus, &mut DevouredFood, &mut RenderedFood)>,
+) {
+    if *game_mode != GameMode::Puzzle
+        || *app_state.current() != AppState::Playing
+        || captured.0
+        || !settings.controls.hotkey(&keyboard_input, KeyCode::U)
+    {
+        return;
+    }
+
+    let (status, mut devoured, mut rendered) = match counts.iter_mut().next() {
+        Some(counts) => counts,
+        None => return,
+    };
+
+    if !matches!(status, GameStatus::InProgress) {
+        return;
+    }
+
+    let snapshot = match moves.history.pop() {
+        Some(snapshot) => snapshot,
+        None => return,
+    };
+
+    moves.queued.clear();
+    devoured.0 = snapshot.devoured;
+    rendered.0 = snapshot.rendered;
+
+    if let Some(mut head) = heads.iter_mut().next() {
+        head.direction = snapshot.moved;
+        head.moved = snapshot.moved;
+        head.turns.clear();
+    }
+
+    // The snake only grows, but anything grown since gets taken off again.
+    for (i, position) in snapshot.snake.iter().enumerate() {
+        match body.0.get(i).copied() {
+            Some(ent) => {
+                if let Ok(mut current) = positions.get_mut(ent) {
+                    *current = *position;
+                }
+            }
+            None => body.0.push(spawn_snake_part(&mut commands, *position)),
+        }
+    }
+
+    for ent in body.0.drain(snapshot.snake.len()..) {
+        commands.entity(ent).despawn();
+    }
+
+    for ent in food.iter() {
+        commands.entity(ent).despawn();
+    }
+
+    for position in snapshot.food {
+        spawn_food(&mut commands, position);
+    }
+}
+
+/// Solved once the board is clear, failed once the moves run out first. Any
+/// level played in puzzle mode works this way, but only one with a par can
+/// run out of moves or earn a medal.
+#[allow(clippy::too_many_arguments)]
+pub fn puzzle_status(
+    game_mode: Res<GameMode>,
+    active_level: Res<ActiveLevel>,
+    moves: Res<PuzzleMoves>,
+    cheats: Res<Cheats>,
+    mut stats: ResMut<Stats>,
+    rendered: Query<&RenderedFood>,
+    mut status_query: Query<&mut GameStatus>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if *game_mode != GameMode::Puzzle {
+        return;
+    }
+
+    let left = match rendered.iter().next() {
+        Some(rendered) => rendered.0,
+        None => return,
+    };
+
+    let mut status = match status_query.iter_mut().next() {
+        Some(status) if matches!(*status, GameStatus::InProgress) => status,
+        _ => return,
+    };
+
+    let level = &active_level.0;
+
+    if left == 0 {
+        status.end(GameStatus::Won);
+
+        let message = match level.par {
+            Some(par) => {
+                let medal = Medal::for_moves(moves.taken(), par);
+                if !cheats.used {
+                    stats.record_medal(&level.name, medal);
+                }
+
+                format!("Solved in {} moves, {} medal", moves.taken(), medal.name())
+            }
+            None => format!("Solved in {} moves", moves.taken()),
+        };
+        toasts.send(ToastEvent::info(message));
+    } else if matches!(level.par, Some(par) if moves.taken() >= par * MOVE_LIMIT_PARS) {
+        status.end(GameStatus::Lost);
+        toasts.send(ToastEvent::info("Out of moves"));
+    }
+}
+
+/// Lines up the next puzzle once a solved one is left behind, wrapping round
+/// to the first after the last.
+fn next_puzzle(
+    game_mode: Res<GameMode>,
+    status: Query<&GameStatus>,
+    mut active_level: ResMut<ActiveLevel>,
+) {
+    let solved = matches!(status.iter().next(), Some(GameStatus::Won));
+    if *game_mode != GameMode::Puzzle || !solved {
+        return;
+    }
+
+    if let Some(i) = puzzle_index(&active_level.0) {
+        active_level.0 = PUZZLES[(i + 1) % PUZZLES.len()].level();
+    }
+}
+
+#[derive(Component)]
+struct MovesText;
+
+fn setup_moves_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: MOVES_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Left,
+                },
+            ),
+            transform: Transform::from_xyz(-WIN_WIDTH / 2.0 + MOVES_PADDING, MOVES_Y, 0.0),
+            ..default()
+        })
+        .insert(MovesText)
+        .insert(Layer::Hud.at(1.0));
+}
+
+/// Shows the moves taken against the level's par, and the best medal
+/// already earned on it.
+fn update_moves_text(
+    game_mode: Res<GameMode>,
+    active_level: Res<ActiveLevel>,
+    moves: Res<PuzzleMoves>,
+    stats: Res<Stats>,
+    mut query: Query<&mut Text, With<MovesText>>,
+) {
+    let level = &active_level.0;
+
+    let value = match (level.par, stats.puzzle_medals.get(&level.name)) {
+        _ if *game_mode != GameMode::Puzzle => String::new(),
+        (Some(par), Some(medal)) => {
+            format!("Moves: {} / Par: {} ({})", moves.taken(), par, medal.name())
+        }
+        (Some(par), None) => format!("Moves: {} / Par: {}", moves.taken(), par),
+        (None, _) => format!("Moves: {}", moves.taken()),
+    };
+
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+pub struct PuzzlePlugin;
+
+impl Plugin for PuzzlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PuzzleMoves>()
+            .add_startup_system(setup_moves_text)
+            .add_system(reset_moves)
+            .add_system(puzzle_input)
+            .add_system(undo_move)
+            .add_system(update_moves_text)
+            .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(next_puzzle));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn medal_for_moves() {
+        assert_eq!(Medal::for_moves(6, 8), Medal::Gold);
+        assert_eq!(Medal::for_moves(8, 8), Medal::Gold);
+        assert_eq!(Medal::for_moves(10, 8), Medal::Silver);
+        assert_eq!(Medal::for_moves(11, 8), Medal::Bronze);
+    }
+}
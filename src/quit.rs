@@ -0,0 +1,151 @@
+use bevy::app::AppExit;
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+use bevy::window::{exit_on_window_close_system, WindowCloseRequested};
+
+use crate::kiosk::Kiosk;
+use crate::layers::Layer;
+use crate::transition::TransitionEvent;
+use crate::{game_over, toggle_pause, AppState, WIN_HEIGHT, WIN_WIDTH};
+
+const PROMPT_BACKDROP: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+
+/// What confirming the quit prompt does: leave the run for the main menu, or
+/// close the game entirely.
+#[derive(Default)]
+struct QuitPrompt {
+    exit: bool,
+}
+
+#[derive(Component)]
+struct QuitPromptOnly;
+
+/// Takes the close requests before bevy's own handler sees them, so closing
+/// the window mid-run asks first instead of exiting straight away. A kiosk
+/// ignores them altogether.
+fn intercept_close_requests(
+    kiosk: Res<Kiosk>,
+    mut close_requests: ResMut<Events<WindowCloseRequested>>,
+    mut pending: Local<bool>,
+    mut prompt: ResMut<QuitPrompt>,
+    mut app_state: ResMut<State<AppState>>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    let requested = close_requests.drain().count() > 0 || *pending;
+    if !requested || kiosk.enabled {
+        return;
+    }
+
+    *pending = false;
+
+    match app_state.current() {
+        AppState::Playing | AppState::Paused => {
+            prompt.exit = true;
+            // Pausing or the run ending got in first this frame, so the
+            // request waits for the next one, in whatever state that brings.
+            if app_state.set(AppState::ConfirmQuit).is_err() {
+                *pending = true;
+            }
+        }
+        // Nothing to lose outside a run, and closing again while the prompt
+        // is already up means it.
+        _ => app_exit.send(AppExit),
+    }
+}
+
+fn quit_from_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut prompt: ResMut<QuitPrompt>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        prompt.exit = false;
+        // P on the same frame has already queued a return to the run.
+        let _ = app_state.set(AppState::ConfirmQuit);
+    }
+}
+
+fn show_quit_prompt(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: PROMPT_BACKDROP,
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(QuitPromptOnly)
+        .insert(Layer::Overlay.at(1.0));
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Quit? Your run will be lost\n\nEnter quit  Esc cancel",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            ..default()
+        })
+        .insert(QuitPromptOnly)
+        .insert(Layer::Overlay.at(2.0));
+}
+
+fn quit_prompt_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    prompt: Res<QuitPrompt>,
+    mut app_state: ResMut<State<AppState>>,
+    mut transitions: EventWriter<TransitionEvent>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        if prompt.exit {
+            app_exit.send(AppExit);
+        } else {
+            transitions.send(TransitionEvent(AppState::MainMenu));
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        let _ = app_state.set(AppState::Paused);
+    }
+}
+
+fn hide_quit_prompt(mut commands: Commands, query: Query<Entity, With<QuitPromptOnly>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+pub struct QuitPlugin;
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QuitPrompt>()
+            .add_system(
+                intercept_close_requests
+                    .after(game_over)
+                    .after(toggle_pause)
+                    .before(exit_on_window_close_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Paused)
+                    .with_system(quit_from_pause.after(toggle_pause)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::ConfirmQuit).with_system(show_quit_prompt),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::ConfirmQuit)
+                    .with_system(quit_prompt_input.after(toggle_pause)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::ConfirmQuit).with_system(hide_quit_prompt),
+            );
+    }
+}
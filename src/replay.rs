@@ -0,0 +1,355 @@
+//! A replay viewer for the run just played, opened with R on the game over
+//! screen. The board is rebuilt for any tick from the [`Observation`] taken
+//! on it, so the run can be played back slower or faster than it went, held
+//! on a tick and stepped through, or jumped about in with the bar along the
+//! bottom.
+
+use bevy::prelude::*;
+
+use crate::boards::BoardLayout;
+use crate::cleanup::DespawnOnStateExit;
+use crate::layers::Layer;
+use crate::mini_replay::cell_color;
+use crate::observation::{Observation, ObservationEvent};
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::transition::ScreenFade;
+use crate::viewport::{board_size, cursor_to_world};
+use crate::{
+    AppState, NewRunEvent, Position, Size, BG_COLOR, MOVEMENT_STEP, WIN_HEIGHT, WIN_WIDTH,
+};
+
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+const NORMAL_SPEED: usize = 2;
+const BAR_Y: f32 = -WIN_HEIGHT / 2.0 + 20.0;
+const BAR_WIDTH: f32 = WIN_WIDTH - 80.0;
+const BAR_HEIGHT: f32 = 8.0;
+/// How far above or below the bar a click still lands on it.
+const BAR_REACH: f32 = 12.0;
+const BAR_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+const BAR_FILL_COLOR: Color = Color::rgb(0.2, 0.6, 0.9);
+const HANDLE_WIDTH: f32 = 6.0;
+const HANDLE_HEIGHT: f32 = 20.0;
+const STATUS_Y: f32 = WIN_HEIGHT / 2.0 - 20.0;
+const HELP_Y: f32 = BAR_Y + 24.0;
+const FONT_SIZE: f32 = 18.0;
+
+/// The board on every tick of the latest run, oldest first.
+#[derive(Default)]
+struct RunRecording(Vec<Observation>);
+
+struct ReplayViewer {
+    frame: usize,
+    playing: bool,
+    speed: usize,
+    timer: Timer,
+    /// The frame the board is showing, to redraw only when it changes.
+    shown: Option<usize>,
+}
+
+impl ReplayViewer {
+    fn new() -> Self {
+        Self {
+            frame: 0,
+            playing: true,
+            speed: NORMAL_SPEED,
+            timer: Self::tick_timer(NORMAL_SPEED),
+            shown: None,
+        }
+    }
+
+    fn tick_timer(speed: usize) -> Timer {
+        Timer::from_seconds(MOVEMENT_STEP as f32 / SPEEDS[speed], true)
+    }
+
+    fn set_speed(&mut self, speed: usize) {
+        self.speed = speed.min(SPEEDS.len() - 1);
+        self.timer = Self::tick_timer(self.speed);
+    }
+}
+
+#[derive(Component)]
+struct ReplayCell;
+
+#[derive(Component)]
+struct ReplayStatus;
+
+#[derive(Component)]
+enum ScrubberPart {
+    Fill,
+    Handle,
+}
+
+fn record_run(
+    mut recording: ResMut<RunRecording>,
+    mut new_runs: EventReader<NewRunEvent>,
+    mut observations: EventReader<ObservationEvent>,
+) {
+    if new_runs.iter().next().is_some() {
+        recording.0.clear();
+    }
+
+    for ObservationEvent(board) in observations.iter() {
+        recording.0.push(board.clone());
+    }
+}
+
+/// Sits over the game over screen rather than replacing it, so it's all
+/// still there once the viewer is closed.
+fn open_replay(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    fade: Res<ScreenFade>,
+    recording: Res<RunRecording>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if captured.0 || fade.is_active() || recording.0.is_empty() {
+        return;
+    }
+
+    // Leaving the game over screen this frame takes precedence.
+    if settings.controls.hotkey(&keyboard_input, KeyCode::R) {
+        let _ = app_state.push(AppState::Replay);
+    }
+}
+
+fn spawn_sprite(commands: &mut Commands, color: Color, size: Vec2, at: Vec2, order: f32) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(at.extend(0.0)),
+            ..default()
+        })
+        .insert(DespawnOnStateExit(AppState::Replay))
+        .insert(Layer::Overlay.at(order))
+        .id()
+}
+
+fn spawn_text(commands: &mut Commands, asset_server: &AssetServer, text: &str, y: f32) -> Entity {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                text,
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, y, 0.0),
+            ..default()
+        })
+        .insert(DespawnOnStateExit(AppState::Replay))
+        .insert(Layer::Overlay.at(2.0))
+        .id()
+}
+
+fn enter_replay(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ReplayViewer::new());
+
+    // Hides the board the run ended on, under the one being replayed.
+    spawn_sprite(&mut commands, BG_COLOR, board_size(), Vec2::ZERO, 1.0);
+
+    let (bar, at) = (Vec2::new(BAR_WIDTH, BAR_HEIGHT), Vec2::new(0.0, BAR_Y));
+    let handle = Vec2::new(HANDLE_WIDTH, HANDLE_HEIGHT);
+    spawn_sprite(&mut commands, BAR_COLOR, bar, at, 2.0);
+    let fill = spawn_sprite(&mut commands, BAR_FILL_COLOR, bar, at, 2.1);
+    let handle = spawn_sprite(&mut commands, Color::WHITE, handle, at, 2.2);
+    commands.entity(fill).insert(ScrubberPart::Fill);
+    commands.entity(handle).insert(ScrubberPart::Handle);
+
+    let status = spawn_text(&mut commands, &asset_server, "", STATUS_Y);
+    commands.entity(status).insert(ReplayStatus);
+    spawn_text(
+        &mut commands,
+        &asset_server,
+        "Space play/pause  Left/Right step  Up/Down speed  Click the bar to jump  Esc back",
+        HELP_Y,
+    );
+}
+
+fn replay_input(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    layout: Res<BoardLayout>,
+    recording: Res<RunRecording>,
+    mut viewer: ResMut<ReplayViewer>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    // Cleared so the game over screen underneath doesn't take it as well.
+    if keyboard_input.just_pressed(KeyCode::Escape) && app_state.pop().is_ok() {
+        keyboard_input.clear_just_pressed(KeyCode::Escape);
+        return;
+    }
+
+    let last = recording.0.len().saturating_sub(1);
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        // Playing from the end starts over.
+        if !viewer.playing && viewer.frame == last {
+            viewer.frame = 0;
+        }
+        viewer.playing = !viewer.playing;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Left) {
+        viewer.playing = false;
+        viewer.frame = viewer.frame.saturating_sub(1);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        viewer.playing = false;
+        viewer.frame = (viewer.frame + 1).min(last);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        let speed = viewer.speed + 1;
+        viewer.set_speed(speed);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        let speed = viewer.speed.saturating_sub(1);
+        viewer.set_speed(speed);
+    }
+
+    if !mouse_input.pressed(MouseButton::Left) {
+        return;
+    }
+
+    let window = windows.get_primary().unwrap();
+    let at = match window.cursor_position() {
+        Some(cursor) => cursor_to_world(cursor, window, &layout),
+        None => return,
+    };
+
+    if (at.y - BAR_Y).abs() <= BAR_REACH && at.x.abs() <= BAR_WIDTH / 2.0 {
+        let along = at.x / BAR_WIDTH + 0.5;
+        viewer.frame = (along * last as f32).round() as usize;
+    }
+}
+
+fn advance_replay(time: Res<Time>, recording: Res<RunRecording>, mut viewer: ResMut<ReplayViewer>) {
+    if !viewer.playing {
+        return;
+    }
+
+    let last = recording.0.len().saturating_sub(1);
+    let steps = viewer.timer.tick(time.delta()).times_finished() as usize;
+    viewer.frame = (viewer.frame + steps).min(last);
+
+    if viewer.frame == last {
+        viewer.playing = false;
+    }
+}
+
+type ScrubberParts<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ScrubberPart,
+        &'static mut Transform,
+        &'static mut Sprite,
+    ),
+>;
+
+/// Rebuilds the board whenever the frame changes, and keeps the bar and the
+/// status line up to date.
+fn draw_replay(
+    mut commands: Commands,
+    recording: Res<RunRecording>,
+    mut viewer: ResMut<ReplayViewer>,
+    cells: Query<Entity, With<ReplayCell>>,
+    mut statuses: Query<&mut Text, With<ReplayStatus>>,
+    mut scrubber: ScrubberParts,
+) {
+    let last = recording.0.len().saturating_sub(1);
+    let frame = viewer.frame.min(last);
+
+    for mut text in statuses.iter_mut() {
+        text.sections[0].value = format!(
+            "Replay  tick {} / {}  {}x{}",
+            frame + 1,
+            last + 1,
+            SPEEDS[viewer.speed],
+            if viewer.playing { "" } else { "  paused" },
+        );
+    }
+
+    let progress = if last == 0 {
+        1.0
+    } else {
+        frame as f32 / last as f32
+    };
+    let left = -BAR_WIDTH / 2.0;
+
+    for (part, mut transform, mut sprite) in scrubber.iter_mut() {
+        match part {
+            ScrubberPart::Fill => {
+                let width = BAR_WIDTH * progress;
+                sprite.custom_size = Some(Vec2::new(width, BAR_HEIGHT));
+                transform.translation.x = left + width / 2.0;
+            }
+            ScrubberPart::Handle => transform.translation.x = left + BAR_WIDTH * progress,
+        }
+    }
+
+    if viewer.shown == Some(frame) {
+        return;
+    }
+    viewer.shown = Some(frame);
+
+    for cell in cells.iter() {
+        commands.entity(cell).despawn();
+    }
+
+    let board = match recording.0.get(frame) {
+        Some(board) => board,
+        None => return,
+    };
+
+    for (i, cell) in board.cells.iter().enumerate() {
+        let color = match cell_color(*cell) {
+            Some(color) => color,
+            None => continue,
+        };
+
+        let (x, y) = (i as u32 % board.width, i as u32 / board.width);
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite { color, ..default() },
+                ..default()
+            })
+            .insert(ReplayCell)
+            .insert(Position::new(x as i32, y as i32))
+            .insert(Size::square(0.8))
+            .insert(DespawnOnStateExit(AppState::Replay))
+            .insert(Layer::Overlay.at(1.5));
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunRecording>()
+            .add_system(record_run)
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(open_replay))
+            .add_system_set(SystemSet::on_enter(AppState::Replay).with_system(enter_replay))
+            .add_system_set(
+                SystemSet::on_update(AppState::Replay)
+                    .with_system(replay_input)
+                    .with_system(advance_replay.after(replay_input))
+                    .with_system(draw_replay.after(advance_replay)),
+            );
+    }
+}
@@ -0,0 +1,139 @@
+//! Short random events that shake up a run now and then, each announced with
+//! a banner as it starts.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::boards::BoardId;
+use crate::mode::GameMode;
+use crate::timer::{Clock, TickTimer};
+use crate::toast::{ToastEvent, ToastKind};
+use crate::{
+    random_free_position, spawn_food, AppState, GameRng, NewRunEvent, Position, RenderFoodEvent,
+    RenderedFood, Rules, TickRate,
+};
+
+/// Seconds between events, picked at random from this range each time.
+const EVENT_INTERVAL: (f32, f32) = (20.0, 40.0);
+const FRENZY_FOOD: u32 = 5;
+const SURGE_SECONDS: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy)]
+enum RunEvent {
+    /// A handful of food drops in at once.
+    FoodFrenzy,
+    /// The snake moves at double speed for a few seconds.
+    SpeedSurge,
+}
+
+impl RunEvent {
+    fn banner(&self) -> &'static str {
+        match self {
+            Self::FoodFrenzy => "Food Frenzy!",
+            Self::SpeedSurge => "Speed Surge!",
+        }
+    }
+}
+
+/// Counts down to the next event.
+struct EventDirector {
+    next: Timer,
+}
+
+impl Default for EventDirector {
+    fn default() -> Self {
+        Self {
+            next: Timer::from_seconds(EVENT_INTERVAL.0, false),
+        }
+    }
+}
+
+/// A speed surge that's running, for as long as its timer runs.
+#[derive(Component)]
+pub struct SpeedSurge;
+
+fn reset_director(mut director: ResMut<EventDirector>, mut reader: EventReader<NewRunEvent>) {
+    if reader.iter().next().is_some() {
+        *director = EventDirector::default();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn event_director(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    mut rng: ResMut<GameRng>,
+    mut director: ResMut<EventDirector>,
+    rules: Res<Rules>,
+    rendered: Query<&RenderedFood>,
+    occupied: Query<&Position, Without<BoardId>>,
+    mut render_event: EventWriter<RenderFoodEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    // Puzzles are laid out by hand, and don't move on a timer anyway.
+    if *game_mode == GameMode::Puzzle {
+        return;
+    }
+
+    if !director.next.tick(time.delta()).finished() {
+        return;
+    }
+
+    let seconds = rng.0.gen_range(EVENT_INTERVAL.0..EVENT_INTERVAL.1);
+    director.next = Timer::from_seconds(seconds, false);
+
+    // Too much food on the board loses the run, so a frenzy only ever fills
+    // up to one short of that.
+    let on_board = rendered.iter().next().map_or(0, |count| count.0);
+    let room = rules.food_to_lose.saturating_sub(on_board + 1);
+
+    let event = if room > 0 && rng.0.gen_bool(0.5) {
+        RunEvent::FoodFrenzy
+    } else {
+        RunEvent::SpeedSurge
+    };
+
+    match event {
+        RunEvent::FoodFrenzy => {
+            let mut occupied: HashSet<Position> = occupied.iter().copied().collect();
+
+            for _ in 0..FRENZY_FOOD.min(room) {
+                let position = random_free_position(&mut rng.0, &occupied);
+                occupied.insert(position);
+                spawn_food(&mut commands, position);
+                render_event.send(RenderFoodEvent);
+            }
+        }
+        RunEvent::SpeedSurge => {
+            commands
+                .spawn()
+                .insert(SpeedSurge)
+                .insert(TickTimer::despawning(SURGE_SECONDS, Clock::Playing));
+        }
+    }
+
+    toasts.send(ToastEvent::new(ToastKind::Event, event.banner()));
+}
+
+/// Speeds the snake up for as long as a surge is running.
+fn apply_surge(mut tick_rate: ResMut<TickRate>, surges: Query<(), With<SpeedSurge>>) {
+    let surging = !surges.is_empty();
+
+    if tick_rate.surging != surging {
+        tick_rate.surging = surging;
+    }
+}
+
+pub struct RunEventsPlugin;
+
+impl Plugin for RunEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventDirector>()
+            .add_system(reset_director)
+            .add_system(apply_surge)
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(event_director));
+    }
+}
@@ -0,0 +1,174 @@
+//! Opt in JSON summary of every run, written when it ends, enabled with
+//! `--export-runs <dir>`. It holds enough to replay or analyse the run: the
+//! seed, every turn taken, how the score moved and the board it ended on.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::level::ActiveLevel;
+use crate::mode::GameMode;
+use crate::observation::{Observation, ObservationEvent};
+use crate::scoring::ScoreBreakdown;
+use crate::toast::ToastEvent;
+use crate::{DevouredFood, Direction, GameOverEvent, GameStatus, NewRunEvent, RunSeed, SnakeHead};
+
+#[derive(Serialize)]
+struct Turn {
+    tick: u64,
+    direction: Direction,
+}
+
+#[derive(Serialize)]
+struct ScorePoint {
+    tick: u64,
+    score: u32,
+}
+
+#[derive(Serialize)]
+struct RunExport<'a> {
+    seed: u64,
+    mode: &'static str,
+    level: &'a str,
+    status: &'a GameStatus,
+    ticks: u64,
+    inputs: &'a [Turn],
+    score_timeline: &'a [ScorePoint],
+    final_board: Option<&'a Observation>,
+}
+
+#[derive(Default)]
+pub struct RunRecorder {
+    dir: Option<PathBuf>,
+    tick: u64,
+    direction: Option<Direction>,
+    score: u32,
+    inputs: Vec<Turn>,
+    score_timeline: Vec<ScorePoint>,
+    board: Option<Observation>,
+}
+
+impl RunRecorder {
+    fn from_args() -> Self {
+        let dir = std::env::args()
+            .skip_while(|arg| arg != "--export-runs")
+            .nth(1);
+
+        Self {
+            dir: dir.map(PathBuf::from),
+            ..default()
+        }
+    }
+
+    fn export(&self, export: &RunExport) -> Result<(), String> {
+        let dir = self.dir.as_ref().ok_or("Run export is off")?;
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        let path = dir.join(format!("run-{}.json", time));
+
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let file = File::create(&path).map_err(|e| e.to_string())?;
+        serde_json::to_writer_pretty(BufWriter::new(file), export).map_err(|e| e.to_string())
+    }
+}
+
+fn start_recording(mut recorder: ResMut<RunRecorder>, mut reader: EventReader<NewRunEvent>) {
+    if recorder.dir.is_none() {
+        return;
+    }
+
+    if reader.iter().next().is_some() {
+        *recorder = RunRecorder {
+            dir: recorder.dir.take(),
+            ..default()
+        };
+    }
+}
+
+/// Runs last on each movement tick, after the board has been observed.
+pub fn record_tick(
+    mut recorder: ResMut<RunRecorder>,
+    mut observations: EventReader<ObservationEvent>,
+    breakdown: Res<ScoreBreakdown>,
+    heads: Query<&SnakeHead>,
+    devoured: Query<&DevouredFood>,
+) {
+    if recorder.dir.is_none() {
+        return;
+    }
+
+    recorder.tick += 1;
+    let tick = recorder.tick;
+
+    if let Some(head) = heads.iter().next() {
+        if recorder.direction != Some(head.moved) {
+            recorder.direction = Some(head.moved);
+            recorder.inputs.push(Turn {
+                tick,
+                direction: head.moved,
+            });
+        }
+    }
+
+    let score = devoured
+        .iter()
+        .next()
+        .map_or(0, |eaten| breakdown.score(eaten));
+    if score != recorder.score {
+        recorder.score = score;
+        recorder.score_timeline.push(ScorePoint { tick, score });
+    }
+
+    if let Some(ObservationEvent(board)) = observations.iter().last() {
+        recorder.board = Some(board.clone());
+    }
+}
+
+fn export_run(
+    recorder: Res<RunRecorder>,
+    seed: Res<RunSeed>,
+    game_mode: Res<GameMode>,
+    active_level: Res<ActiveLevel>,
+    mut reader: EventReader<GameOverEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if recorder.dir.is_none() {
+        return;
+    }
+
+    let event = match reader.iter().next() {
+        Some(event) => event,
+        None => return,
+    };
+
+    let export = RunExport {
+        seed: seed.0,
+        mode: game_mode.name(),
+        level: &active_level.0.name,
+        status: &event.0,
+        ticks: recorder.tick,
+        inputs: &recorder.inputs,
+        score_timeline: &recorder.score_timeline,
+        final_board: recorder.board.as_ref(),
+    };
+
+    if let Err(e) = recorder.export(&export) {
+        eprintln!("Failed to export the run: {}", e);
+        toasts.send(ToastEvent::error("Failed to export the run"));
+    }
+}
+
+pub struct RunExportPlugin;
+
+impl Plugin for RunExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunRecorder::from_args())
+            .add_system(start_recording)
+            .add_system(export_run);
+    }
+}
@@ -0,0 +1,353 @@
+//! Editing the board mid-run in sandbox mode: left clicking a cell toggles a
+//! wall there and right clicking drops a piece of food. Works while paused
+//! too, for laying things out before carrying on. Tab opens a panel of
+//! sliders for the run's [`Rules`], which take effect as soon as they move.
+
+use bevy::prelude::*;
+
+use crate::boards::BoardLayout;
+use crate::cheats::Cheats;
+use crate::layers::Layer;
+use crate::mode::GameMode;
+use crate::obstacles::{spawn_tile, Tile, Wall};
+use crate::settings::KeyboardCaptured;
+use crate::{
+    cursor_to_grid, spawn_food, AppState, Food, Position, RenderFoodEvent, Rules, SnakePart,
+    TickRate, MOVEMENT_STEP, WIN_WIDTH,
+};
+
+const PANEL_PADDING: f32 = 10.0;
+const PANEL_FONT_SIZE: f32 = 18.0;
+const PANEL_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+const PANEL_SELECTED_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+/// Characters in each slider's track.
+const SLIDER_WIDTH: usize = 10;
+
+/// The rules sandbox runs play by, kept separately so the other modes always
+/// get the defaults.
+struct SandboxRules {
+    rules: Rules,
+    /// Seconds between the snake's moves.
+    tick_step: f64,
+}
+
+impl Default for SandboxRules {
+    fn default() -> Self {
+        Self {
+            rules: Rules::default(),
+            tick_step: MOVEMENT_STEP,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RulesPanel {
+    open: bool,
+    selected: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Slider {
+    TickRate,
+    FoodRate,
+    Growth,
+    WinAt,
+    LoseAt,
+}
+
+impl Slider {
+    const ALL: [Self; 5] = [
+        Self::TickRate,
+        Self::FoodRate,
+        Self::Growth,
+        Self::WinAt,
+        Self::LoseAt,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::TickRate => "Tick rate",
+            Self::FoodRate => "Food every",
+            Self::Growth => "Growth",
+            Self::WinAt => "Win at",
+            Self::LoseAt => "Lose at",
+        }
+    }
+
+    /// The lowest and highest values, and how far one press moves it.
+    fn range(&self) -> (f64, f64, f64) {
+        match self {
+            Self::TickRate => (0.02, 0.5, 0.02),
+            Self::FoodRate => (0.5, 10.0, 0.5),
+            Self::Growth => (0.0, 5.0, 1.0),
+            Self::WinAt => (0.0, 200.0, 10.0),
+            Self::LoseAt => (1.0, 50.0, 1.0),
+        }
+    }
+
+    fn get(&self, sandbox: &SandboxRules) -> f64 {
+        match self {
+            Self::TickRate => sandbox.tick_step,
+            Self::FoodRate => sandbox.rules.food_interval,
+            Self::Growth => sandbox.rules.growth as f64,
+            Self::WinAt => sandbox.rules.food_to_win.unwrap_or(0) as f64,
+            Self::LoseAt => sandbox.rules.food_to_lose as f64,
+        }
+    }
+
+    fn set(&self, sandbox: &mut SandboxRules, value: f64) {
+        match self {
+            Self::TickRate => sandbox.tick_step = value,
+            Self::FoodRate => sandbox.rules.food_interval = value,
+            Self::Growth => sandbox.rules.growth = value.round() as u32,
+            // Zero turns winning off altogether.
+            Self::WinAt => {
+                sandbox.rules.food_to_win = Some(value.round() as u32).filter(|&food| food > 0)
+            }
+            Self::LoseAt => sandbox.rules.food_to_lose = value.round() as u32,
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self {
+            Self::TickRate | Self::FoodRate => format!("{:.2}s", value),
+            Self::WinAt if value == 0.0 => "off".to_string(),
+            Self::Growth | Self::WinAt | Self::LoseAt => format!("{}", value),
+        }
+    }
+
+    /// The slider's label, track and value, e.g. `Growth [##--------] 1`.
+    fn line(&self, sandbox: &SandboxRules) -> String {
+        let (min, max, _) = self.range();
+        let value = self.get(sandbox);
+        let filled = ((value - min) / (max - min) * SLIDER_WIDTH as f64).round() as usize;
+
+        format!(
+            "{} [{}{}] {}",
+            self.label(),
+            "#".repeat(filled),
+            "-".repeat(SLIDER_WIDTH - filled),
+            self.format(value)
+        )
+    }
+}
+
+#[derive(Component)]
+struct RulesPanelText;
+
+#[allow(clippy::too_many_arguments)]
+fn sandbox_editing(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    app_state: Res<State<AppState>>,
+    windows: Res<Windows>,
+    layout: Res<BoardLayout>,
+    mouse_input: Res<Input<MouseButton>>,
+    mut cheats: ResMut<Cheats>,
+    parts: Query<&Position, With<SnakePart>>,
+    food: Query<&Position, With<Food>>,
+    walls: Query<(Entity, &Position), With<Wall>>,
+    mut render_event: EventWriter<RenderFoodEvent>,
+) {
+    if *game_mode != GameMode::Sandbox
+        || !matches!(app_state.current(), AppState::Playing | AppState::Paused)
+    {
+        return;
+    }
+
+    let left = mouse_input.just_pressed(MouseButton::Left);
+    let right = mouse_input.just_pressed(MouseButton::Right);
+    if !left && !right {
+        return;
+    }
+
+    let window = windows.get_primary().unwrap();
+    let cell = match window
+        .cursor_position()
+        .and_then(|c| cursor_to_grid(c, window, &layout))
+    {
+        Some(cell) => cell,
+        None => return,
+    };
+
+    // Don't build on top of the snake or the food.
+    if parts
+        .iter()
+        .chain(food.iter())
+        .any(|position| *position == cell)
+    {
+        return;
+    }
+
+    let wall = walls.iter().find(|(_, position)| **position == cell);
+
+    if left {
+        match wall {
+            Some((ent, _)) => commands.entity(ent).despawn(),
+            None => spawn_tile(&mut commands, cell, Tile::Wall),
+        }
+    } else if wall.is_none() {
+        spawn_food(&mut commands, cell);
+        render_event.send(RenderFoodEvent);
+        // Free food would make short work of the best score.
+        cheats.used = true;
+    }
+}
+
+/// Keeps the live rules in step with the sandbox's whenever either they or
+/// the mode change, and puts the defaults back for every other mode. A new
+/// run resets the tick rate, so that's watched too.
+fn apply_sandbox_rules(
+    game_mode: Res<GameMode>,
+    sandbox: Res<SandboxRules>,
+    mut rules: ResMut<Rules>,
+    mut tick_rate: ResMut<TickRate>,
+) {
+    if !game_mode.is_changed() && !sandbox.is_changed() && !tick_rate.is_changed() {
+        return;
+    }
+
+    let target = match *game_mode {
+        GameMode::Sandbox => sandbox.rules.clone(),
+        _ => Rules::default(),
+    };
+
+    if *rules != target {
+        *rules = target;
+    }
+
+    // Outside the sandbox the tick rate is only put back on leaving it, so
+    // the console can still change it.
+    let step = match *game_mode {
+        GameMode::Sandbox => sandbox.tick_step,
+        _ if game_mode.is_changed() => MOVEMENT_STEP,
+        _ => return,
+    };
+
+    if tick_rate.base != step {
+        tick_rate.base = step;
+    }
+}
+
+fn setup_rules_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+        font_size: PANEL_FONT_SIZE,
+        color: PANEL_COLOR,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text {
+                sections: Slider::ALL
+                    .iter()
+                    .map(|_| TextSection {
+                        value: String::new(),
+                        style: style.clone(),
+                    })
+                    .collect(),
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Left,
+                },
+            },
+            transform: Transform::from_xyz(-WIN_WIDTH / 2.0 + PANEL_PADDING, 0.0, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(RulesPanelText)
+        .insert(Layer::Overlay.at(6.0));
+}
+
+/// Tab opens and closes the panel. While it's open the arrow keys pick a
+/// slider and move it, instead of steering the snake.
+fn rules_panel_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_mode: Res<GameMode>,
+    app_state: Res<State<AppState>>,
+    mut panel: ResMut<RulesPanel>,
+    mut sandbox: ResMut<SandboxRules>,
+    mut captured: ResMut<KeyboardCaptured>,
+) {
+    let available = *game_mode == GameMode::Sandbox
+        && matches!(app_state.current(), AppState::Playing | AppState::Paused);
+
+    if !available {
+        if panel.open {
+            panel.open = false;
+            captured.0 = false;
+        }
+
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        panel.open = !panel.open;
+        captured.0 = panel.open;
+        return;
+    }
+
+    if !panel.open {
+        return;
+    }
+
+    let count = Slider::ALL.len();
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        panel.selected = (panel.selected + count - 1) % count;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        panel.selected = (panel.selected + 1) % count;
+    }
+
+    let nudge = if keyboard_input.just_pressed(KeyCode::Left) {
+        -1.0
+    } else if keyboard_input.just_pressed(KeyCode::Right) {
+        1.0
+    } else {
+        return;
+    };
+
+    let slider = Slider::ALL[panel.selected];
+    let (min, max, step) = slider.range();
+    let value = (slider.get(&sandbox) + step * nudge).clamp(min, max);
+    slider.set(&mut sandbox, value);
+}
+
+fn draw_rules_panel(
+    panel: Res<RulesPanel>,
+    sandbox: Res<SandboxRules>,
+    mut query: Query<(&mut Text, &mut Visibility), With<RulesPanelText>>,
+) {
+    if !panel.is_changed() && !sandbox.is_changed() {
+        return;
+    }
+
+    for (mut text, mut visibility) in query.iter_mut() {
+        visibility.is_visible = panel.open;
+
+        for (i, (section, slider)) in text.sections.iter_mut().zip(Slider::ALL).enumerate() {
+            section.value = format!("{}\n", slider.line(&sandbox));
+            section.style.color = if i == panel.selected {
+                PANEL_SELECTED_COLOR
+            } else {
+                PANEL_COLOR
+            };
+        }
+    }
+}
+
+pub struct SandboxPlugin;
+
+impl Plugin for SandboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SandboxRules>()
+            .init_resource::<RulesPanel>()
+            .add_startup_system(setup_rules_panel)
+            .add_system(sandbox_editing)
+            .add_system(apply_sandbox_rules)
+            .add_system(rules_panel_input)
+            .add_system(draw_rules_panel);
+    }
+}
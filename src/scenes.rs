@@ -0,0 +1,163 @@
+//! Levels can pull in a Bevy scene, named by their `scene` field and kept in
+//! `assets/scenes`, for anything the level format itself doesn't cover. The
+//! scene's entities are spawned on top of the board, and the ones carrying a
+//! component from here are then fleshed out into walls, decorations or the
+//! snake's starting point. Any other registered component can go in a scene
+//! too, and is spawned as it is.
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::level::ActiveLevel;
+use crate::obstacles::{spawn_tile, Tile};
+use crate::{Direction, NewRunEvent, Position, Size, SnakeBody, SnakePart};
+
+const DECORATION_ORDER: f32 = 0.5;
+
+/// Becomes a wall, as if it had been in the level's tiles.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SceneWall {
+    pub breakable: bool,
+}
+
+/// A plain square drawn under everything else, that the snake passes over.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Decoration {
+    pub color: Color,
+    /// As a fraction of a cell.
+    pub size: f32,
+}
+
+/// Where the snake starts, overriding the level's own spawn.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SpawnMarker;
+
+/// The scene spawned for the current board, if its level has one.
+#[derive(Default)]
+struct LevelScene(Option<Handle<DynamicScene>>);
+
+fn spawn_scene(
+    asset_server: &AssetServer,
+    scene_spawner: &mut SceneSpawner,
+    level_scene: &mut LevelScene,
+    active_level: &ActiveLevel,
+) {
+    if let Some(old) = level_scene.0.take() {
+        scene_spawner.despawn(old);
+    }
+
+    if let Some(path) = active_level.0.scene.as_ref() {
+        let scene = asset_server.load(path.as_str());
+        scene_spawner.spawn_dynamic(scene.clone());
+        level_scene.0 = Some(scene);
+    }
+}
+
+fn spawn_first_scene(
+    asset_server: Res<AssetServer>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut level_scene: ResMut<LevelScene>,
+    active_level: Res<ActiveLevel>,
+) {
+    spawn_scene(
+        &asset_server,
+        &mut scene_spawner,
+        &mut level_scene,
+        &active_level,
+    );
+}
+
+/// Swaps the last board's scene for the new one's on every new run.
+fn respawn_scene(
+    asset_server: Res<AssetServer>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut level_scene: ResMut<LevelScene>,
+    active_level: Res<ActiveLevel>,
+    mut reader: EventReader<NewRunEvent>,
+) {
+    if reader.iter().next().is_some() {
+        spawn_scene(
+            &asset_server,
+            &mut scene_spawner,
+            &mut level_scene,
+            &active_level,
+        );
+    }
+}
+
+type Segments<'w, 's> = Query<
+    'w,
+    's,
+    &'static mut Position,
+    (With<SnakePart>, Without<SceneWall>, Without<SpawnMarker>),
+>;
+
+/// Turns the scene's entities into the real thing as they come in. Walls and
+/// spawn markers hand over to what the rest of the game already spawns and
+/// go away; decorations stay, and go with the scene.
+fn build_scene_entities(
+    mut commands: Commands,
+    body: Res<SnakeBody>,
+    walls: Query<(Entity, &Position, &SceneWall), Added<SceneWall>>,
+    decorations: Query<(Entity, &Decoration), Added<Decoration>>,
+    markers: Query<(Entity, &Position), Added<SpawnMarker>>,
+    mut segments: Segments,
+) {
+    for (ent, position, wall) in walls.iter() {
+        let tile = if wall.breakable {
+            Tile::Breakable
+        } else {
+            Tile::Wall
+        };
+
+        spawn_tile(&mut commands, *position, tile);
+        commands.entity(ent).despawn();
+    }
+
+    for (ent, decoration) in decorations.iter() {
+        commands
+            .entity(ent)
+            .insert_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: decoration.color,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Size::square(decoration.size))
+            .insert(Layer::Board.at(DECORATION_ORDER));
+    }
+
+    // Lays the snake out from the marker the way a new one starts, heading
+    // up with the body trailing below.
+    if let Some((ent, marker)) = markers.iter().next() {
+        let mut at = *marker;
+
+        for segment in body.0.iter() {
+            if let Ok(mut position) = segments.get_mut(*segment) {
+                *position = at;
+            }
+            at = at.step(Direction::Down);
+        }
+
+        commands.entity(ent).despawn();
+    }
+}
+
+pub struct ScenesPlugin;
+
+impl Plugin for ScenesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Position>()
+            .register_type::<SceneWall>()
+            .register_type::<Decoration>()
+            .register_type::<SpawnMarker>()
+            .init_resource::<LevelScene>()
+            .add_startup_system(spawn_first_scene)
+            .add_system(respawn_scene)
+            .add_system(build_scene_entities);
+    }
+}
@@ -0,0 +1,219 @@
+//! Where a run's score came from. Each piece of food eaten is a point, and
+//! everything that adds points on top of that, or takes them away, goes
+//! through [`ScoreBreakdown`], which keeps a tally of each. The food count
+//! itself is left alone, since winning and the difficulty go by it. A run
+//! that's won under the par time also earns a time bonus here. Once the game
+//! over text is up, the breakdown is shown above it a line at a time, ending
+//! on the total.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::layers::Layer;
+use crate::progression::RunClock;
+use crate::timer::{Clock, TickTimer};
+use crate::{announce_game_over, AppState, DevouredFood, GameOverText, GameStatus};
+
+/// Winning inside this many seconds earns a time bonus.
+const PAR_SECONDS: u64 = 180;
+/// Seconds under par for each point of time bonus.
+const SECONDS_PER_TIME_POINT: u64 = 10;
+const RESULTS_TOP: f32 = 180.0;
+const RESULTS_LINE_HEIGHT: f32 = 22.0;
+const RESULTS_LABEL_X: f32 = -90.0;
+const RESULTS_VALUE_X: f32 = 90.0;
+const RESULTS_FONT_SIZE: f32 = 20.0;
+const RESULTS_LINE_SECONDS: f32 = 0.3;
+const PENALTY_COLOR: Color = Color::rgb(0.9, 0.4, 0.4);
+const TOTAL_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+
+/// What an award of points was for.
+#[derive(Clone, Copy)]
+pub enum ScoreSource {
+    Combo,
+    CloseCall,
+    /// Pickups and objectives: offshoots, dropped tails and chains.
+    Bonus,
+    Time,
+}
+
+/// The current run's points on top of the food eaten, by where they came
+/// from.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    combo: u32,
+    close_calls: u32,
+    bonuses: u32,
+    time: u32,
+    penalties: u32,
+}
+
+impl ScoreBreakdown {
+    pub fn award(&mut self, source: ScoreSource, points: u32) {
+        let tally = match source {
+            ScoreSource::Combo => &mut self.combo,
+            ScoreSource::CloseCall => &mut self.close_calls,
+            ScoreSource::Bonus => &mut self.bonuses,
+            ScoreSource::Time => &mut self.time,
+        };
+        *tally += points;
+    }
+
+    /// Takes points off the score, recording only what there was to take.
+    pub fn penalise(&mut self, eaten: &DevouredFood, points: u32) {
+        self.penalties += points.min(self.score(eaten));
+    }
+
+    /// The run's score: a point a piece of food, plus everything awarded,
+    /// less the penalties.
+    pub fn score(&self, eaten: &DevouredFood) -> u32 {
+        let awarded = self.combo + self.close_calls + self.bonuses + self.time;
+        (eaten.0 + awarded).saturating_sub(self.penalties)
+    }
+
+    /// Label and value for each line of the results, with the colour to show
+    /// the value in.
+    fn lines(&self, eaten: &DevouredFood) -> [(&'static str, String, Color); 7] {
+        [
+            ("Food", eaten.0.to_string(), Color::WHITE),
+            ("Combos", format!("+{}", self.combo), Color::WHITE),
+            (
+                "Near misses",
+                format!("+{}", self.close_calls),
+                Color::WHITE,
+            ),
+            ("Bonuses", format!("+{}", self.bonuses), Color::WHITE),
+            ("Time bonus", format!("+{}", self.time), Color::WHITE),
+            ("Penalties", format!("-{}", self.penalties), PENALTY_COLOR),
+            ("Total", self.score(eaten).to_string(), TOTAL_COLOR),
+        ]
+    }
+}
+
+fn time_bonus(clock: &RunClock) -> u32 {
+    (PAR_SECONDS.saturating_sub(clock.0.as_secs()) / SECONDS_PER_TIME_POINT) as u32
+}
+
+/// Pays the time bonus as a run is won, ahead of the game over event, so
+/// everything that reads the final score sees it included.
+fn award_time_bonus(
+    clock: Res<RunClock>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    query: Query<&GameStatus, Changed<GameStatus>>,
+) {
+    for status in query.iter() {
+        // Only one time bonus a run, however many times it's marked won.
+        if matches!(status, GameStatus::Won) && breakdown.time == 0 {
+            breakdown.award(ScoreSource::Time, time_bonus(&clock));
+        }
+    }
+}
+
+#[derive(Component)]
+struct ResultsLine;
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_results_text(
+    commands: &mut Commands,
+    font: &Handle<Font>,
+    value: &str,
+    color: Color,
+    x: f32,
+    y: f32,
+    horizontal: HorizontalAlign,
+    delay: f32,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                value,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: RESULTS_FONT_SIZE,
+                    color,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal,
+                },
+            ),
+            transform: Transform::from_xyz(x, y, 0.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ResultsLine)
+        .insert(TickTimer::new(delay, Clock::Real))
+        .insert(crate::cleanup::DespawnOnStateExit(AppState::GameOver))
+        .insert(Layer::Hud.at(2.0));
+}
+
+/// Lays the breakdown out above the game over text as soon as it's shown,
+/// each line hidden until its turn comes round.
+fn show_results(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    breakdown: Res<ScoreBreakdown>,
+    devoured: Query<&DevouredFood>,
+    shown: Query<(), Added<GameOverText>>,
+) {
+    if shown.is_empty() {
+        return;
+    }
+
+    let eaten = match devoured.iter().next() {
+        Some(eaten) => eaten,
+        None => return,
+    };
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    for (i, (label, value, color)) in breakdown.lines(eaten).iter().enumerate() {
+        let y = RESULTS_TOP - i as f32 * RESULTS_LINE_HEIGHT;
+        let delay = i as f32 * RESULTS_LINE_SECONDS;
+
+        spawn_results_text(
+            &mut commands,
+            &font,
+            label,
+            Color::WHITE,
+            RESULTS_LABEL_X,
+            y,
+            HorizontalAlign::Left,
+            delay,
+        );
+        spawn_results_text(
+            &mut commands,
+            &font,
+            value,
+            *color,
+            RESULTS_VALUE_X,
+            y,
+            HorizontalAlign::Right,
+            delay,
+        );
+    }
+}
+
+fn reveal_results(mut query: Query<(&TickTimer, &mut Visibility), With<ResultsLine>>) {
+    for (timer, mut visibility) in query.iter_mut() {
+        if timer.finished() && !visibility.is_visible {
+            visibility.is_visible = true;
+        }
+    }
+}
+
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreBreakdown>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                award_time_bonus.before(announce_game_over),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(show_results)
+                    .with_system(reveal_results),
+            );
+    }
+}
@@ -0,0 +1,97 @@
+//! Shows the run's seed on the pause and game over screens, with `C` to copy
+//! it to the clipboard for sharing.
+
+use arboard::Clipboard;
+use bevy::prelude::*;
+
+use crate::cleanup::DespawnOnStateExit;
+use crate::layers::Layer;
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::toast::ToastEvent;
+use crate::{AppState, GameOverText, RunSeed};
+
+const SEED_TEXT_Y: f32 = -35.0;
+const SEED_FONT_SIZE: f32 = 20.0;
+const SEED_COLOR: Color = Color::rgb(0.7, 0.7, 0.7);
+
+#[derive(Component)]
+struct SeedText;
+
+/// Shown on `state`'s screen until it's left.
+fn spawn_seed_text(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    seed: u64,
+    state: AppState,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                format!("Seed {} (C to copy)", seed),
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                    font_size: SEED_FONT_SIZE,
+                    color: SEED_COLOR,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, SEED_TEXT_Y, 0.0),
+            ..default()
+        })
+        .insert(SeedText)
+        .insert(DespawnOnStateExit(state))
+        .insert(Layer::Hud.at(2.0));
+}
+
+fn show_paused_seed(mut commands: Commands, asset_server: Res<AssetServer>, seed: Res<RunSeed>) {
+    spawn_seed_text(&mut commands, &asset_server, seed.0, AppState::Paused);
+}
+
+/// Waits for the game over text, which a lost run holds back until the
+/// death sequence is done.
+fn show_game_over_seed(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    seed: Res<RunSeed>,
+    texts: Query<(), Added<GameOverText>>,
+) {
+    if !texts.is_empty() {
+        spawn_seed_text(&mut commands, &asset_server, seed.0, AppState::GameOver);
+    }
+}
+
+fn copy_seed(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    seed: Res<RunSeed>,
+    texts: Query<(), With<SeedText>>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if captured.0 || texts.is_empty() || !settings.controls.hotkey(&keyboard_input, KeyCode::C) {
+        return;
+    }
+
+    let toast =
+        match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(seed.0.to_string())) {
+            Ok(()) => ToastEvent::info("Copied the seed to the clipboard"),
+            Err(e) => ToastEvent::error(format!("Failed to copy the seed: {}", e)),
+        };
+
+    toasts.send(toast);
+}
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Paused).with_system(show_paused_seed))
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver).with_system(show_game_over_seed),
+            )
+            .add_system(copy_seed);
+    }
+}
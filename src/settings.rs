@@ -0,0 +1,391 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::window::PresentMode;
+use serde::{Deserialize, Serialize};
+
+use crate::food_bias::FoodBias;
+use crate::speed_curve::SpeedCurve;
+use crate::theme::ThemeChoice;
+use crate::ui_scale::{UI_SCALE_MAX, UI_SCALE_MIN, UI_SCALE_STEP};
+use crate::Direction;
+
+const FPS_CAP_PRESETS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+const SETTINGS_DIR: &str = "snake";
+const SETTINGS_FILE: &str = "settings.toml";
+
+/// Options for this machine rather than for whoever's playing, kept in
+/// `settings.toml` under the user's config directory and saved whenever
+/// one of them changes. Gameplay rules and cosmetics go with the profile.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub video: VideoSettings,
+    pub controls: ControlSettings,
+    pub accessibility: AccessibilitySettings,
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(SETTINGS_DIR).join(SETTINGS_FILE))
+    }
+
+    /// Reads the settings file, falling back to the defaults when there
+    /// isn't one yet or it can't be parsed.
+    fn load() -> Self {
+        let contents = match Self::path().map(fs::read_to_string) {
+            Some(Ok(contents)) => contents,
+            _ => return Self::default(),
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|e| {
+            eprintln!("Failed to read settings, using the defaults: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return eprintln!("Failed to save settings: no config directory"),
+        };
+
+        let result = toml::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                fs::create_dir_all(path.parent().unwrap())
+                    .and_then(|_| fs::write(&path, contents))
+                    .map_err(|e| e.to_string())
+            });
+
+        if let Err(e) = result {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+}
+
+/// Sound levels, from 0.0 to 1.0.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub music_volume: f32,
+    pub sound_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            music_volume: 0.5,
+            sound_volume: 0.5,
+        }
+    }
+}
+
+/// Video options that can be changed while the game is running.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub vsync: bool,
+    pub fps_cap: Option<u32>,
+    pub grid_lines: bool,
+    /// Lines the edges of everything on the board up with whole pixels.
+    pub pixel_snap: bool,
+    /// Shows the latest turns pressed, for streaming.
+    pub input_display: bool,
+    /// Which monitor to open on, or wherever the system puts it.
+    pub monitor: Option<usize>,
+    /// Drops the title bar and border.
+    pub borderless: bool,
+    /// How big text and the HUD are drawn, from 0.75 to 2.0.
+    pub ui_scale: f32,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            fps_cap: None,
+            grid_lines: false,
+            pixel_snap: false,
+            input_display: false,
+            monitor: None,
+            borderless: false,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+impl VideoSettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::Fifo
+        } else {
+            PresentMode::Immediate
+        }
+    }
+
+    fn nudge_ui_scale(&mut self, steps: f32) {
+        self.ui_scale = (self.ui_scale + UI_SCALE_STEP * steps).clamp(UI_SCALE_MIN, UI_SCALE_MAX);
+    }
+
+    fn cycle_fps_cap(&mut self) {
+        let current = FPS_CAP_PRESETS
+            .iter()
+            .position(|cap| *cap == self.fps_cap)
+            .unwrap_or(0);
+
+        self.fps_cap = FPS_CAP_PRESETS[(current + 1) % FPS_CAP_PRESETS.len()];
+    }
+}
+
+/// Optional gameplay rules layered on top of the classic game.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    pub hunger: bool,
+    pub auto_pause: bool,
+    pub speed_curve: SpeedCurve,
+    pub food_bias: FoodBias,
+    /// See [`crate::wrap::Grace`].
+    pub wrap_grace: bool,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            hunger: false,
+            auto_pause: true,
+            speed_curve: SpeedCurve::Flat,
+            food_bias: FoodBias::Off,
+            wrap_grace: false,
+        }
+    }
+}
+
+/// Options for players who'd rather not have the screen moving around. Any
+/// purely visual effect (shake, particles, flashes, smoothing) checks this
+/// and skips itself, without touching the gameplay underneath.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub reduced_motion: bool,
+}
+
+/// Looks that don't change how the game plays.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CosmeticSettings {
+    /// Cycles the snake through the colours of the rainbow. Unlocked by
+    /// winning a run.
+    pub rainbow: bool,
+    pub theme: ThemeChoice,
+}
+
+/// A second set of steering keys on top of the arrow keys, which always work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControlPreset {
+    Arrows,
+    Wasd,
+    Ijkl,
+    Numpad,
+    Vim,
+}
+
+impl ControlPreset {
+    const ALL: [Self; 5] = [
+        Self::Arrows,
+        Self::Wasd,
+        Self::Ijkl,
+        Self::Numpad,
+        Self::Vim,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Arrows => "Arrows",
+            Self::Wasd => "WASD",
+            Self::Ijkl => "IJKL",
+            Self::Numpad => "Numpad",
+            Self::Vim => "Vim HJKL",
+        }
+    }
+
+    /// The preset `offset` places along from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        let count = Self::ALL.len() as isize;
+        let current = Self::ALL.iter().position(|p| p == self).unwrap() as isize;
+        Self::ALL[(current + offset).rem_euclid(count) as usize]
+    }
+
+    /// Left, up, right and down, in that order.
+    fn keys(&self) -> [KeyCode; 4] {
+        use KeyCode::*;
+
+        match self {
+            Self::Arrows => [Left, Up, Right, Down],
+            Self::Wasd => [A, W, D, S],
+            Self::Ijkl => [J, I, L, K],
+            Self::Numpad => [Numpad4, Numpad8, Numpad6, Numpad2],
+            Self::Vim => [H, K, L, J],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    pub preset: ControlPreset,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            preset: ControlPreset::Arrows,
+        }
+    }
+}
+
+impl ControlSettings {
+    /// Which way a key steers, if it steers at all.
+    pub fn direction(&self, key: KeyCode) -> Option<Direction> {
+        let directions = [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ];
+
+        ControlPreset::Arrows
+            .keys()
+            .iter()
+            .chain(self.preset.keys().iter())
+            .zip(directions.iter().cycle())
+            .find(|(k, _)| **k == key)
+            .map(|(_, dir)| *dir)
+    }
+
+    /// Whether `key` was pressed as a hotkey this frame. Keys the preset
+    /// steers with don't count, so WASD players don't toggle auto-pause
+    /// every time they turn left.
+    pub fn hotkey(&self, keyboard_input: &Input<KeyCode>, key: KeyCode) -> bool {
+        keyboard_input.just_pressed(key) && !self.preset.keys().contains(&key)
+    }
+}
+
+/// Set while a text prompt has the keyboard, so typing into it doesn't also
+/// flip every setting that has a hotkey.
+#[derive(Default)]
+pub struct KeyboardCaptured(pub bool);
+
+fn settings_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    captured: Res<KeyboardCaptured>,
+    mut settings: ResMut<Settings>,
+) {
+    if captured.0 {
+        return;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::V) {
+        settings.video.vsync = !settings.video.vsync;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::F) {
+        settings.video.cycle_fps_cap();
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::G) {
+        settings.video.grid_lines = !settings.video.grid_lines;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::X) {
+        settings.video.pixel_snap = !settings.video.pixel_snap;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::M) {
+        settings.accessibility.reduced_motion = !settings.accessibility.reduced_motion;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::I) {
+        settings.video.input_display = !settings.video.input_display;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::Minus) {
+        settings.video.nudge_ui_scale(-1.0);
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::Equals) {
+        settings.video.nudge_ui_scale(1.0);
+    }
+}
+
+fn gameplay_settings_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    captured: Res<KeyboardCaptured>,
+    settings: Res<Settings>,
+    mut gameplay: ResMut<GameplaySettings>,
+) {
+    if captured.0 {
+        return;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::H) {
+        gameplay.hunger = !gameplay.hunger;
+    }
+
+    if settings.controls.hotkey(&keyboard_input, KeyCode::A) {
+        gameplay.auto_pause = !gameplay.auto_pause;
+    }
+}
+
+fn apply_video_settings(settings: Res<Settings>, mut windows: ResMut<Windows>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_present_mode(settings.video.present_mode());
+    }
+}
+
+/// Writes the settings file whenever anything in it changes, but not for
+/// the load at startup.
+fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        settings.save();
+    }
+}
+
+/// Sleeps off whatever is left of the frame budget when a cap is set. The game
+/// logic runs on fixed timesteps, so rendering faster than this buys nothing.
+fn frame_rate_limiter(settings: Res<Settings>, mut last_frame: Local<Option<Instant>>) {
+    if let (Some(cap), Some(last)) = (settings.video.fps_cap, *last_frame) {
+        let budget = Duration::from_secs_f64(1.0 / cap as f64);
+        let elapsed = last.elapsed();
+
+        if elapsed < budget {
+            std::thread::sleep(budget - elapsed);
+        }
+    }
+
+    *last_frame = Some(Instant::now());
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .init_resource::<GameplaySettings>()
+            .init_resource::<CosmeticSettings>()
+            .init_resource::<KeyboardCaptured>()
+            .add_system(settings_input)
+            .add_system(gameplay_settings_input)
+            .add_system(apply_video_settings.after(settings_input))
+            .add_system(save_settings.after(settings_input))
+            .add_system_to_stage(CoreStage::Last, frame_rate_limiter);
+    }
+}
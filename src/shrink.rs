@@ -0,0 +1,126 @@
+//! A pickup for getting out of a tight spot. Eating it drops the last few
+//! segments off the tail on the spot, at the cost of a couple of points.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::boards::BoardId;
+use crate::cleanup::DespawnOnGameOver;
+use crate::layers::Layer;
+use crate::mode::GameMode;
+use crate::scoring::ScoreBreakdown;
+use crate::toast::{ToastEvent, ToastKind};
+use crate::{
+    playing_timestep, random_free_position, DevouredFood, GameRng, LastTailPosition, Position,
+    Size, SnakeBody, SnakeHead, SnakePart,
+};
+
+const PICKUP_COLOR: Color = Color::rgb(0.85, 0.35, 0.75);
+const SHRINK_SPAWN_INTERVAL: f64 = 10.0;
+/// Chance of a pickup turning up each time the spawner runs.
+const SHRINK_SPAWN_CHANCE: f64 = 0.3;
+const SHRINK_SEGMENTS: usize = 3;
+const SHRINK_PENALTY: u32 = 2;
+
+#[derive(Component)]
+pub struct ShrinkPickup;
+
+/// Only bothers while there's a tail worth losing, and never more than one
+/// pickup at a time.
+fn shrink_spawner(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    game_mode: Res<GameMode>,
+    body: Res<SnakeBody>,
+    pickups: Query<(), With<ShrinkPickup>>,
+    occupied: Query<&Position, Without<BoardId>>,
+) {
+    if *game_mode == GameMode::Puzzle || body.0.len() <= SHRINK_SEGMENTS || !pickups.is_empty() {
+        return;
+    }
+
+    if !rng.0.gen_bool(SHRINK_SPAWN_CHANCE) {
+        return;
+    }
+
+    let occupied: HashSet<Position> = occupied.iter().copied().collect();
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: PICKUP_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ShrinkPickup)
+        .insert(DespawnOnGameOver)
+        .insert(random_free_position(&mut rng.0, &occupied))
+        .insert(Size::square(0.6))
+        .insert(Layer::Entities.at(1.0));
+}
+
+/// Cuts the tail short when the head reaches a pickup, always leaving the
+/// head and at least one segment behind it.
+#[allow(clippy::too_many_arguments)]
+pub fn shrink_pickup(
+    mut commands: Commands,
+    mut body: ResMut<SnakeBody>,
+    mut last_tail_position: ResMut<LastTailPosition>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    pickups: Query<(Entity, &Position), With<ShrinkPickup>>,
+    heads: Query<&Position, With<SnakeHead>>,
+    parts: Query<&Position, With<SnakePart>>,
+    devoured: Query<&DevouredFood>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    for (ent, position) in pickups.iter() {
+        if *position != head {
+            continue;
+        }
+
+        commands.entity(ent).despawn();
+
+        let keep = body.0.len().saturating_sub(SHRINK_SEGMENTS).max(2);
+        if keep >= body.0.len() {
+            continue;
+        }
+
+        let dropped = body.0.split_off(keep);
+
+        // Anything grown this tick goes where the new tail just was.
+        *last_tail_position = LastTailPosition(parts.get(dropped[0]).ok().copied());
+
+        for segment in dropped.iter() {
+            commands.entity(*segment).despawn();
+        }
+
+        if let Some(eaten) = devoured.iter().next() {
+            breakdown.penalise(eaten, SHRINK_PENALTY);
+        }
+
+        toasts.send(ToastEvent::new(
+            ToastKind::PowerUp,
+            format!("Shrink! -{} segments, -{}", dropped.len(), SHRINK_PENALTY),
+        ));
+    }
+}
+
+pub struct ShrinkPlugin;
+
+impl Plugin for ShrinkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::new()
+                .with_run_criteria(playing_timestep(SHRINK_SPAWN_INTERVAL))
+                .with_system(shrink_spawner),
+        );
+    }
+}
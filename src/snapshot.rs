@@ -0,0 +1,301 @@
+//! Saving a run part way through and picking it back up later. `F5` on the
+//! pause screen writes the board to `save/snapshot.ron`, and Continue on the
+//! main menu restores it and carries on from there. A snapshot can only be
+//! continued once. Boards whose state a snapshot doesn't keep, like ones
+//! with walls to break, keys, gates, a chain, or anything hunting the snake
+//! or waiting to be picked up, can't be saved.
+
+use std::fs;
+use std::path::Path;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::autosave::{load_file, save_file, SAVE_DIR};
+use crate::boost::Stamina;
+use crate::boss::Boss;
+use crate::combo::{Combo, SavedCombo};
+use crate::hunger::Hunger;
+use crate::hunter::Hunter;
+use crate::length_cap::DroppedTail;
+use crate::level::{ActiveLevel, Level};
+use crate::mode::GameMode;
+use crate::obstacles::{Hammer, HammerPowerUp, Tile};
+use crate::run_events::SpeedSurge;
+use crate::scoring::ScoreBreakdown;
+use crate::settings::{KeyboardCaptured, Settings};
+use crate::shrink::ShrinkPickup;
+use crate::split::{Offshoot, SplitPickup};
+use crate::toast::ToastEvent;
+use crate::transition::TransitionEvent;
+use crate::{
+    handle_render_event, new_run, spawn_food, spawn_snake_part, AppState, DevouredFood, Direction,
+    Food, GameRng, NewRunEvent, Position, RenderedFood, RunKind, RunSeed, SnakeBody, SnakeHead,
+    SnakePart,
+};
+
+const SNAPSHOT_FILE: &str = "snapshot.ron";
+
+/// Everything needed to put a run back the way it was.
+#[derive(Serialize, Deserialize)]
+struct RunSnapshot {
+    mode: GameMode,
+    run_kind: RunKind,
+    level: Level,
+    /// Every segment, head first.
+    snake: Vec<Position>,
+    direction: Direction,
+    food: Vec<Position>,
+    devoured: u32,
+    rendered: u32,
+    seed: u64,
+    /// What [`GameRng`] was reseeded with when the snapshot was taken, so
+    /// the food lands in the same places after continuing as it would have.
+    rng_seed: u64,
+    #[serde(default)]
+    breakdown: ScoreBreakdown,
+    #[serde(default)]
+    hunger: Hunger,
+    #[serde(default)]
+    stamina: Stamina,
+    #[serde(default)]
+    combo: SavedCombo,
+}
+
+/// Anything on the board a snapshot has no room for.
+type Unsaveable<'w, 's> = Query<
+    'w,
+    's,
+    (),
+    Or<(
+        With<Hunter>,
+        With<Boss>,
+        With<SplitPickup>,
+        With<Offshoot>,
+        With<ShrinkPickup>,
+        With<DroppedTail>,
+        With<SpeedSurge>,
+        With<Hammer>,
+        With<HammerPowerUp>,
+    )>,
+>;
+
+/// The rest of a run's state, saved alongside the board.
+#[derive(SystemParam)]
+struct RunState<'w, 's> {
+    breakdown: Res<'w, ScoreBreakdown>,
+    hunger: Res<'w, Hunger>,
+    stamina: Res<'w, Stamina>,
+    combo: Res<'w, Combo>,
+    unsaveable: Unsaveable<'w, 's>,
+}
+
+/// Why the run as it stands can't be saved, if it can't.
+fn unsaveable_reason(mode: GameMode, level: &Level, state: &RunState) -> Option<&'static str> {
+    if matches!(mode, GameMode::Puzzle | GameMode::Sandbox) {
+        return Some("Puzzle and sandbox runs can't be saved");
+    }
+
+    let stateful = level.tiles.iter().any(|(_, tile)| {
+        matches!(
+            tile,
+            Tile::Breakable | Tile::Door(_) | Tile::Key(_) | Tile::Gate(_)
+        )
+    });
+    if stateful || !level.chain.is_empty() {
+        return Some("Runs on this board can't be saved");
+    }
+
+    if !state.unsaveable.is_empty() {
+        return Some("Can't save with hunters or pickups on the board");
+    }
+
+    None
+}
+
+/// Sent by the main menu to continue the saved run.
+pub struct ContinueRunEvent;
+
+/// A snapshot waiting for a fresh board to be laid out, before it's put over
+/// the top.
+pub struct PendingSnapshot(RunSnapshot);
+
+#[allow(clippy::too_many_arguments)]
+fn save_snapshot(
+    keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    captured: Res<KeyboardCaptured>,
+    game_mode: Res<GameMode>,
+    run_kind: Res<RunKind>,
+    active_level: Res<ActiveLevel>,
+    seed: Res<RunSeed>,
+    body: Res<SnakeBody>,
+    mut rng: ResMut<GameRng>,
+    heads: Query<&SnakeHead>,
+    positions: Query<&Position, With<SnakePart>>,
+    food: Query<&Position, With<Food>>,
+    counts: Query<(&DevouredFood, &RenderedFood)>,
+    state: RunState,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if captured.0 || !settings.controls.hotkey(&keyboard_input, KeyCode::F5) {
+        return;
+    }
+
+    if let Some(reason) = unsaveable_reason(*game_mode, &active_level.0, &state) {
+        return toasts.send(ToastEvent::info(reason));
+    }
+
+    let (head, (devoured, rendered)) = match (heads.iter().next(), counts.iter().next()) {
+        (Some(head), Some(counts)) => (head, counts),
+        _ => return,
+    };
+
+    // Reseeding from here on makes the rest of the run repeatable from the
+    // snapshot, which a generator's internal state can't be saved to do.
+    let rng_seed = rng.0.gen();
+    rng.0 = StdRng::seed_from_u64(rng_seed);
+
+    let snapshot = RunSnapshot {
+        mode: *game_mode,
+        run_kind: *run_kind,
+        level: active_level.0.clone(),
+        snake: body
+            .0
+            .iter()
+            .filter_map(|e| positions.get(*e).ok())
+            .copied()
+            .collect(),
+        direction: head.moved,
+        food: food.iter().copied().collect(),
+        devoured: devoured.0,
+        rendered: rendered.0,
+        seed: seed.0,
+        rng_seed,
+        breakdown: state.breakdown.clone(),
+        hunger: state.hunger.clone(),
+        stamina: state.stamina.clone(),
+        combo: state.combo.save(),
+    };
+
+    save_file(Path::new(SNAPSHOT_FILE), &snapshot);
+    toasts.send(ToastEvent::info(
+        "Run saved, continue it from the main menu",
+    ));
+}
+
+/// Starts a fresh run on the snapshot's board, and holds the snapshot back
+/// until it's been laid out.
+#[allow(clippy::too_many_arguments)]
+fn continue_run(
+    mut commands: Commands,
+    mut reader: EventReader<ContinueRunEvent>,
+    mut game_mode: ResMut<GameMode>,
+    mut run_kind: ResMut<RunKind>,
+    mut active_level: ResMut<ActiveLevel>,
+    mut new_run: EventWriter<NewRunEvent>,
+    mut transitions: EventWriter<TransitionEvent>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if reader.iter().next().is_none() {
+        return;
+    }
+
+    let snapshot: RunSnapshot = match load_file(Path::new(SNAPSHOT_FILE)) {
+        Some(snapshot) => snapshot,
+        None => return toasts.send(ToastEvent::info("No saved run to continue")),
+    };
+
+    let _ = fs::remove_file(Path::new(SAVE_DIR).join(SNAPSHOT_FILE));
+
+    *game_mode = snapshot.mode;
+    *run_kind = snapshot.run_kind;
+    active_level.0 = snapshot.level.clone();
+    commands.insert_resource(PendingSnapshot(snapshot));
+
+    new_run.send(NewRunEvent);
+    transitions.send(TransitionEvent(AppState::Playing));
+}
+
+/// Runs once the fresh board's entities exist, and moves the snake, food
+/// and score over to where the snapshot had them, along with the rest of the
+/// run's state. By then the fresh board's
+/// first food has been counted, so the count can be overwritten.
+#[allow(clippy::too_many_arguments)]
+fn apply_snapshot(
+    mut commands: Commands,
+    pending: Option<Res<PendingSnapshot>>,
+    mut body: ResMut<SnakeBody>,
+    mut rng: ResMut<GameRng>,
+    mut heads: Query<&mut SnakeHead>,
+    mut positions: Query<&mut Position, With<SnakePart>>,
+    food: Query<Entity, With<Food>>,
+    mut counts: Query<(&mut DevouredFood, &mut RenderedFood)>,
+) {
+    let pending = match pending {
+        Some(pending) => pending,
+        None => return,
+    };
+    let snapshot = &pending.0;
+
+    // The fresh snake is only just in place.
+    if body.0.iter().any(|e| positions.get(*e).is_err()) {
+        return;
+    }
+
+    commands.remove_resource::<PendingSnapshot>();
+    commands.insert_resource(RunSeed(snapshot.seed));
+    commands.insert_resource(snapshot.breakdown.clone());
+    commands.insert_resource(snapshot.hunger.clone());
+    commands.insert_resource(snapshot.stamina.clone());
+    commands.insert_resource(Combo::restore(&snapshot.combo));
+    rng.0 = StdRng::seed_from_u64(snapshot.rng_seed);
+
+    if let Some(mut head) = heads.iter_mut().next() {
+        head.direction = snapshot.direction;
+        head.moved = snapshot.direction;
+        head.turns.clear();
+    }
+
+    for (i, position) in snapshot.snake.iter().enumerate() {
+        match body.0.get(i).copied() {
+            Some(ent) => {
+                if let Ok(mut current) = positions.get_mut(ent) {
+                    *current = *position;
+                }
+            }
+            None => body.0.push(spawn_snake_part(&mut commands, *position)),
+        }
+    }
+
+    for ent in body.0.drain(snapshot.snake.len()..) {
+        commands.entity(ent).despawn_recursive();
+    }
+
+    for ent in food.iter() {
+        commands.entity(ent).despawn();
+    }
+
+    for position in snapshot.food.iter() {
+        spawn_food(&mut commands, *position);
+    }
+
+    if let Some((mut devoured, mut rendered)) = counts.iter_mut().next() {
+        devoured.0 = snapshot.devoured;
+        rendered.0 = snapshot.rendered;
+    }
+}
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ContinueRunEvent>()
+            .add_system_set(SystemSet::on_update(AppState::Paused).with_system(save_snapshot))
+            .add_system(continue_run)
+            .add_system(apply_snapshot.after(new_run).after(handle_render_event));
+    }
+}
@@ -0,0 +1,67 @@
+//! How the snake speeds up as the score climbs, picked per profile. Each
+//! curve gives the fraction of the base step to wait between moves, so 0.5
+//! moves twice as fast as the run started.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::GameplaySettings;
+use crate::{AppState, DevouredFood, TickRate};
+
+/// However a curve is set up, the snake never gets faster than this.
+const FASTEST_RAMP: f64 = 0.1;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum SpeedCurve {
+    /// The same speed all run.
+    #[default]
+    Flat,
+    /// Takes `per_point` off the step for every point scored.
+    Linear { per_point: f64 },
+    /// Takes `amount` off the step once every `every` points.
+    Stepped { every: u32, amount: f64 },
+    /// Multiplies the step by `factor` for every point scored.
+    Exponential { factor: f64 },
+    /// Follows `curve`, but never past `fastest`.
+    Capped {
+        curve: Box<SpeedCurve>,
+        fastest: f64,
+    },
+}
+
+impl SpeedCurve {
+    fn ramp(&self, score: u32) -> f64 {
+        let ramp = match self {
+            Self::Flat => 1.0,
+            Self::Linear { per_point } => 1.0 - per_point * score as f64,
+            Self::Stepped { every, amount } => {
+                1.0 - amount * (score.checked_div(*every).unwrap_or(0)) as f64
+            }
+            Self::Exponential { factor } => factor.powi(score as i32),
+            Self::Capped { curve, fastest } => curve.ramp(score).max(*fastest),
+        };
+
+        ramp.clamp(FASTEST_RAMP, 1.0)
+    }
+}
+
+fn apply_speed_curve(
+    gameplay: Res<GameplaySettings>,
+    devoured: Query<&DevouredFood>,
+    mut tick_rate: ResMut<TickRate>,
+) {
+    let score = devoured.iter().next().map_or(0, |eaten| eaten.0);
+    let ramp = gameplay.speed_curve.ramp(score);
+
+    if tick_rate.ramp != ramp {
+        tick_rate.ramp = ramp;
+    }
+}
+
+pub struct SpeedCurvePlugin;
+
+impl Plugin for SpeedCurvePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_update(AppState::Playing).with_system(apply_speed_curve));
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::transition::{ScreenFade, TransitionEvent};
+use crate::{AppState, BG_COLOR, WIN_HEIGHT, WIN_WIDTH};
+
+const SPLASH_MIN_SECONDS: f32 = 1.5;
+/// Loaded behind the splash, so the first frame of play doesn't stall on them.
+const PRELOADED_ASSETS: [&str; 2] = ["fonts/FiraSans-Bold.ttf", "fonts/FiraSans-Regular.ttf"];
+
+/// Strong handles to everything preloaded, kept for the whole session so the
+/// assets aren't unloaded again the moment the splash goes away.
+struct PreloadedAssets(Vec<HandleUntyped>);
+
+struct SplashTimer(Timer);
+
+#[derive(Component)]
+struct SplashOnly;
+
+fn enter_splash(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handles = PRELOADED_ASSETS
+        .iter()
+        .map(|path| asset_server.load_untyped(*path))
+        .collect();
+
+    commands.insert_resource(PreloadedAssets(handles));
+    commands.insert_resource(SplashTimer(Timer::from_seconds(SPLASH_MIN_SECONDS, false)));
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: BG_COLOR,
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SplashOnly)
+        .insert(Layer::Overlay.at(3.0));
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Snake!",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 80.0,
+                    color: Color::rgb(0.0, 0.7, 0.0),
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
+            ..default()
+        })
+        .insert(SplashOnly)
+        .insert(Layer::Overlay.at(4.0));
+}
+
+/// Moves on once the splash has been up for a moment and the preload has
+/// finished, whether or not every asset actually made it.
+fn finish_splash(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    preloaded: Res<PreloadedAssets>,
+    fade: Res<ScreenFade>,
+    mut timer: ResMut<SplashTimer>,
+    mut transitions: EventWriter<TransitionEvent>,
+) {
+    if !timer.0.tick(time.delta()).finished() || fade.is_active() {
+        return;
+    }
+
+    let ids = preloaded.0.iter().map(|handle| handle.id);
+    if asset_server.get_group_load_state(ids) != LoadState::Loading {
+        transitions.send(TransitionEvent(AppState::MainMenu));
+    }
+}
+
+fn exit_splash(mut commands: Commands, query: Query<Entity, With<SplashOnly>>) {
+    commands.remove_resource::<SplashTimer>();
+
+    for ent in query.iter() {
+        commands.entity(ent).despawn();
+    }
+}
+
+pub struct SplashPlugin;
+
+impl Plugin for SplashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Splash).with_system(enter_splash))
+            .add_system_set(SystemSet::on_update(AppState::Splash).with_system(finish_splash))
+            .add_system_set(SystemSet::on_exit(AppState::Splash).with_system(exit_splash));
+    }
+}
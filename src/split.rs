@@ -0,0 +1,246 @@
+//! A rare pickup that splits the snake in two. The tail half breaks away as
+//! an offshoot that wanders the board on its own, and running the head back
+//! into it re-absorbs it for bonus points.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::boards::BoardId;
+use crate::cleanup::DespawnOnGameOver;
+use crate::layers::Layer;
+use crate::mode::GameMode;
+use crate::obstacles::Wall;
+use crate::scoring::{ScoreBreakdown, ScoreSource};
+use crate::toast::ToastEvent;
+use crate::{
+    playing_timestep, random_free_position, spawn_snake_part, Direction, GameRng, LastTailPosition,
+    Position, Size, SnakeBody, SnakeHead, SnakePart,
+};
+
+const PICKUP_COLOR: Color = Color::rgb(0.2, 0.8, 0.9);
+const OFFSHOOT_COLOR: Color = Color::rgb(0.2, 0.45, 0.5);
+const SPLIT_SPAWN_INTERVAL: f64 = 15.0;
+/// Chance of a pickup turning up each time the spawner runs.
+const SPLIT_SPAWN_CHANCE: f64 = 0.25;
+/// The snake has to be at least this long for there to be a tail to spare.
+const SPLIT_MIN_LENGTH: usize = 6;
+/// How likely the offshoot is to keep going the way it's headed each move.
+const WANDER_KEEP_CHANCE: f64 = 0.75;
+const ABSORB_BONUS: u32 = 3;
+
+#[derive(Component)]
+pub struct SplitPickup;
+
+/// The broken off tail, steering itself. Its segments are kept in a
+/// [`SnakeBody`] on the same entity and move every other tick.
+#[derive(Component)]
+pub struct Offshoot {
+    direction: Direction,
+    rested: bool,
+}
+
+/// A segment of an [`Offshoot`]; no longer part of the player's snake.
+#[derive(Component)]
+pub struct OffshootPart;
+
+fn split_spawner(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    game_mode: Res<GameMode>,
+    body: Res<SnakeBody>,
+    pickups: Query<(), With<SplitPickup>>,
+    offshoots: Query<(), With<Offshoot>>,
+    occupied: Query<&Position, Without<BoardId>>,
+) {
+    if *game_mode == GameMode::Puzzle
+        || body.0.len() < SPLIT_MIN_LENGTH
+        || !pickups.is_empty()
+        || !offshoots.is_empty()
+    {
+        return;
+    }
+
+    if !rng.0.gen_bool(SPLIT_SPAWN_CHANCE) {
+        return;
+    }
+
+    let occupied: HashSet<Position> = occupied.iter().copied().collect();
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: PICKUP_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SplitPickup)
+        .insert(DespawnOnGameOver)
+        .insert(random_free_position(&mut rng.0, &occupied))
+        .insert(Size::square(0.6))
+        .insert(Layer::Entities.at(1.0));
+}
+
+/// Breaks the tail half off the snake when the head reaches a pickup. The old
+/// tail end leads the offshoot, so it heads away from where it came from.
+pub fn split_pickup(
+    mut commands: Commands,
+    mut body: ResMut<SnakeBody>,
+    mut last_tail_position: ResMut<LastTailPosition>,
+    pickups: Query<(Entity, &Position), With<SplitPickup>>,
+    heads: Query<&Position, With<SnakeHead>>,
+    parts: Query<&Position, With<SnakePart>>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let head = match heads.iter().next() {
+        Some(head) => *head,
+        None => return,
+    };
+
+    for (ent, position) in pickups.iter() {
+        if *position != head {
+            continue;
+        }
+
+        commands.entity(ent).despawn();
+
+        if body.0.len() < SPLIT_MIN_LENGTH {
+            continue;
+        }
+
+        let half = body.0.len() / 2;
+        let mut tail = body.0.split_off(half);
+
+        // Anything grown this tick goes where the remaining tail just was.
+        *last_tail_position = LastTailPosition(parts.get(tail[0]).ok().copied());
+
+        for segment in tail.iter() {
+            commands
+                .entity(*segment)
+                .remove::<SnakePart>()
+                .insert(OffshootPart)
+                .insert(Sprite {
+                    color: OFFSHOOT_COLOR,
+                    ..default()
+                });
+        }
+
+        tail.reverse();
+        commands
+            .spawn()
+            .insert(Offshoot {
+                direction: Direction::Up,
+                rested: false,
+            })
+            .insert(SnakeBody(tail))
+            .insert(DespawnOnGameOver);
+
+        toasts.send(ToastEvent::info("Split! Catch your tail again"));
+    }
+}
+
+/// Wanders each offshoot about, mostly in a straight line with the odd
+/// random turn, steering clear of walls and both snakes.
+pub fn offshoot_movement(
+    mut rng: ResMut<GameRng>,
+    mut offshoots: Query<(&mut Offshoot, &SnakeBody)>,
+    mut positions: Query<&mut Position, With<OffshootPart>>,
+    parts: Query<&Position, (With<SnakePart>, Without<OffshootPart>)>,
+    walls: Query<&Position, (With<Wall>, Without<OffshootPart>)>,
+) {
+    let mut blocked: HashSet<Position> = parts.iter().chain(walls.iter()).copied().collect();
+
+    for (mut offshoot, body) in offshoots.iter_mut() {
+        offshoot.rested = !offshoot.rested;
+        if offshoot.rested {
+            continue;
+        }
+
+        let before = body.positions(&positions);
+        let head = before[0];
+
+        // The tail end moves out of the way, so it's fair game.
+        let own: HashSet<Position> = before[..before.len() - 1].iter().copied().collect();
+        let is_free = |direction: Direction| {
+            let next = head.step(direction);
+            !blocked.contains(&next) && !own.contains(&next)
+        };
+
+        let turns: Vec<Direction> = [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+        .into_iter()
+        .filter(|direction| *direction != -offshoot.direction && is_free(*direction))
+        .collect();
+
+        let keep = is_free(offshoot.direction) && rng.0.gen_bool(WANDER_KEEP_CHANCE);
+        if !keep {
+            match turns.choose(&mut rng.0) {
+                Some(direction) => offshoot.direction = *direction,
+                None => continue,
+            }
+        }
+
+        *positions.get_mut(body.0[0]).unwrap() = head.step(offshoot.direction);
+        body.follow(&before, &mut positions);
+
+        blocked.extend(body.positions(&positions));
+    }
+}
+
+/// Running the head into an offshoot takes it back into the snake, along with
+/// a few points for the trouble.
+#[allow(clippy::too_many_arguments)]
+pub fn offshoot_absorb(
+    mut commands: Commands,
+    last_tail_position: Res<LastTailPosition>,
+    mut body: ResMut<SnakeBody>,
+    mut breakdown: ResMut<ScoreBreakdown>,
+    offshoots: Query<(Entity, &SnakeBody), With<Offshoot>>,
+    parts: Query<&Position, With<OffshootPart>>,
+    heads: Query<&Position, (With<SnakeHead>, Without<OffshootPart>)>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    let (head, tail) = match (heads.iter().next(), last_tail_position.0) {
+        (Some(head), Some(tail)) => (*head, tail),
+        _ => return,
+    };
+
+    for (ent, offshoot) in offshoots.iter() {
+        if !offshoot
+            .0
+            .iter()
+            .any(|e| matches!(parts.get(*e), Ok(part) if *part == head))
+        {
+            continue;
+        }
+
+        for segment in offshoot.0.iter() {
+            commands.entity(*segment).despawn();
+            body.0.push(spawn_snake_part(&mut commands, tail));
+        }
+        commands.entity(ent).despawn();
+
+        breakdown.award(ScoreSource::Bonus, ABSORB_BONUS);
+
+        toasts.send(ToastEvent::info(format!("Re-absorbed! +{}", ABSORB_BONUS)));
+    }
+}
+
+pub struct SplitPlugin;
+
+impl Plugin for SplitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::new()
+                .with_run_criteria(playing_timestep(SPLIT_SPAWN_INTERVAL))
+                .with_system(split_spawner),
+        );
+    }
+}
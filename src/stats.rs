@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cheats::Cheats;
+use crate::kiosk::Kiosk;
+use crate::pace::RunPace;
+use crate::puzzle::Medal;
+use crate::scoring::ScoreBreakdown;
+use crate::{
+    DevouredFood, GameOverEvent, GameStatus, GrowthEvent, Position, SnakeHead, GRID_HEIGHT,
+    GRID_WIDTH,
+};
+
+/// Lifetime totals across every run, persisted by the autosave.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub runs_played: u32,
+    pub wins: u32,
+    pub best_score: u32,
+    pub food_eaten: u32,
+    /// The score every few seconds through the best run, to race against.
+    pub best_pace: Vec<u32>,
+    /// Food eaten on each cell of the board, row by row from the bottom left.
+    pub food_heatmap: Vec<u32>,
+    /// The best medal earned on each puzzle, by level name.
+    pub puzzle_medals: BTreeMap<String, Medal>,
+}
+
+impl Stats {
+    /// The most food eaten on any one cell, with the count for each.
+    pub fn heatmap(&self) -> (u32, impl Iterator<Item = (Position, u32)> + '_) {
+        let most = self.food_heatmap.iter().copied().max().unwrap_or(0);
+        let cells = self.food_heatmap.iter().enumerate().map(|(i, count)| {
+            let (x, y) = (i as u32 % GRID_WIDTH, i as u32 / GRID_WIDTH);
+            (Position::new(x as i32, y as i32), *count)
+        });
+
+        (most, cells)
+    }
+
+    /// How many puzzles have `medal` as their best.
+    pub fn medals(&self, medal: Medal) -> usize {
+        self.puzzle_medals.values().filter(|m| **m == medal).count()
+    }
+
+    /// Keeps `medal` for the level, unless a better one is already there.
+    pub fn record_medal(&mut self, level: &str, medal: Medal) {
+        let best = self.puzzle_medals.entry(level.to_string()).or_insert(medal);
+        *best = (*best).max(medal);
+    }
+
+    fn record_eaten_at(&mut self, position: Position) {
+        let cells = (GRID_WIDTH * GRID_HEIGHT) as usize;
+        if self.food_heatmap.len() != cells {
+            self.food_heatmap = vec![0; cells];
+        }
+
+        if (0..GRID_WIDTH as i32).contains(&position.x)
+            && (0..GRID_HEIGHT as i32).contains(&position.y)
+        {
+            self.food_heatmap[(position.y as u32 * GRID_WIDTH + position.x as u32) as usize] += 1;
+        }
+    }
+}
+
+fn record_food(
+    cheats: Res<Cheats>,
+    kiosk: Res<Kiosk>,
+    mut stats: ResMut<Stats>,
+    mut growth_reader: EventReader<GrowthEvent>,
+    heads: Query<&Position, With<SnakeHead>>,
+) {
+    let eaten = growth_reader.iter().count() as u32;
+
+    // The game playing itself doesn't count, and nor does food eaten once a
+    // cheat's been on, since the kiosk's table ranks it.
+    if eaten > 0 && !kiosk.attract && !cheats.used {
+        stats.food_eaten += eaten;
+
+        if let Some(head) = heads.iter().next() {
+            for _ in 0..eaten {
+                stats.record_eaten_at(*head);
+            }
+        }
+    }
+}
+
+fn record_run(
+    cheats: Res<Cheats>,
+    kiosk: Res<Kiosk>,
+    pace: Res<RunPace>,
+    breakdown: Res<ScoreBreakdown>,
+    mut stats: ResMut<Stats>,
+    mut reader: EventReader<GameOverEvent>,
+    devoured: Query<&DevouredFood>,
+) {
+    let event = match reader.iter().next() {
+        Some(event) if !kiosk.attract => event,
+        _ => return,
+    };
+
+    let score = devoured
+        .iter()
+        .next()
+        .map_or(0, |eaten| breakdown.score(eaten));
+
+    stats.runs_played += 1;
+
+    // A cheated run still counts as played, just not for the best score or
+    // as a win.
+    if cheats.used {
+        return;
+    }
+
+    if score > stats.best_score {
+        stats.best_score = score;
+        stats.best_pace = pace.samples.clone();
+    }
+
+    if let GameStatus::Won = event.0 {
+        stats.wins += 1;
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Stats>()
+            .add_system(record_food)
+            .add_system(record_run);
+    }
+}
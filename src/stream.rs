@@ -0,0 +1,139 @@
+//! Opt in live feed of the board for dashboards, overlays and bots, enabled
+//! with `--stream <port>`. Anyone connecting to that port on localhost gets
+//! a JSON line for every movement tick until they disconnect.
+
+use std::io::{ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::observation::{Observation, ObservationEvent};
+use crate::scoring::ScoreBreakdown;
+use crate::{DevouredFood, GameStatus, NewRunEvent};
+
+#[derive(Serialize)]
+struct StreamLine<'a> {
+    tick: u64,
+    score: u32,
+    status: Option<&'a GameStatus>,
+    #[serde(flatten)]
+    board: &'a Observation,
+}
+
+#[derive(Default)]
+struct StateStream {
+    listener: Option<TcpListener>,
+    clients: Vec<TcpStream>,
+    tick: u64,
+}
+
+impl StateStream {
+    fn from_args() -> Self {
+        let port = std::env::args().skip_while(|arg| arg != "--stream").nth(1);
+
+        let listener = port.and_then(|port| {
+            let bound = port
+                .parse::<u16>()
+                .map_err(|e| e.to_string())
+                .and_then(|number| {
+                    TcpListener::bind(("127.0.0.1", number))
+                        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+                        .map_err(|e| e.to_string())
+                });
+
+            match bound {
+                Ok(listener) => Some(listener),
+                Err(e) => {
+                    eprintln!("Failed to start the state stream on port '{}': {}", port, e);
+                    None
+                }
+            }
+        });
+
+        Self {
+            listener,
+            ..default()
+        }
+    }
+
+    /// Picks up everyone who has connected since the last frame.
+    fn accept(&mut self) {
+        let listener = match self.listener.as_ref() {
+            Some(listener) => listener,
+            None => return,
+        };
+
+        loop {
+            match listener.accept() {
+                Ok((client, _)) => {
+                    // A client that can't keep up gets dropped rather than
+                    // holding the game up.
+                    if client.set_nonblocking(true).is_ok() {
+                        self.clients.push(client);
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("Failed to accept a state stream client: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn broadcast(&mut self, line: &[u8]) {
+        self.clients
+            .retain_mut(|client| client.write_all(line).is_ok());
+    }
+}
+
+fn stream_state(
+    mut stream: ResMut<StateStream>,
+    mut new_runs: EventReader<NewRunEvent>,
+    mut observations: EventReader<ObservationEvent>,
+    breakdown: Res<ScoreBreakdown>,
+    devoured: Query<&DevouredFood>,
+    statuses: Query<&GameStatus>,
+) {
+    if stream.listener.is_none() {
+        return;
+    }
+
+    if new_runs.iter().next().is_some() {
+        stream.tick = 0;
+    }
+
+    stream.accept();
+
+    for ObservationEvent(board) in observations.iter() {
+        stream.tick += 1;
+
+        let line = StreamLine {
+            tick: stream.tick,
+            score: devoured
+                .iter()
+                .next()
+                .map_or(0, |eaten| breakdown.score(eaten)),
+            status: statuses.iter().next(),
+            board,
+        };
+
+        match serde_json::to_vec(&line) {
+            Ok(mut bytes) => {
+                bytes.push(b'\n');
+                stream.broadcast(&bytes);
+            }
+            Err(e) => eprintln!("Failed to encode the board for the state stream: {}", e),
+        }
+    }
+}
+
+pub struct StreamPlugin;
+
+impl Plugin for StreamPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StateStream::from_args())
+            .add_system(stream_state);
+    }
+}
@@ -0,0 +1,140 @@
+//! Optional syncing of profiles to a server, so stats and settings follow the
+//! player between machines. Enabled with `--sync-url <url>`, which can be any
+//! endpoint that stores a `PUT` of `<url>/<profile>.ron` and hands it back on
+//! `GET`, such as a WebDAV folder or a writable S3 bucket.
+
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use ron::ser::PrettyConfig;
+
+use crate::profile::ProfileData;
+
+const SYNC_TOKEN_VAR: &str = "SNAKE_SYNC_TOKEN";
+const SYNC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Seconds since the epoch, used to decide which copy of a profile is newer.
+pub fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[derive(Default)]
+pub struct ProfileSync {
+    url: Option<String>,
+    /// Sent as a bearer token when `SNAKE_SYNC_TOKEN` is set.
+    token: Option<String>,
+    /// The upload in flight, if any, so uploads land in the order they were
+    /// made and the last one can be waited on at exit.
+    upload: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ProfileSync {
+    pub fn from_args() -> Self {
+        let url = std::env::args()
+            .skip_while(|arg| arg != "--sync-url")
+            .nth(1);
+
+        Self {
+            url: url.map(|url| url.trim_end_matches('/').to_string()),
+            token: std::env::var(SYNC_TOKEN_VAR).ok(),
+            ..Default::default()
+        }
+    }
+
+    fn request(&self, method: &str, name: &str) -> Option<ureq::Request> {
+        let url = format!("{}/{}.ron", self.url.as_ref()?, name);
+        let request = ureq::AgentBuilder::new()
+            .timeout(SYNC_TIMEOUT)
+            .build()
+            .request(method, &url);
+
+        Some(match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+            None => request,
+        })
+    }
+
+    /// Downloads the server's copy of a profile on the async compute pool, so
+    /// a slow server never stalls the game. Gives no task without a server.
+    pub fn fetch(
+        &self,
+        pool: &AsyncComputeTaskPool,
+        name: &str,
+    ) -> Option<Task<Option<ProfileData>>> {
+        self.url.as_ref()?;
+
+        let sync = Self {
+            url: self.url.clone(),
+            token: self.token.clone(),
+            ..Default::default()
+        };
+        let name = name.to_string();
+
+        Some(pool.spawn(async move { sync.download(&name) }))
+    }
+
+    /// Downloads the server's copy of a profile. A profile the server has
+    /// never seen isn't an error, it just has nothing to offer yet.
+    fn download(&self, name: &str) -> Option<ProfileData> {
+        let response = match self.request("GET", name)?.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return None,
+            Err(e) => {
+                eprintln!("Failed to fetch profile '{}': {}", name, e);
+                return None;
+            }
+        };
+
+        let parsed = response
+            .into_string()
+            .map_err(|e| e.to_string())
+            .and_then(|text| ron::from_str(&text).map_err(|e| e.to_string()));
+
+        match parsed {
+            Ok(data) => Some(data),
+            Err(e) => {
+                eprintln!("Ignoring unreadable remote profile '{}': {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Uploads a profile in the background, so a slow server never stalls the
+    /// game.
+    pub fn push(&self, name: &str, data: &ProfileData) {
+        let request = match self.request("PUT", name) {
+            Some(request) => request,
+            None => return,
+        };
+
+        let text = match ron::ser::to_string_pretty(data, PrettyConfig::new()) {
+            Ok(text) => text,
+            Err(e) => return eprintln!("Failed to serialize profile '{}': {}", name, e),
+        };
+
+        let name = name.to_string();
+        let mut upload = self.upload.lock().unwrap();
+        let previous = upload.take();
+
+        *upload = Some(thread::spawn(move || {
+            if let Some(previous) = previous {
+                let _ = previous.join();
+            }
+
+            if let Err(e) = request.send_string(&text) {
+                eprintln!("Failed to upload profile '{}': {}", name, e);
+            }
+        }));
+    }
+
+    /// Waits for the last upload to finish, so quitting doesn't cut it off.
+    pub fn finish(&self) {
+        if let Some(upload) = self.upload.lock().unwrap().take() {
+            let _ = upload.join();
+        }
+    }
+}
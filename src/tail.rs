@@ -0,0 +1,113 @@
+//! The end of the snake. The last segment is drawn tapered, narrower across
+//! the snake than along it, and slides out of its old cell over the tick
+//! rather than jumping, so the body looks to be pulled in behind the head.
+
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+use crate::viewport::board_size;
+use crate::{
+    calculate_grid_position, position_translation, size_scaling, AppState, Position, SnakeBody,
+    TickRate, GRID_HEIGHT, GRID_WIDTH,
+};
+
+/// How wide the tail is across the snake, as a fraction of a segment.
+const TAIL_TAPER: f32 = 0.55;
+
+/// The tail's latest move, from one cell to the next.
+pub struct TailSlide {
+    tail: Entity,
+    from: Position,
+    to: Position,
+    timer: Timer,
+}
+
+impl TailSlide {
+    /// Only a step to a neighbouring cell slides. Anything further, like
+    /// crossing the edge of the board, just jumps.
+    fn is_step(&self) -> bool {
+        (self.from.x - self.to.x).abs() + (self.from.y - self.to.y).abs() == 1
+    }
+}
+
+/// Runs after [`position_translation`] and [`size_scaling`], which put the
+/// tail square on its cell every frame, and works from there.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_tail(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    tick_rate: Res<TickRate>,
+    body: Res<SnakeBody>,
+    mut slide: Local<Option<TailSlide>>,
+    mut query: Query<(&Position, &mut Transform)>,
+) {
+    let (tail, ahead) = match body.0.as_slice() {
+        [.., ahead, tail] => (*tail, *ahead),
+        _ => return,
+    };
+
+    let ahead = match query.get(ahead) {
+        Ok((position, _)) => *position,
+        Err(_) => return,
+    };
+
+    let (position, mut transform) = match query.get_mut(tail) {
+        Ok(tail) => tail,
+        Err(_) => return,
+    };
+
+    // A new tail, from growing or a new run, starts where it stands.
+    let slide = match slide.as_mut() {
+        Some(slide) if slide.tail == tail => slide,
+        _ => slide.insert(TailSlide {
+            tail,
+            from: *position,
+            to: *position,
+            timer: Timer::from_seconds(0.0, false),
+        }),
+    };
+
+    if slide.to != *position {
+        slide.from = slide.to;
+        slide.to = *position;
+        slide.timer = Timer::from_seconds(tick_rate.step() as f32, false);
+    }
+
+    if ahead.x != position.x {
+        transform.scale.y *= TAIL_TAPER;
+    } else {
+        transform.scale.x *= TAIL_TAPER;
+    }
+
+    if *app_state.current() == AppState::Playing {
+        slide.timer.tick(time.delta());
+    }
+
+    if settings.accessibility.reduced_motion || slide.timer.finished() || !slide.is_step() {
+        return;
+    }
+
+    let board = board_size();
+    let cell = |position: Position| {
+        Vec2::new(
+            calculate_grid_position(position.x as f32, board.x, GRID_WIDTH as f32),
+            calculate_grid_position(position.y as f32, board.y, GRID_HEIGHT as f32),
+        )
+    };
+
+    let at = cell(slide.from).lerp(cell(slide.to), slide.timer.percent());
+    transform.translation.x = at.x;
+    transform.translation.y = at.y;
+}
+
+pub struct TailPlugin;
+
+impl Plugin for TailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            draw_tail.after(position_translation).after(size_scaling),
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::layers::Layer;
+use crate::{Direction, Position, Size};
+
+const ICE_COLOR: Color = Color::rgba(0.6, 0.85, 1.0, 0.35);
+const MUD_COLOR: Color = Color::rgba(0.4, 0.28, 0.15, 0.6);
+const CONVEYOR_COLOR: Color = Color::rgba(0.5, 0.5, 0.5, 0.4);
+const CONVEYOR_ARROW_COLOR: Color = Color::rgba(0.8, 0.8, 0.8, 0.6);
+const WIND_COLOR: Color = Color::rgba(0.75, 0.9, 0.85, 0.15);
+const WIND_ARROW_COLOR: Color = Color::rgba(0.85, 1.0, 0.95, 0.5);
+/// Wind pushes the head along on one tick in this many.
+const WIND_GUST_TICKS: u32 = 3;
+
+/// A floor effect applied to the snake head by `snake_movement`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TileEffect {
+    /// The snake can't turn on the tick it leaves an ice cell.
+    Ice,
+    /// Movement runs at half speed while the head is in mud.
+    Mud,
+    /// Pushes the head one extra cell in the given direction.
+    Conveyor(Direction),
+    /// Like a conveyor, but only on a gust, every few ticks.
+    Wind(Direction),
+}
+
+impl TileEffect {
+    fn color(&self) -> Color {
+        match self {
+            Self::Ice => ICE_COLOR,
+            Self::Mud => MUD_COLOR,
+            Self::Conveyor(_) => CONVEYOR_COLOR,
+            Self::Wind(_) => WIND_COLOR,
+        }
+    }
+}
+
+/// Counts movement ticks round between gusts of wind, from the start of a
+/// run.
+#[derive(Default)]
+pub struct WindClock(u32);
+
+impl WindClock {
+    /// Moves the clock on a tick, returning whether the wind gusts on it.
+    pub fn tick(&mut self) -> bool {
+        self.0 = (self.0 + 1) % WIND_GUST_TICKS;
+        self.0 == 0
+    }
+}
+
+pub fn spawn_terrain(commands: &mut Commands, position: Position, effect: TileEffect) -> Entity {
+    let mut tile = commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: effect.color(),
+            ..default()
+        },
+        ..default()
+    });
+
+    tile.insert(effect)
+        .insert(position)
+        .insert(Size::square(1.0))
+        .insert(Layer::Board.at(0.5));
+
+    if let TileEffect::Conveyor(direction) = effect {
+        // A nub on the side of the cell the belt pushes towards. The parent is
+        // scaled to a single cell, so these offsets are fractions of a cell.
+        let offset = match direction {
+            Direction::Left => Vec3::new(-0.3, 0.0, 0.0),
+            Direction::Right => Vec3::new(0.3, 0.0, 0.0),
+            Direction::Up => Vec3::new(0.0, 0.3, 0.0),
+            Direction::Down => Vec3::new(0.0, -0.3, 0.0),
+        };
+
+        tile.with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: CONVEYOR_ARROW_COLOR,
+                        custom_size: Some(Vec2::splat(0.3)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(offset),
+                    ..default()
+                })
+                .insert(Layer::Board.at(0.6));
+        });
+    }
+
+    if let TileEffect::Wind(direction) = effect {
+        // An arrow pointing downwind: a shaft with two strokes for a head,
+        // drawn pointing right and turned to face the wind.
+        let angle = match direction {
+            Direction::Right => 0.0,
+            Direction::Up => std::f32::consts::FRAC_PI_2,
+            Direction::Left => std::f32::consts::PI,
+            Direction::Down => -std::f32::consts::FRAC_PI_2,
+        };
+        let turn = Quat::from_rotation_z(angle);
+        let strokes = [
+            (Vec2::new(0.5, 0.08), Vec2::ZERO, 0.0),
+            (Vec2::new(0.25, 0.08), Vec2::new(0.15, 0.08), -0.8),
+            (Vec2::new(0.25, 0.08), Vec2::new(0.15, -0.08), 0.8),
+        ];
+
+        tile.with_children(|parent| {
+            for (size, offset, tilt) in strokes {
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: WIND_ARROW_COLOR,
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: turn * offset.extend(0.0),
+                            rotation: turn * Quat::from_rotation_z(tilt),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(Layer::Board.at(0.6));
+            }
+        });
+    }
+
+    tile.id()
+}
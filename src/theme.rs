@@ -0,0 +1,249 @@
+//! Holiday looks picked from the system date, or forced from the main menu.
+//! Themes recolour the food and the background, and can swap in sprites from
+//! `assets/themes/<theme>/` when they've been dropped in.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
+use rand::prelude::random;
+use serde::{Deserialize, Serialize};
+
+use crate::daily::today;
+use crate::settings::{CosmeticSettings, Settings};
+use crate::{Food, BG_COLOR, FOOD_COLOR, WIN_HEIGHT, WIN_WIDTH};
+
+const SNOWFLAKE_COUNT: usize = 60;
+const SNOWFLAKE_SIZE: f32 = 3.0;
+const SNOWFALL_SPEED: f32 = 40.0;
+const SNOWFLAKE_COLOR: Color = Color::rgba(0.9, 0.95, 1.0, 0.6);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Classic,
+    Halloween,
+    Winter,
+}
+
+impl Theme {
+    /// The theme for a month of the year, from 1 to 12.
+    fn for_month(month: u32) -> Self {
+        match month {
+            10 => Self::Halloween,
+            12 => Self::Winter,
+            _ => Self::Classic,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Halloween => "Halloween",
+            Self::Winter => "Winter",
+        }
+    }
+
+    /// Stops along the way from a fresh run to a won one.
+    pub fn palette(&self) -> [Color; 4] {
+        match self {
+            Self::Classic => [
+                BG_COLOR,
+                Color::rgb(0.05, 0.08, 0.16),
+                Color::rgb(0.12, 0.06, 0.15),
+                Color::rgb(0.17, 0.09, 0.05),
+            ],
+            Self::Halloween => [
+                Color::rgb(0.06, 0.03, 0.08),
+                Color::rgb(0.12, 0.04, 0.14),
+                Color::rgb(0.18, 0.07, 0.05),
+                Color::rgb(0.25, 0.11, 0.02),
+            ],
+            Self::Winter => [
+                Color::rgb(0.04, 0.06, 0.12),
+                Color::rgb(0.06, 0.10, 0.18),
+                Color::rgb(0.10, 0.15, 0.24),
+                Color::rgb(0.16, 0.22, 0.30),
+            ],
+        }
+    }
+
+    fn food_color(&self) -> Color {
+        match self {
+            Self::Classic => FOOD_COLOR,
+            Self::Halloween => Color::rgb(0.95, 0.5, 0.05),
+            Self::Winter => Color::rgb(0.85, 0.1, 0.15),
+        }
+    }
+
+    /// A sprite to draw food with instead of a plain square, if one ships.
+    fn food_texture(&self) -> Option<String> {
+        let path = match self {
+            Self::Classic => return None,
+            Self::Halloween => "themes/halloween/food.png",
+            Self::Winter => "themes/winter/food.png",
+        };
+
+        Path::new("assets")
+            .join(path)
+            .exists()
+            .then(|| path.to_string())
+    }
+
+    fn snows(&self) -> bool {
+        *self == Self::Winter
+    }
+}
+
+/// The theme setting: follow the calendar, or always use one theme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    Auto,
+    Fixed(Theme),
+}
+
+impl ThemeChoice {
+    const ALL: [Self; 4] = [
+        Self::Auto,
+        Self::Fixed(Theme::Classic),
+        Self::Fixed(Theme::Halloween),
+        Self::Fixed(Theme::Winter),
+    ];
+
+    pub fn resolve(&self) -> Theme {
+        match self {
+            Self::Auto => Theme::for_month(current_month()),
+            Self::Fixed(theme) => *theme,
+        }
+    }
+
+    /// The choice `offset` places along from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        let count = Self::ALL.len() as isize;
+        let current = Self::ALL.iter().position(|c| c == self).unwrap() as isize;
+        Self::ALL[(current + offset).rem_euclid(count) as usize]
+    }
+}
+
+/// Today's month in UTC, from 1 to 12. Converts the days since the epoch to a
+/// civil date, following Howard Hinnant's `civil_from_days`.
+fn current_month() -> u32 {
+    let days = today() as i64 + 719_468;
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+
+    (if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    }) as u32
+}
+
+pub struct ActiveTheme(pub Theme);
+
+#[derive(Component)]
+struct Snowflake;
+
+fn update_theme(cosmetics: Res<CosmeticSettings>, mut active: ResMut<ActiveTheme>) {
+    if !cosmetics.is_changed() {
+        return;
+    }
+
+    let theme = cosmetics.theme.resolve();
+    if active.0 != theme {
+        active.0 = theme;
+    }
+}
+
+/// Dresses food as it's spawned, and all of it again when the theme changes.
+fn style_food(
+    asset_server: Res<AssetServer>,
+    theme: Res<ActiveTheme>,
+    mut food: Query<(&mut Sprite, &mut Handle<Image>, ChangeTrackers<Food>)>,
+) {
+    let texture = theme.0.food_texture();
+
+    for (mut sprite, mut image, tracker) in food.iter_mut() {
+        if !tracker.is_added() && !theme.is_changed() {
+            continue;
+        }
+
+        match &texture {
+            Some(path) => {
+                sprite.color = Color::WHITE;
+                *image = asset_server.load(path.as_str());
+            }
+            None => {
+                sprite.color = theme.0.food_color();
+                *image = DEFAULT_IMAGE_HANDLE.typed();
+            }
+        }
+    }
+}
+
+fn snowfall(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<ActiveTheme>,
+    settings: Res<Settings>,
+    mut flakes: Query<(Entity, &mut Transform), With<Snowflake>>,
+) {
+    if !theme.0.snows() || settings.accessibility.reduced_motion {
+        for (ent, _) in flakes.iter() {
+            commands.entity(ent).despawn();
+        }
+        return;
+    }
+
+    if flakes.is_empty() {
+        for _ in 0..SNOWFLAKE_COUNT {
+            let x = (random::<f32>() - 0.5) * WIN_WIDTH;
+            let y = (random::<f32>() - 0.5) * WIN_HEIGHT;
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: SNOWFLAKE_COLOR,
+                        custom_size: Some(Vec2::splat(SNOWFLAKE_SIZE)),
+                        ..default()
+                    },
+                    // Behind the board, which starts at zero.
+                    transform: Transform::from_xyz(x, y, -1.0),
+                    ..default()
+                })
+                .insert(Snowflake);
+        }
+        return;
+    }
+
+    let t = time.seconds_since_startup() as f32;
+    let fall = SNOWFALL_SPEED * time.delta_seconds();
+
+    for (i, (_, mut transform)) in flakes.iter_mut().enumerate() {
+        let translation = &mut transform.translation;
+        translation.y -= fall;
+        translation.x += (t + i as f32).sin() * fall * 0.5;
+
+        // Back to the top once it drifts off the bottom.
+        if translation.y < -WIN_HEIGHT / 2.0 {
+            translation.y += WIN_HEIGHT;
+            translation.x = (random::<f32>() - 0.5) * WIN_WIDTH;
+        }
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActiveTheme(Theme::Classic))
+            .add_system(update_theme)
+            // Food spawned by commands during the update is visible by now,
+            // so it never shows up unstyled for a frame.
+            .add_system_to_stage(CoreStage::PostUpdate, style_food)
+            .add_system(snowfall.after(update_theme));
+    }
+}
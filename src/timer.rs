@@ -0,0 +1,93 @@
+//! Countdowns that live on entities. Anything timed, from a power-up running
+//! out to a toast fading away, carries a [`TickTimer`], and [`tick_timers`]
+//! advances every one of them at the start of the frame, so the systems that
+//! use them only ever read them.
+
+use bevy::prelude::*;
+
+use crate::AppState;
+
+/// Which time a [`TickTimer`] counts.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    /// Counts all the time, for things like the UI.
+    Real,
+    /// Only counts while a run is being played, so pausing holds it.
+    Playing,
+}
+
+/// What happens to the entity once its [`TickTimer`] runs out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OnFinish {
+    /// Left for whatever uses the timer to deal with.
+    Keep,
+    Despawn,
+}
+
+#[derive(Component)]
+pub struct TickTimer {
+    timer: Timer,
+    clock: Clock,
+    on_finish: OnFinish,
+}
+
+impl TickTimer {
+    pub fn new(seconds: f32, clock: Clock) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, false),
+            clock,
+            on_finish: OnFinish::Keep,
+        }
+    }
+
+    /// A timer whose entity goes away when it runs out.
+    pub fn despawning(seconds: f32, clock: Clock) -> Self {
+        Self {
+            on_finish: OnFinish::Despawn,
+            ..Self::new(seconds, clock)
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.timer.finished()
+    }
+
+    pub fn percent(&self) -> f32 {
+        self.timer.percent()
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.timer.elapsed_secs()
+    }
+
+    pub fn remaining_secs(&self) -> f32 {
+        self.timer.duration().as_secs_f32() - self.timer.elapsed_secs()
+    }
+}
+
+pub fn tick_timers(
+    mut commands: Commands,
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    mut query: Query<(Entity, &mut TickTimer)>,
+) {
+    let playing = *app_state.current() == AppState::Playing;
+
+    for (ent, mut timer) in query.iter_mut() {
+        if timer.clock == Clock::Playing && !playing {
+            continue;
+        }
+
+        if timer.timer.tick(time.delta()).just_finished() && timer.on_finish == OnFinish::Despawn {
+            commands.entity(ent).despawn_recursive();
+        }
+    }
+}
+
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, tick_timers);
+    }
+}
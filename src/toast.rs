@@ -0,0 +1,161 @@
+//! Short notices that slide in near the top of the screen, hang around for a
+//! moment and fade away. Anything can raise one with a [`ToastEvent`]; only a
+//! few show at once, and the rest wait their turn in [`ToastQueue`].
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::timer::{Clock, TickTimer};
+
+const TOAST_SECONDS: f32 = 3.0;
+const TOAST_FADE_SECONDS: f32 = 0.75;
+const TOAST_SLIDE_SECONDS: f32 = 0.25;
+/// How far off to the right a toast starts before sliding into place.
+const TOAST_SLIDE_DISTANCE: f32 = 120.0;
+/// How quickly toasts close the gap left by one going away, per second.
+const TOAST_REFLOW_RATE: f32 = 12.0;
+const TOAST_FONT_SIZE: f32 = 28.0;
+const TOAST_Y: f32 = 220.0;
+const TOAST_SPACING: f32 = 36.0;
+const MAX_TOASTS_SHOWN: usize = 3;
+
+/// What a toast is about, which decides its color.
+#[derive(Clone, Copy)]
+pub enum ToastKind {
+    Info,
+    Achievement,
+    PowerUp,
+    /// Something that happened to the run, like a random event.
+    Event,
+    Error,
+}
+
+impl ToastKind {
+    fn color(&self) -> Color {
+        match self {
+            Self::Info => Color::rgb(0.9, 0.8, 0.2),
+            Self::Achievement => Color::rgb(1.0, 0.6, 0.9),
+            Self::PowerUp => Color::rgb(0.4, 0.9, 1.0),
+            Self::Event => Color::rgb(1.0, 0.55, 0.2),
+            Self::Error => Color::rgb(1.0, 0.3, 0.3),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ToastEvent {
+    pub kind: ToastKind,
+    pub message: String,
+}
+
+impl ToastEvent {
+    pub fn new(kind: ToastKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Info, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Error, message)
+    }
+}
+
+/// Toasts raised while the screen was already full, oldest first.
+#[derive(Default)]
+pub struct ToastQueue(VecDeque<ToastEvent>);
+
+#[derive(Component)]
+struct Toast;
+
+fn spawn_toasts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut queue: ResMut<ToastQueue>,
+    mut reader: EventReader<ToastEvent>,
+    shown: Query<(), With<Toast>>,
+) {
+    queue.0.extend(reader.iter().cloned());
+
+    // New ones go below any still showing rather than on top of them.
+    for slot in shown.iter().count()..MAX_TOASTS_SHOWN {
+        let toast = match queue.0.pop_front() {
+            Some(toast) => toast,
+            None => break,
+        };
+        let y = TOAST_Y - slot as f32 * TOAST_SPACING;
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    toast.message,
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: TOAST_FONT_SIZE,
+                        color: toast.kind.color(),
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                ),
+                transform: Transform::from_xyz(TOAST_SLIDE_DISTANCE, y, 0.0),
+                ..default()
+            })
+            .insert(Toast)
+            .insert(TickTimer::despawning(TOAST_SECONDS, Clock::Real))
+            .insert(Layer::Overlay.at(4.0));
+    }
+}
+
+/// Slides each toast in, moves it up as the ones above it go, and fades it
+/// out before its timer dismisses it.
+fn animate_toasts(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut query: Query<(&TickTimer, &mut Text, &mut Transform), With<Toast>>,
+) {
+    let mut toasts: Vec<_> = query.iter_mut().collect();
+    // Oldest at the top.
+    toasts.sort_by(|a, b| b.0.elapsed_secs().total_cmp(&a.0.elapsed_secs()));
+
+    for (slot, (timer, mut text, mut transform)) in toasts.into_iter().enumerate() {
+        let y = TOAST_Y - slot as f32 * TOAST_SPACING;
+
+        if settings.accessibility.reduced_motion {
+            transform.translation.x = 0.0;
+            transform.translation.y = y;
+            continue;
+        }
+
+        let slid = (timer.elapsed_secs() / TOAST_SLIDE_SECONDS).min(1.0);
+        transform.translation.x = TOAST_SLIDE_DISTANCE * (1.0 - slid).powi(3);
+
+        let reflow = (TOAST_REFLOW_RATE * time.delta_seconds()).min(1.0);
+        transform.translation.y += (y - transform.translation.y) * reflow;
+
+        let alpha = (timer.remaining_secs() / TOAST_FADE_SECONDS).min(1.0);
+
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ToastEvent>()
+            .init_resource::<ToastQueue>()
+            .add_system(spawn_toasts)
+            .add_system(animate_toasts.after(spawn_toasts));
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::{Position, Size, SnakeBody, SnakePart};
+
+const TRAIL_COLOR: Color = Color::rgba(0.0, 0.7, 0.0, 0.35);
+const TRAIL_TICKS: u8 = 4;
+
+/// A fading marker left in a cell the head just moved out of.
+#[derive(Component)]
+pub struct TrailMark {
+    ticks_left: u8,
+}
+
+/// Runs once per movement tick: fades out the existing marks, then drops a
+/// new one where the head was before this step.
+pub fn leave_trail(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    body: Res<SnakeBody>,
+    positions: Query<&Position, With<SnakePart>>,
+    mut marks: Query<(Entity, &mut TrailMark, &mut Sprite)>,
+) {
+    for (ent, mut mark, mut sprite) in marks.iter_mut() {
+        mark.ticks_left -= 1;
+
+        if mark.ticks_left == 0 {
+            commands.entity(ent).despawn();
+        } else {
+            let fade = mark.ticks_left as f32 / TRAIL_TICKS as f32;
+            sprite.color.set_a(TRAIL_COLOR.a() * fade);
+        }
+    }
+
+    if settings.accessibility.reduced_motion {
+        return;
+    }
+
+    // After moving, the segment behind the head sits in the head's old cell.
+    let neck = match body.0.get(1).and_then(|ent| positions.get(*ent).ok()) {
+        Some(position) => *position,
+        None => return,
+    };
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: TRAIL_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(TrailMark {
+            ticks_left: TRAIL_TICKS,
+        })
+        .insert(neck)
+        .insert(Size::square(1.0))
+        .insert(Layer::Board.at(0.5));
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::{game_over, toggle_pause, AppState, WIN_HEIGHT, WIN_WIDTH};
+
+const FADE_SECONDS: f32 = 0.25;
+
+/// Asks for a fade to black, a switch to the given state while the screen is
+/// dark, then a fade back in. Moving between screens should go through this
+/// rather than setting the state directly.
+pub struct TransitionEvent(pub AppState);
+
+#[derive(Default)]
+pub struct ScreenFade {
+    alpha: f32,
+    next: Option<AppState>,
+}
+
+impl ScreenFade {
+    pub fn is_active(&self) -> bool {
+        self.next.is_some() || self.alpha > 0.0
+    }
+}
+
+#[derive(Component)]
+struct FadeQuad;
+
+fn setup_fade_quad(mut commands: Commands) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                custom_size: Some(Vec2::new(WIN_WIDTH, WIN_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(FadeQuad)
+        .insert(Layer::Overlay.at(8.0));
+}
+
+fn start_transition(
+    mut reader: EventReader<TransitionEvent>,
+    settings: Res<Settings>,
+    mut fade: ResMut<ScreenFade>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    // Only the first transition asked for in a frame is taken, and the rest
+    // are dropped with it.
+    let mut events = reader.iter();
+    let next = match events.next() {
+        Some(TransitionEvent(next)) => next.clone(),
+        None => return,
+    };
+    events.for_each(drop);
+
+    if settings.accessibility.reduced_motion {
+        // Something else may have queued a state this frame already, such as
+        // a run ending, and that goes ahead instead.
+        if *app_state.current() != next {
+            let _ = app_state.set(next);
+        }
+    } else if fade.next.is_none() {
+        fade.next = Some(next);
+    }
+}
+
+/// Darkens the screen until it's fully black, switches state, then clears
+/// it again, so the new screen never pops in half built.
+fn run_fade(
+    time: Res<Time>,
+    mut fade: ResMut<ScreenFade>,
+    mut app_state: ResMut<State<AppState>>,
+    mut quads: Query<&mut Sprite, With<FadeQuad>>,
+) {
+    if !fade.is_active() {
+        return;
+    }
+
+    let step = time.delta_seconds() / FADE_SECONDS;
+
+    match fade.next.take() {
+        Some(next) if fade.alpha < 1.0 => {
+            fade.alpha = (fade.alpha + step).min(1.0);
+            fade.next = Some(next);
+        }
+        Some(next) => {
+            // With another state already queued this frame, the screen stays
+            // dark and the switch is tried again on the next.
+            if *app_state.current() != next && app_state.set(next.clone()).is_err() {
+                fade.next = Some(next);
+            }
+        }
+        None => fade.alpha = (fade.alpha - step).max(0.0),
+    }
+
+    for mut sprite in quads.iter_mut() {
+        sprite.color.set_a(fade.alpha);
+    }
+}
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenFade>()
+            .add_event::<TransitionEvent>()
+            .add_startup_system(setup_fade_quad)
+            .add_system(start_transition.after(game_over).after(toggle_pause))
+            .add_system(run_fade.after(start_transition));
+    }
+}
@@ -0,0 +1,63 @@
+//! Scales text and HUD bars by the `ui_scale` video setting, for big screens
+//! and small ones alike. Each element grows about the nearest edge or the
+//! middle of the screen, whichever it sits closest to, so anything pinned to
+//! an edge stays there and its margin grows with it.
+
+use bevy::prelude::*;
+
+use crate::hud::HudBar;
+use crate::settings::Settings;
+use crate::{WIN_HEIGHT, WIN_WIDTH};
+
+pub const UI_SCALE_MIN: f32 = 0.75;
+pub const UI_SCALE_MAX: f32 = 2.0;
+pub const UI_SCALE_STEP: f32 = 0.25;
+
+/// The closest of the two edges and the middle of an axis `extent` long.
+fn anchor(position: f32, extent: f32) -> f32 {
+    let half = extent / 2.0;
+
+    [-half, 0.0, half]
+        .into_iter()
+        .min_by(|a, b| (position - a).abs().total_cmp(&(position - b).abs()))
+        .unwrap()
+}
+
+/// Every piece of text and HUD bar that isn't attached to something else.
+type ScaledUi<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static mut GlobalTransform,
+        Option<&'static HudBar>,
+    ),
+    (Or<(With<Text>, With<HudBar>)>, Without<Parent>),
+>;
+
+/// Works from the local transform each frame, after everything else has
+/// placed itself, so the scale never compounds.
+fn apply_ui_scale(settings: Res<Settings>, mut query: ScaledUi) {
+    let scale = settings.video.ui_scale.clamp(UI_SCALE_MIN, UI_SCALE_MAX);
+
+    for (transform, mut global_transform, bar) in query.iter_mut() {
+        // Both halves of a bar share its resting position, so they move
+        // together as the fill shrinks.
+        let home = bar.map_or(transform.translation.truncate(), |bar| {
+            Vec2::new(bar.x, bar.y)
+        });
+        let origin = Vec2::new(anchor(home.x, WIN_WIDTH), anchor(home.y, WIN_HEIGHT));
+        let scaled = origin + (transform.translation.truncate() - origin) * scale;
+
+        global_transform.translation = scaled.extend(global_transform.translation.z);
+        global_transform.scale = transform.scale * scale;
+    }
+}
+
+pub struct UiScalePlugin;
+
+impl Plugin for UiScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::Last, apply_ui_scale);
+    }
+}
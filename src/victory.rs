@@ -0,0 +1,186 @@
+//! Celebrates a won run before the end screen: confetti bursts out over the
+//! board, a fanfare plays, and the snake runs a quick lap of honour while
+//! [`WinSequence`] steers it.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::timer::{Clock, TickTimer};
+use crate::{show_end_game_text, AppState, Direction, GameStatus, Position, SnakeBody, SnakeHead};
+
+/// Cells along each side of the lap, which turns right at every corner.
+const LAP_SIDE: usize = 5;
+const LAP_STEP_SECONDS: f32 = 0.06;
+const CONFETTI_PIECES: usize = 80;
+const CONFETTI_SIZE: f32 = 7.0;
+const CONFETTI_SPEED: (f32, f32) = (150.0, 450.0);
+const CONFETTI_GRAVITY: f32 = 500.0;
+const CONFETTI_SECONDS: f32 = 2.0;
+const CONFETTI_COLORS: [Color; 5] = [
+    Color::rgb(1.0, 0.3, 0.3),
+    Color::rgb(1.0, 0.85, 0.2),
+    Color::rgb(0.3, 0.9, 0.4),
+    Color::rgb(0.3, 0.6, 1.0),
+    Color::rgb(0.9, 0.4, 1.0),
+];
+/// Not bundled, so the fanfare only plays once one has been dropped in.
+const FANFARE_SOUND: &str = "sounds/fanfare.ogg";
+
+/// Plays out a won run. Holds the head's direction until the lap is done,
+/// then puts up the end screen.
+pub struct WinSequence {
+    steps_left: usize,
+    direction: Direction,
+    timer: Timer,
+    started: bool,
+}
+
+impl WinSequence {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            steps_left: LAP_SIDE * 4,
+            direction,
+            timer: Timer::from_seconds(LAP_STEP_SECONDS, true),
+            started: false,
+        }
+    }
+}
+
+fn turn_right(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Right,
+        Direction::Right => Direction::Down,
+        Direction::Down => Direction::Left,
+        Direction::Left => Direction::Up,
+    }
+}
+
+#[derive(Component)]
+pub struct Confetti {
+    velocity: Vec2,
+}
+
+fn spawn_confetti(commands: &mut Commands) {
+    let mut rng = rand::thread_rng();
+
+    for i in 0..CONFETTI_PIECES {
+        // Fans upwards from the middle of the screen, then falls.
+        let angle = rng.gen_range(0.15..0.85) * std::f32::consts::PI;
+        let speed = rng.gen_range(CONFETTI_SPEED.0..CONFETTI_SPEED.1);
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: CONFETTI_COLORS[i % CONFETTI_COLORS.len()],
+                    custom_size: Some(Vec2::splat(CONFETTI_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_rotation(Quat::from_rotation_z(rng.gen_range(0.0..6.0))),
+                ..default()
+            })
+            .insert(Confetti {
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+            })
+            .insert(TickTimer::despawning(CONFETTI_SECONDS, Clock::Real))
+            .insert(Layer::Hud.at(1.5));
+    }
+}
+
+fn fall_confetti(
+    time: Res<Time>,
+    mut query: Query<(&mut Confetti, &TickTimer, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut confetti, life, mut transform, mut sprite) in query.iter_mut() {
+        confetti.velocity.y -= CONFETTI_GRAVITY * dt;
+        transform.translation += (confetti.velocity * dt).extend(0.0);
+        transform.rotate(Quat::from_rotation_z(
+            confetti.velocity.x.signum() * 4.0 * dt,
+        ));
+        sprite.color.set_a(1.0 - life.percent());
+    }
+}
+
+/// Starts the celebration off, then moves the snake round the lap one step
+/// at a time, with the body following the head as it would in a run.
+#[allow(clippy::too_many_arguments)]
+fn celebrate(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    body: Res<SnakeBody>,
+    sequence: Option<ResMut<WinSequence>>,
+    mut heads: Query<&mut SnakeHead>,
+    mut positions: Query<&mut Position>,
+) {
+    let mut sequence = match sequence {
+        Some(sequence) => sequence,
+        None => return,
+    };
+
+    if !sequence.started {
+        sequence.started = true;
+        spawn_confetti(&mut commands);
+
+        if Path::new("assets").join(FANFARE_SOUND).exists() {
+            audio.play_with_settings(
+                asset_server.load(FANFARE_SOUND),
+                PlaybackSettings::ONCE.with_volume(settings.audio.sound_volume),
+            );
+        }
+    }
+
+    for _ in 0..sequence.timer.tick(time.delta()).times_finished() {
+        if sequence.steps_left == 0 {
+            break;
+        }
+
+        if sequence.steps_left % LAP_SIDE == 0 && sequence.steps_left != LAP_SIDE * 4 {
+            sequence.direction = turn_right(sequence.direction);
+        }
+        sequence.steps_left -= 1;
+
+        let before: Vec<Position> = body
+            .0
+            .iter()
+            .filter_map(|segment| positions.get(*segment).ok().copied())
+            .collect();
+
+        let head = match body.0.first() {
+            Some(head) => *head,
+            None => break,
+        };
+
+        if let Ok(mut position) = positions.get_mut(head) {
+            *position = position.step(sequence.direction);
+        }
+        if let Ok(mut head) = heads.get_mut(head) {
+            head.direction = sequence.direction;
+            head.moved = sequence.direction;
+            head.turns.clear();
+        }
+
+        body.follow(&before, &mut positions);
+    }
+
+    if sequence.steps_left == 0 {
+        commands.remove_resource::<WinSequence>();
+        show_end_game_text(commands, &GameStatus::Won, asset_server);
+    }
+}
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(fall_confetti)
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(celebrate));
+    }
+}
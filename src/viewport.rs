@@ -0,0 +1,212 @@
+//! Fits the board to the window. The camera always shows the whole
+//! `WIN_WIDTH` by `WIN_HEIGHT` world, or one of those for each board when
+//! there's more than one, scaled evenly to whatever size the window is. The
+//! board is the biggest grid of square cells that fits in that. Anything left
+//! over, top and bottom or either side, is covered by bars. Cells can still
+//! land part way across a pixel, so there's an option to snap everything on
+//! the board to whole ones.
+
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+
+use crate::animation::grow_in;
+use crate::boards::BoardLayout;
+use crate::layers::Layer;
+use crate::settings::Settings;
+use crate::tail::draw_tail;
+use crate::{
+    position_translation, size_scaling, Position, Size, GRID_HEIGHT, GRID_WIDTH, WIN_HEIGHT,
+    WIN_WIDTH,
+};
+
+const BAR_COLOR: Color = Color::BLACK;
+/// How far the bars reach out past the boards, enough for any window.
+const BAR_REACH: f32 = 10_000.0;
+
+#[derive(Component)]
+struct Bar;
+
+/// The side of one cell, in world units.
+pub fn cell_size() -> f32 {
+    (WIN_WIDTH / GRID_WIDTH as f32).min(WIN_HEIGHT / GRID_HEIGHT as f32)
+}
+
+/// The width and height of the whole board, in world units.
+pub fn board_size() -> Vec2 {
+    Vec2::new(GRID_WIDTH as f32, GRID_HEIGHT as f32) * cell_size()
+}
+
+/// The bottom left and top right corners of what the camera shows in a
+/// window of `size`: all of the world, and more along whichever axis the
+/// window is longer in.
+fn visible_bounds(size: Vec2, layout: &BoardLayout) -> (Vec2, Vec2) {
+    let (min, max) = layout.world_bounds();
+    let (world, centre) = (max - min, (min + max) / 2.0);
+    let aspect = size.x / size.y;
+
+    let visible = if aspect > world.x / world.y {
+        Vec2::new(world.y * aspect, world.y)
+    } else {
+        Vec2::new(world.x, world.x / aspect)
+    };
+
+    (centre - visible / 2.0, centre + visible / 2.0)
+}
+
+/// Maps a cursor position in window coordinates to the world under it.
+pub fn cursor_to_world(cursor: Vec2, window: &Window, layout: &BoardLayout) -> Vec2 {
+    let size = Vec2::new(window.width(), window.height());
+    let (min, max) = visible_bounds(size, layout);
+    min + cursor / size * (max - min)
+}
+
+fn spawn_bar(commands: &mut Commands, min: Vec2, max: Vec2) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: BAR_COLOR,
+                custom_size: Some(max - min),
+                ..default()
+            },
+            transform: Transform::from_translation(((min + max) / 2.0).extend(0.0)),
+            ..default()
+        })
+        .insert(Bar)
+        .insert(Layer::Entities.at(10.0));
+}
+
+/// Covers everything but the boards, rebuilt whenever the layout changes.
+/// Each board's slot is filled in around it, with the outermost ones
+/// reaching on past the edges of the world.
+fn place_bars(mut commands: Commands, layout: Res<BoardLayout>, bars: Query<Entity, With<Bar>>) {
+    if !layout.is_changed() {
+        return;
+    }
+
+    for bar in bars.iter() {
+        commands.entity(bar).despawn();
+    }
+
+    let half = board_size() / 2.0;
+    let slot = Vec2::new(WIN_WIDTH, WIN_HEIGHT) / 2.0 + Vec2::new(0.0, BAR_REACH);
+    let last = layout.boards.max(1) - 1;
+
+    for id in layout.ids() {
+        let origin = id.origin();
+        let (board_min, board_max) = (origin - half, origin + half);
+        let (mut slot_min, mut slot_max) = (origin - slot, origin + slot);
+
+        if id.0 == 0 {
+            slot_min.x -= BAR_REACH;
+        }
+        if id.0 == last {
+            slot_max.x += BAR_REACH;
+        }
+
+        // Either side of the board, then the full width of the slot below
+        // and above it, so the corners are covered too.
+        let rects = [
+            (
+                Vec2::new(slot_min.x, board_min.y),
+                Vec2::new(board_min.x, board_max.y),
+            ),
+            (
+                Vec2::new(board_max.x, board_min.y),
+                Vec2::new(slot_max.x, board_max.y),
+            ),
+            (slot_min, Vec2::new(slot_max.x, board_min.y)),
+            (Vec2::new(slot_min.x, board_max.y), slot_max),
+        ];
+
+        for (min, max) in rects {
+            if min.x < max.x && min.y < max.y {
+                spawn_bar(&mut commands, min, max);
+            }
+        }
+    }
+}
+
+/// Keeps the camera showing the whole world whenever the window changes
+/// shape. Leaves the camera's scale alone, which is used for zooming in.
+fn fit_camera(
+    windows: Res<Windows>,
+    layout: Res<BoardLayout>,
+    mut cameras: Query<&mut OrthographicProjection>,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let (min, max) = visible_bounds(Vec2::new(window.width(), window.height()), &layout);
+
+    for mut projection in cameras.iter_mut() {
+        let current = (
+            Vec2::new(projection.left, projection.bottom),
+            Vec2::new(projection.right, projection.top),
+        );
+        if current == (min, max) {
+            continue;
+        }
+
+        projection.scaling_mode = ScalingMode::None;
+        projection.left = min.x;
+        projection.right = max.x;
+        projection.bottom = min.y;
+        projection.top = max.y;
+    }
+}
+
+/// Moves the edges of everything on the board onto whole pixels, with the
+/// `pixel_snap` setting on. A sprite whose edge falls part way across a pixel
+/// gets blended into its neighbour there, which shows as a faint seam between
+/// segments at some window sizes.
+pub fn snap_to_pixels(
+    settings: Res<Settings>,
+    windows: Res<Windows>,
+    layout: Res<BoardLayout>,
+    mut query: Query<&mut Transform, (With<Position>, With<Size>)>,
+) {
+    let window = match windows.get_primary() {
+        Some(window) if settings.video.pixel_snap => window,
+        _ => return,
+    };
+
+    let pixels = Vec2::new(
+        window.physical_width() as f32,
+        window.physical_height() as f32,
+    );
+    let (min, max) = visible_bounds(Vec2::new(window.width(), window.height()), &layout);
+    let per_unit = pixels / (max - min);
+    // Pixels are counted from the bottom left corner of the window.
+    let snap = |at: Vec2| ((at - min) * per_unit).round() / per_unit + min;
+
+    for mut transform in query.iter_mut() {
+        let half = transform.scale.truncate() / 2.0;
+        let centre = transform.translation.truncate();
+        let (low, high) = (snap(centre - half), snap(centre + half));
+
+        transform.translation.x = (low.x + high.x) / 2.0;
+        transform.translation.y = (low.y + high.y) / 2.0;
+        transform.scale.x = high.x - low.x;
+        transform.scale.y = high.y - low.y;
+    }
+}
+
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoardLayout>()
+            .add_system(place_bars)
+            .add_system(fit_camera)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                snap_to_pixels
+                    .after(position_translation)
+                    .after(size_scaling)
+                    .after(grow_in)
+                    .after(draw_tail),
+            );
+    }
+}
@@ -0,0 +1,62 @@
+//! Where the window sits and whether it has a border, from the video
+//! settings. Applied at startup and again whenever they change.
+
+use bevy::prelude::*;
+use bevy::winit::WinitWindows;
+
+use crate::settings::Settings;
+
+/// Moves the window to the middle of the chosen monitor, counting from 0 in
+/// the order the system lists them, and sets its border. The window is only
+/// moved when the monitor changes, so it can be dragged around freely.
+fn apply_window_settings(
+    settings: Res<Settings>,
+    winit_windows: NonSend<WinitWindows>,
+    mut windows: ResMut<Windows>,
+    mut placed_on: Local<Option<usize>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+
+    if window.decorations() == settings.video.borderless {
+        window.set_decorations(!settings.video.borderless);
+    }
+
+    let index = match settings.video.monitor {
+        Some(index) if *placed_on != Some(index) => index,
+        _ => return,
+    };
+
+    *placed_on = Some(index);
+
+    let monitor = winit_windows
+        .get_window(window.id())
+        .and_then(|winit_window| winit_window.available_monitors().nth(index));
+
+    let monitor = match monitor {
+        Some(monitor) => monitor,
+        None => return eprintln!("No monitor {} to open the window on", index),
+    };
+
+    let (position, size) = (monitor.position(), monitor.size());
+    let centre = IVec2::new(
+        position.x + (size.width as i32 - window.physical_width() as i32) / 2,
+        position.y + (size.height as i32 - window.physical_height() as i32) / 2,
+    );
+
+    window.set_position(centre);
+}
+
+pub struct WindowingPlugin;
+
+impl Plugin for WindowingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(apply_window_settings);
+    }
+}
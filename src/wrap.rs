@@ -0,0 +1,214 @@
+//! Crossing the edge of the board. Rather than the head jumping from one side
+//! of the screen to the other, it's drawn split across both edges for a tick,
+//! with a flash on each side where it went through.
+
+use bevy::prelude::*;
+
+use crate::layers::Layer;
+use crate::settings::{GameplaySettings, Settings};
+use crate::viewport::cell_size;
+use crate::{position_translation, size_scaling, Direction, Position, Size, SnakeHead, TickRate};
+
+const FLASH_COLOR: Color = Color::rgb(0.1, 0.8, 0.9);
+const FLASH_SECONDS: f32 = 0.35;
+/// How far the flash grows along the edge as it fades.
+const FLASH_SWELL: f32 = 0.6;
+
+/// Draws only the half of a sprite on one side of its cell.
+#[derive(Component)]
+struct EdgeClip {
+    side: Direction,
+    timer: Timer,
+}
+
+/// Forgives the head running into the snake's own body on the next tick,
+/// after it's come through an edge or a portal with the `wrap_grace` rule
+/// on. Taken off again by [`crate::snake_movement`] once it's had its tick.
+#[derive(Component)]
+pub struct Grace;
+
+/// The half of the head still leaving by the far edge, gone after a tick.
+#[derive(Component)]
+struct WrapGhost;
+
+/// A fading streak along the edge of the board, where the head went through.
+#[derive(Component)]
+struct WrapFlash {
+    side: Direction,
+    timer: Timer,
+}
+
+/// Half the width of a cell towards `side`, in world units.
+fn edge_offset(side: Direction) -> Vec2 {
+    let half = cell_size() / 2.0;
+
+    match side {
+        Direction::Left => Vec2::new(-half, 0.0),
+        Direction::Right => Vec2::new(half, 0.0),
+        Direction::Up => Vec2::new(0.0, half),
+        Direction::Down => Vec2::new(0.0, -half),
+    }
+}
+
+fn spawn_flash(commands: &mut Commands, position: Position, side: Direction) {
+    let size = match side {
+        Direction::Left | Direction::Right => Size {
+            width: 0.25,
+            height: 1.2,
+        },
+        Direction::Up | Direction::Down => Size {
+            width: 1.2,
+            height: 0.25,
+        },
+    };
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: FLASH_COLOR,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(WrapFlash {
+            side,
+            timer: Timer::from_seconds(FLASH_SECONDS, false),
+        })
+        .insert(position)
+        .insert(size)
+        .insert(Layer::Entities.at(8.0));
+}
+
+/// Spots the head stepping off one edge and onto the other. Runs before the
+/// portals, so a jump through one of those never counts.
+pub fn detect_wrap(
+    mut commands: Commands,
+    tick_rate: Res<TickRate>,
+    settings: Res<Settings>,
+    gameplay: Res<GameplaySettings>,
+    mut last: Local<Option<Position>>,
+    heads: Query<(Entity, &SnakeHead, &Position, &Sprite)>,
+) {
+    let (ent, head, position, sprite) = match heads.iter().next() {
+        Some(head) => head,
+        None => return,
+    };
+
+    let previous = last.replace(*position);
+    let wrapped = match previous {
+        Some(from) => {
+            from.step(head.moved) == *position
+                && ((from.x - position.x).abs() > 1 || (from.y - position.y).abs() > 1)
+        }
+        None => false,
+    };
+
+    if !wrapped {
+        return;
+    }
+
+    if gameplay.wrap_grace {
+        commands.entity(ent).insert(Grace);
+    }
+
+    let from = previous.unwrap();
+    let tick = tick_rate.step() as f32;
+
+    commands.entity(ent).insert(EdgeClip {
+        side: -head.moved,
+        timer: Timer::from_seconds(tick, false),
+    });
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: sprite.clone(),
+            ..default()
+        })
+        .insert(WrapGhost)
+        .insert(EdgeClip {
+            side: head.moved,
+            timer: Timer::from_seconds(tick, false),
+        })
+        .insert(from)
+        .insert(Size::square(0.8))
+        .insert(Layer::Entities.at(7.0));
+
+    if !settings.accessibility.reduced_motion {
+        spawn_flash(&mut commands, from, head.moved);
+        spawn_flash(&mut commands, *position, -head.moved);
+    }
+}
+
+/// Runs after the grid systems have placed and sized everything this frame,
+/// trimming clipped sprites down to their half of the cell.
+fn clip_to_edge(mut query: Query<(&EdgeClip, &mut Transform)>) {
+    for (clip, mut transform) in query.iter_mut() {
+        match clip.side {
+            Direction::Left | Direction::Right => transform.scale.x /= 2.0,
+            Direction::Up | Direction::Down => transform.scale.y /= 2.0,
+        }
+
+        // Pushed a quarter of a cell over, so the half left sits flush
+        // against the edge it's crossing.
+        transform.translation += (edge_offset(clip.side) / 2.0).extend(0.0);
+    }
+}
+
+fn expire_edge_clips(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut EdgeClip, Option<&WrapGhost>)>,
+) {
+    for (ent, mut clip, ghost) in query.iter_mut() {
+        if !clip.timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        match ghost {
+            Some(_) => commands.entity(ent).despawn(),
+            None => {
+                commands.entity(ent).remove::<EdgeClip>();
+            }
+        }
+    }
+}
+
+fn flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut WrapFlash, &mut Sprite, &mut Transform)>,
+) {
+    for (ent, mut flash, mut sprite, mut transform) in query.iter_mut() {
+        if flash.timer.tick(time.delta()).finished() {
+            commands.entity(ent).despawn();
+            continue;
+        }
+
+        let progress = flash.timer.percent();
+        let swell = 1.0 + FLASH_SWELL * progress;
+
+        match flash.side {
+            Direction::Left | Direction::Right => transform.scale.y *= swell,
+            Direction::Up | Direction::Down => transform.scale.x *= swell,
+        }
+
+        transform.translation += edge_offset(flash.side).extend(0.0);
+        sprite.color.set_a(1.0 - progress);
+    }
+}
+
+pub struct WrapPlugin;
+
+impl Plugin for WrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(expire_edge_clips)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                clip_to_edge.after(size_scaling).after(position_translation),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                flash.after(size_scaling).after(position_translation),
+            );
+    }
+}